@@ -0,0 +1,409 @@
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use tokio::time::Instant;
+use tracing::info;
+
+use crate::cli::CanaryOptions;
+use crate::context::FuburaContext;
+use crate::error::FuburaError;
+use crate::human_println;
+use crate::sfn;
+use crate::types::{
+    ArnBuilder, Execution, RoutingConfigurationListItem, StateMachine, StateMachineAlias,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+// Failed executions printed on rollback
+const MAX_REPORTED_FAILURES: usize = 3;
+
+// Executions of canary version observed so far.
+struct CanaryStats {
+    finished: usize,
+    failed: Vec<Execution>,
+}
+
+impl CanaryStats {
+    fn from_executions(executions: Vec<Execution>) -> Self {
+        let finished = executions.iter().filter(|e| !e.is_running()).count();
+        let failed = executions.into_iter().filter(|e| e.is_failed()).collect();
+
+        Self { finished, failed }
+    }
+
+    fn failure_rate(&self) -> f64 {
+        if self.finished == 0 {
+            return 0.0;
+        }
+
+        self.failed.len() as f64 * 100.0 / self.finished as f64
+    }
+}
+
+// Canary alias is moved by fubura during deployment, so it can't be managed in config.
+pub fn ensure_alias_not_managed(state: &StateMachine, options: &CanaryOptions) -> Result<()> {
+    let managed = state
+        .aliases
+        .iter()
+        .flatten()
+        .any(|alias| alias.name == options.alias);
+    if managed {
+        return Err(FuburaError::Validation(format!(
+            "alias '{}' of state machine({}) is managed in aliases, which conflicts with --canary",
+            options.alias, state.name
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+// Update state machine as a new version behind the alias, watch its executions,
+// and promote or roll back the alias. Returns arn of the published version.
+pub async fn deploy(
+    context: &FuburaContext,
+    arn_builder: &ArnBuilder,
+    state: &StateMachine,
+    options: &CanaryOptions,
+) -> Result<Option<String>> {
+    let client = context.sfn_client.as_ref();
+    let state_arn = arn_builder.state_machine(&state.name);
+    let alias_arn = arn_builder.state_machine_alias(&state.name, &options.alias);
+
+    let Some(mut alias) = sfn::describe_state_machine_alias(client, &alias_arn).await? else {
+        return Err(FuburaError::Validation(format!(
+            "alias '{}' of state machine({}) does not exist, create it before canary deployment",
+            options.alias, state.name
+        ))
+        .into());
+    };
+    let stable_version_arn = match alias.routing_configuration.as_slice() {
+        [item] => item.state_machine_version_arn.clone(),
+        _ => {
+            return Err(FuburaError::Validation(format!(
+                "alias '{}' of state machine({}) routes to more than one version, finish the previous canary first",
+                options.alias, state.name
+            ))
+            .into());
+        }
+    };
+
+    // Canary needs a version to route to, even if publish is off in config
+    let mut state = state.clone();
+    state.publish = true;
+    info!("Updating state machine: {}", state.name);
+    let Some(canary_version_arn) = sfn::update_state_machine(client, &state_arn, &state).await?
+    else {
+        bail!("no version of state machine({}) is published", state.name);
+    };
+    if canary_version_arn == stable_version_arn {
+        human_println!(
+            context,
+            "Canary of {} is skipped, since no new version is published",
+            alias_arn
+        );
+        return Ok(Some(canary_version_arn));
+    }
+
+    human_println!(
+        context,
+        "Canary: routing {}% of {} to {}",
+        options.weight,
+        alias_arn,
+        canary_version_arn
+    );
+    alias.routing_configuration = vec![
+        RoutingConfigurationListItem {
+            state_machine_version_arn: stable_version_arn.clone(),
+            weight: 100 - options.weight,
+        },
+        RoutingConfigurationListItem {
+            state_machine_version_arn: canary_version_arn.clone(),
+            weight: options.weight,
+        },
+    ];
+    sfn::update_state_machine_alias(client, &alias_arn, &alias).await?;
+
+    let stats = match watch(context, &canary_version_arn, options).await {
+        Ok(stats) => stats,
+        Err(e) => {
+            // Don't leave the alias half routed to unverified version
+            route_all(context, &alias_arn, &mut alias, &stable_version_arn).await?;
+            return Err(e);
+        }
+    };
+
+    if stats.failure_rate() > options.max_failure_rate {
+        route_all(context, &alias_arn, &mut alias, &stable_version_arn).await?;
+        report_failures(context, &stats).await?;
+
+        return Err(FuburaError::CanaryFailed(format!(
+            "{} of {} finished executions of {} failed, and {} has been rolled back to {}. state machine itself keeps the new definition, revert config and apply it again.",
+            stats.failed.len(),
+            stats.finished,
+            canary_version_arn,
+            alias_arn,
+            stable_version_arn
+        ))
+        .into());
+    }
+
+    if stats.finished == 0 {
+        human_println!(
+            context,
+            "Canary: no execution of {} finished in bake time",
+            canary_version_arn
+        );
+    }
+    route_all(context, &alias_arn, &mut alias, &canary_version_arn).await?;
+    human_println!(
+        context,
+        "Canary: promoted {} to {} ({} finished, {} failed)",
+        alias_arn,
+        canary_version_arn,
+        stats.finished,
+        stats.failed.len()
+    );
+
+    Ok(Some(canary_version_arn))
+}
+
+// Poll executions of the version until bake time passes, or failure rate exceeds the threshold.
+async fn watch(
+    context: &FuburaContext,
+    version_arn: &str,
+    options: &CanaryOptions,
+) -> Result<CanaryStats> {
+    let deadline = Instant::now() + Duration::from_secs(options.bake_time);
+
+    loop {
+        let executions = sfn::list_executions(context.sfn_client.as_ref(), version_arn).await?;
+        let stats = CanaryStats::from_executions(executions);
+        if stats.failure_rate() > options.max_failure_rate {
+            return Ok(stats);
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(stats);
+        }
+
+        info!(
+            "Watching executions of {}: {} finished, {} failed, {}s left",
+            version_arn,
+            stats.finished,
+            stats.failed.len(),
+            (deadline - now).as_secs()
+        );
+        tokio::time::sleep(POLL_INTERVAL.min(deadline - now)).await;
+    }
+}
+
+async fn route_all(
+    context: &FuburaContext,
+    alias_arn: &str,
+    alias: &mut StateMachineAlias,
+    version_arn: &str,
+) -> Result<()> {
+    alias.routing_configuration = vec![RoutingConfigurationListItem {
+        state_machine_version_arn: version_arn.to_string(),
+        weight: 100,
+    }];
+
+    info!("Routing all of {} to {}", alias_arn, version_arn);
+    sfn::update_state_machine_alias(context.sfn_client.as_ref(), alias_arn, alias).await
+}
+
+async fn report_failures(context: &FuburaContext, stats: &CanaryStats) -> Result<()> {
+    human_println!(context, "Canary: failed executions:");
+    for execution in stats.failed.iter().take(MAX_REPORTED_FAILURES) {
+        let execution =
+            sfn::describe_execution(context.sfn_client.as_ref(), &execution.execution_arn).await?;
+        human_println!(
+            context,
+            "    {} {}: {} {}",
+            execution.execution_arn,
+            execution.status,
+            execution.error.as_deref().unwrap_or("-"),
+            execution.cause.as_deref().unwrap_or("")
+        );
+    }
+    if stats.failed.len() > MAX_REPORTED_FAILURES {
+        human_println!(
+            context,
+            "    and {} more",
+            stats.failed.len() - MAX_REPORTED_FAILURES
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+
+    use crate::error::ErrorCategory;
+    use crate::memory::{
+        DEFAULT_ACCOUNT, MemoryBackend, MemorySnapshot, MemoryStateMachineVersion,
+    };
+
+    const STATE_ARN: &str = "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld";
+
+    fn canary_options() -> CanaryOptions {
+        CanaryOptions {
+            enabled: true,
+            alias: "live".to_string(),
+            weight: 10,
+            bake_time: 0,
+            max_failure_rate: 0.0,
+        }
+    }
+
+    fn execution(name: &str, version: u32, status: &str) -> Execution {
+        Execution {
+            execution_arn: format!(
+                "arn:aws:states:us-west-2:123456789012:execution:HelloWorld:{}",
+                name
+            ),
+            state_machine_arn: STATE_ARN.to_string(),
+            state_machine_version_arn: Some(format!("{}:{}", STATE_ARN, version)),
+            status: status.to_string(),
+            start_date: "2024-01-01T00:00:00Z".to_string(),
+            error: (status == "FAILED").then(|| "States.TaskFailed".to_string()),
+            cause: None,
+        }
+    }
+
+    // HelloWorld with version 1, and live alias routing all to it
+    fn canary_context(executions: Vec<Execution>) -> (FuburaContext, Arc<MemoryBackend>) {
+        let mut state = StateMachine::test_default();
+        state.aliases = Some(vec![StateMachineAlias {
+            name: "live".to_string(),
+            description: None,
+            routing_configuration: vec![RoutingConfigurationListItem {
+                state_machine_version_arn: format!("{}:1", STATE_ARN),
+                weight: 100,
+            }],
+        }]);
+        let snapshot = MemorySnapshot {
+            account: Some(DEFAULT_ACCOUNT.to_string()),
+            state_machines: vec![state],
+            schedules: vec![],
+            schedule_groups: vec![],
+            state_machine_versions: vec![MemoryStateMachineVersion {
+                version: 1,
+                description: None,
+                creation_date: "2024-01-01T00:00:00Z".to_string(),
+                state: StateMachine::test_default(),
+            }],
+            executions,
+            rules: vec![],
+            log_groups: vec![],
+        };
+        let backend = Arc::new(MemoryBackend::from_snapshot(snapshot, "us-west-2"));
+
+        (FuburaContext::from_memory_backend(backend.clone()), backend)
+    }
+
+    fn updated_state() -> StateMachine {
+        let mut state = StateMachine::test_default();
+        state.definition = serde_json::json!({ "StartAt": "SecondState" });
+        state
+    }
+
+    async fn live_routing(context: &FuburaContext) -> Vec<RoutingConfigurationListItem> {
+        sfn::describe_state_machine_alias(
+            context.sfn_client.as_ref(),
+            &format!("{}:live", STATE_ARN),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .routing_configuration
+    }
+
+    #[tokio::test]
+    async fn test_deploy_promotes_canary() {
+        let (context, _) = canary_context(vec![
+            execution("ok", 2, "SUCCEEDED"),
+            execution("running", 2, "RUNNING"),
+            execution("old", 1, "FAILED"),
+        ]);
+        let arn_builder = crate::sts::build_arn_builder(&context).await.unwrap();
+
+        let actual = deploy(&context, &arn_builder, &updated_state(), &canary_options())
+            .await
+            .unwrap();
+
+        assert_eq!(Some(format!("{}:2", STATE_ARN)), actual);
+        similar_asserts::assert_eq!(
+            vec![RoutingConfigurationListItem {
+                state_machine_version_arn: format!("{}:2", STATE_ARN),
+                weight: 100,
+            }],
+            live_routing(&context).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deploy_rolls_back_failed_canary() {
+        let (context, backend) = canary_context(vec![
+            execution("ok", 2, "SUCCEEDED"),
+            execution("ng", 2, "FAILED"),
+        ]);
+        let arn_builder = crate::sts::build_arn_builder(&context).await.unwrap();
+
+        let err = deploy(&context, &arn_builder, &updated_state(), &canary_options())
+            .await
+            .unwrap_err();
+
+        assert_eq!(ErrorCategory::CanaryFailed, ErrorCategory::of(&err));
+        similar_asserts::assert_eq!(
+            vec![RoutingConfigurationListItem {
+                state_machine_version_arn: format!("{}:1", STATE_ARN),
+                weight: 100,
+            }],
+            live_routing(&context).await
+        );
+        // state machine keeps the new definition
+        let state = sfn::describe_state_machine_with_tags(&*backend, STATE_ARN)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated_state().definition, state.definition);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_requires_alias_routing_to_a_version() {
+        let (context, _) = canary_context(vec![]);
+        let arn_builder = crate::sts::build_arn_builder(&context).await.unwrap();
+
+        let mut options = canary_options();
+        options.alias = "beta".to_string();
+        let err = deploy(&context, &arn_builder, &updated_state(), &options)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "alias 'beta' of state machine(HelloWorld) does not exist, create it before canary deployment",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_ensure_alias_not_managed() {
+        let (_, backend) = canary_context(vec![]);
+        let state = backend.snapshot().state_machines.remove(0);
+
+        let err = ensure_alias_not_managed(&state, &canary_options()).unwrap_err();
+        assert_eq!(
+            "alias 'live' of state machine(HelloWorld) is managed in aliases, which conflicts with --canary",
+            err.to_string()
+        );
+        assert!(ensure_alias_not_managed(&StateMachine::test_default(), &canary_options()).is_ok());
+    }
+}
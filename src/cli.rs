@@ -1,13 +1,183 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = false)]
 pub struct Cli {
+    // declared before flattened options to be listed under "Options"
+    /// Format of error reported on failure
+    #[arg(long = "error-format", global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
+    /// Format of command result written to stdout
+    #[arg(long = "output", global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output_format: OutputFormat,
+    #[command(flatten)]
+    pub aws_options: AwsOptions,
+    #[command(flatten)]
+    pub backend_options: BackendOptions,
+    #[command(flatten)]
+    pub log_options: LogOptions,
     #[command(subcommand)]
     pub command: Commands,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable diff and summary
+    #[default]
+    Text,
+    /// Single JSON document of command result
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// Error message as is
+    #[default]
+    Text,
+    /// JSON object with category, exit code and message of error
+    Json,
+}
+
+// Options to build AWS clients, which override the default AWS config chain.
+#[derive(Args, Clone, Debug, Default)]
+#[command(next_help_heading = "AWS Options")]
+pub struct AwsOptions {
+    /// AWS profile name to use
+    #[arg(long = "profile", global = true, value_name = "profile-name")]
+    pub profile: Option<String>,
+    /// AWS region to use
+    #[arg(long = "region", global = true, value_name = "region")]
+    pub region: Option<String>,
+    /// IAM role arn to assume
+    #[arg(long = "role-arn", global = true, value_name = "role-arn")]
+    pub role_arn: Option<String>,
+    /// External id used on assuming role
+    #[arg(
+        long = "external-id",
+        global = true,
+        value_name = "external-id",
+        requires = "role_arn"
+    )]
+    pub external_id: Option<String>,
+    /// Session name used on assuming role
+    #[arg(
+        long = "role-session-name",
+        global = true,
+        value_name = "session-name",
+        requires = "role_arn"
+    )]
+    pub role_session_name: Option<String>,
+    /// Endpoint url for all AWS clients, e.g. http://localhost:4566 for LocalStack
+    #[arg(long = "endpoint-url", global = true, value_name = "url")]
+    pub endpoint_url: Option<String>,
+}
+
+// Options of canary deployment on apply, see `canary`.
+#[derive(Args, Clone, Debug, PartialEq)]
+#[command(next_help_heading = "Canary Options")]
+pub struct CanaryOptions {
+    /// Route a part of alias to updated state machine, and promote it only if its executions succeed
+    #[arg(long = "canary")]
+    pub enabled: bool,
+    /// Alias which routes executions to canary version
+    #[arg(
+        long = "canary-alias",
+        value_name = "alias-name",
+        default_value = "live",
+        requires = "enabled"
+    )]
+    pub alias: String,
+    /// Percentage of executions routed to canary version
+    #[arg(
+        long = "canary-weight",
+        value_name = "percent",
+        default_value_t = 10,
+        value_parser = clap::value_parser!(i32).range(1..100),
+        requires = "enabled"
+    )]
+    pub weight: i32,
+    /// Seconds to watch executions of canary version before promoting it
+    #[arg(
+        long = "canary-bake-time",
+        value_name = "seconds",
+        default_value_t = 300,
+        requires = "enabled"
+    )]
+    pub bake_time: u64,
+    /// Roll back when more than this percentage of finished executions failed
+    #[arg(
+        long = "canary-max-failure-rate",
+        value_name = "percent",
+        default_value_t = 0.0,
+        value_parser = parse_failure_rate,
+        requires = "enabled"
+    )]
+    pub max_failure_rate: f64,
+}
+
+fn parse_failure_rate(value: &str) -> Result<f64, String> {
+    let rate: f64 = value.parse().map_err(|e| format!("{}", e))?;
+    if !(0.0..=100.0).contains(&rate) {
+        return Err(format!("{} is not in 0..=100", rate));
+    }
+
+    Ok(rate)
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Compact human readable lines
+    #[default]
+    Text,
+    /// JSON lines with fields of enclosing spans, and a line for each closed span
+    Json,
+}
+
+// Options to choose how fubura reports logs and traces.
+#[derive(Args, Clone, Debug, Default)]
+#[command(next_help_heading = "Log Options")]
+pub struct LogOptions {
+    /// Format of logs
+    #[arg(long = "log-format", global = true, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+    /// Export spans as OTLP JSON lines to given file, or where logs go with "-"
+    #[arg(long = "trace-output", global = true, value_name = "path")]
+    pub trace_output: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Manage resources on AWS
+    #[default]
+    Aws,
+    /// Manage resources held in memory, for trying fubura without AWS account
+    Memory,
+}
+
+// Options to choose where fubura manages resources.
+#[derive(Args, Clone, Debug, Default)]
+#[command(next_help_heading = "Backend Options")]
+pub struct BackendOptions {
+    /// Backend which resources are managed on
+    #[arg(long = "backend", global = true, value_enum, default_value_t = Backend::Aws)]
+    pub backend: Backend,
+    /// JSON snapshot to seed memory backend with, which is updated after apply
+    #[arg(long = "memory-snapshot", global = true, value_name = "path")]
+    pub memory_snapshot_path: Option<String>,
+    /// Record AWS requests and responses into cassette in given directory
+    #[arg(long = "record", global = true, value_name = "dir")]
+    pub record_dir: Option<String>,
+    /// Serve AWS responses from cassette in given directory, instead of calling AWS
+    #[arg(
+        long = "replay",
+        global = true,
+        value_name = "dir",
+        conflicts_with_all = ["record_dir", "backend"]
+    )]
+    pub replay_dir: Option<String>,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// apply config
@@ -15,18 +185,29 @@ pub enum Commands {
         /// Skip confirm changes, and apply it immediately.
         #[clap(long = "auto-approve", short = 'a')]
         auto_approve: bool,
+        /// Allow to apply plan which deletes state machine or schedule.
+        #[clap(long = "allow-destroy")]
+        allow_destroy: bool,
+        /// Ask approval for each state machine config, and apply only approved ones.
+        #[clap(long = "interactive", short = 'i', conflicts_with = "auto_approve")]
+        interactive: bool,
         /// Config file path
         #[clap(long = "config", short = 'c', default_value = "fubura.jsonnet")]
         config_path: String,
         /// filter with target state machine names
         #[clap(long = "ext-str", short = 'V', value_name = "key=[val]")]
         ext_str: Vec<StrKeyVal>,
-        /// filter with target state machine names
+        /// filter with target state machine names, or "group/name" of standalone schedules
         #[clap(long = "target", short = 't', value_name = "key=[val]")]
         target: Option<Vec<String>>,
         /// Specify path to diff result as json
         #[clap(long = "diff-as-json", short = 'o', value_name = "output path")]
         json_diff_path: Option<String>,
+        /// Fail on remote fields which fubura doesn't model, instead of warning about them.
+        #[clap(long = "strict")]
+        strict: bool,
+        #[command(flatten)]
+        canary_options: CanaryOptions,
         /// Emit logs for debugging
         #[clap(long = "debug")]
         debug_mode: bool,
@@ -39,12 +220,15 @@ pub enum Commands {
         #[clap(long = "ext-str", short = 'V', value_name = "key=[val]")]
         /// jsonnet --ext-str options
         ext_str: Vec<StrKeyVal>,
-        /// filter with target state machine names
+        /// filter with target state machine names, or "group/name" of standalone schedules
         #[clap(long = "target", short = 't', value_name = "key=[val]")]
         target: Option<Vec<String>>,
         /// Specify path to diff result as json
         #[clap(long = "diff-as-json", short = 'o', value_name = "output path")]
         json_diff_path: Option<String>,
+        /// Fail on remote fields which fubura doesn't model, instead of warning about them.
+        #[clap(long = "strict")]
+        strict: bool,
         /// Emit logs for debugging
         #[clap(long = "debug")]
         debug_mode: bool,
@@ -70,6 +254,51 @@ pub enum Commands {
         #[clap(long = "debug")]
         debug_mode: bool,
     },
+    /// roll back state machine, or its alias, to published version
+    Rollback {
+        /// state machine name
+        #[arg(value_name = "state-machine-name")]
+        state_name: String,
+        /// Version to roll back to. Defaults to the one before the current version.
+        #[clap(long = "to-version", value_name = "version")]
+        to_version: Option<u32>,
+        /// Point this alias back to the version, instead of updating state machine
+        #[clap(long = "alias", value_name = "alias-name")]
+        alias: Option<String>,
+        /// Skip confirm changes, and roll back immediately.
+        #[clap(long = "auto-approve", short = 'a')]
+        auto_approve: bool,
+        /// Emit logs for debugging
+        #[clap(long = "debug")]
+        debug_mode: bool,
+    },
+    /// print IAM policies which roles of state machines, schedules and rules need
+    IamPolicy {
+        /// Config file path
+        #[clap(long = "config", short = 'c', default_value = "fubura.jsonnet")]
+        config_path: String,
+        /// jsonnet --ext-str options
+        #[clap(long = "ext-str", short = 'V', value_name = "key=[val]")]
+        ext_str: Vec<StrKeyVal>,
+        /// filter with target state machine names
+        #[clap(long = "target", short = 't', value_name = "key=[val]")]
+        target: Option<Vec<String>>,
+        /// Merge policies of the same role across state machine configs
+        #[clap(long = "merge")]
+        merge: bool,
+        /// Emit logs for debugging
+        #[clap(long = "debug")]
+        debug_mode: bool,
+    },
+    /// list published versions of state machine
+    Versions {
+        /// state machine name
+        #[arg(value_name = "state-machine-name")]
+        state_name: String,
+        /// Emit logs for debugging
+        #[clap(long = "debug")]
+        debug_mode: bool,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -93,3 +322,29 @@ impl From<&str> for StrKeyVal {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_canary_max_failure_rate_range() {
+        let parse = |rate: &str| {
+            Cli::try_parse_from([
+                "fubura",
+                "apply",
+                "-c",
+                "fubura.jsonnet",
+                "--canary",
+                "--canary-max-failure-rate",
+                rate,
+            ])
+        };
+
+        assert!(parse("0").is_ok());
+        assert!(parse("100").is_ok());
+        assert!(parse("-1").is_err());
+        assert!(parse("100.5").is_err());
+        assert!(parse("NaN").is_err());
+    }
+}
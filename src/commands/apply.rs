@@ -1,102 +1,643 @@
 use std::collections::HashMap;
 
-use anyhow::{Result, bail};
-use tracing::info;
+use anyhow::Result;
+use tracing::{Instrument, info};
 
+use crate::cli::CanaryOptions;
 use crate::context::FuburaContext;
 use crate::differ::diff;
-use crate::types::{Config, DiffOp, DiffResult, SsConfig};
-use crate::{scheduler, sfn, sts};
+use crate::error::FuburaError;
+use crate::types::{
+    ArnBuilder, Config, DiffOp, DiffOpsForScheduleGroup, DiffResult, ScheduleGroupConfig, SsConfig,
+    SsKey, SsName, StandaloneScheduleConfig, StateMachine, StateMachineAlias, target_display_name,
+};
+use crate::{canary, events, human_print, human_println, logs, scheduler, sfn, sts, telemetry};
 
 pub struct ApplyCommand;
 
 impl ApplyCommand {
-    pub async fn run(context: &FuburaContext, auto_approve: &bool, config: &Config) -> Result<()> {
-        let ss_config_by_name: HashMap<String, &SsConfig> = HashMap::from_iter(
-            config
-                .ss_configs
-                .iter()
-                .map(|ss_config| (ss_config.state.name.clone(), ss_config)),
-        );
+    pub async fn run(
+        context: &FuburaContext,
+        auto_approve: &bool,
+        allow_destroy: &bool,
+        interactive: &bool,
+        canary_options: &CanaryOptions,
+        config: &Config,
+    ) -> Result<DiffResult> {
+        use text_io::read;
+
+        Self::run_with_input(
+            context,
+            auto_approve,
+            allow_destroy,
+            interactive,
+            canary_options,
+            config,
+            &mut || read!("{}\n"),
+        )
+        .await
+    }
+
+    // Same as run, but responses of prompts are read by given function
+    async fn run_with_input(
+        context: &FuburaContext,
+        auto_approve: &bool,
+        allow_destroy: &bool,
+        interactive: &bool,
+        canary_options: &CanaryOptions,
+        config: &Config,
+        read_response: &mut dyn FnMut() -> String,
+    ) -> Result<DiffResult> {
+        let mut diff_result = diff(context, config).await?;
 
-        let diff_result = diff(context, config).await?;
+        if *interactive && !diff_result.no_change {
+            select_interactively(context, &mut diff_result, read_response);
+        }
 
         if let Some(json_diff_path) = &context.json_diff_path {
             write_result_to_path(json_diff_path, &diff_result)?;
         }
 
         if diff_result.no_change {
-            return Ok(());
+            // All changes could be skipped on interactive approval
+            print_skipped(context, &diff_result);
+            return Ok(diff_result);
+        }
+
+        let mut ss_config_by_key: HashMap<SsKey, &SsConfig> = HashMap::new();
+        // keyed by deploy target label and "group/name"
+        let mut schedule_config_by_key: HashMap<SsKey, &StandaloneScheduleConfig> = HashMap::new();
+        // keyed by deploy target label and group name
+        let mut group_config_by_key: HashMap<SsKey, &ScheduleGroupConfig> = HashMap::new();
+        for resources in config.target_resources_by_deploy_target(&context.targets) {
+            let target_context = context.for_deploy_target(&resources.deploy_target)?;
+            let target_label = if resources.deploy_target.is_some() {
+                Some(sts::build_target_label(target_context).await?)
+            } else {
+                None
+            };
+
+            for ss_config in resources.ss_configs {
+                ss_config_by_key.insert(
+                    (target_label.clone(), ss_config.state.name.clone()),
+                    ss_config,
+                );
+            }
+            for schedule_config in resources.schedules {
+                schedule_config_by_key.insert(
+                    (
+                        target_label.clone(),
+                        schedule_config.schedule_name_with_group(),
+                    ),
+                    schedule_config,
+                );
+            }
+            for group_config in resources.schedule_groups {
+                group_config_by_key.insert(
+                    (target_label.clone(), group_config.group.name.clone()),
+                    group_config,
+                );
+            }
         }
 
-        if !auto_approve {
-            print!(
-                r#"
+        let destroy_targets = collect_destroy_targets(&diff_result);
+        if !destroy_targets.is_empty() && !allow_destroy {
+            return Err(FuburaError::Validation(format!(
+                "plan contains deletion of {}, but --allow-destroy is not given.",
+                destroy_targets.join(", ")
+            ))
+            .into());
+        }
+
+        if canary_options.enabled {
+            for diff_ops_for_ss in diff_result.detail_diff_ops.iter() {
+                if diff_ops_for_ss.diff_ops.contains(&DiffOp::UpdateState) {
+                    let ss_config = *ss_config_by_key.get(&diff_ops_for_ss.key()).unwrap();
+                    canary::ensure_alias_not_managed(&ss_config.state, canary_options)?;
+                }
+            }
+        }
+
+        // Interactive approval already asked for each state and group, so only deletion needs confirm
+        let approved_interactively = *interactive && destroy_targets.is_empty();
+        if !auto_approve && !approved_interactively {
+            if destroy_targets.is_empty() {
+                human_print!(
+                    context,
+                    r#"
 Do you want apply this change?
 Only 'yes' will be accepted to approve.
 
 Enter a value: "#
-            );
-            use text_io::read;
-            let response: String = read!("{}\n");
+                );
+                let response = read_response();
 
-            if response != "yes" {
-                bail!("apply cancelled!");
+                if response != "yes" {
+                    return Err(FuburaError::Cancelled("apply cancelled!".to_string()).into());
+                }
+            } else {
+                human_print!(
+                    context,
+                    r#"
+Do you want apply this change?
+Following resources are going to be deleted:
+    {}
+
+Only the names of all resources above, separated by ',', will be accepted to approve.
+
+Enter a value: "#,
+                    destroy_targets.join("\n    ")
+                );
+                let response = read_response();
+
+                if !is_destroy_approved(&destroy_targets, &response) {
+                    return Err(FuburaError::Cancelled("apply cancelled!".to_string()).into());
+                }
             }
         }
 
-        let state_arn_prefix = sts::build_state_arn_prefix(context).await;
+        // Schedule groups are created before schedules in them, and deleted after them.
+        for diff_ops_for_group in diff_result.detail_schedule_group_diff_ops.iter() {
+            let diff_ops: Vec<DiffOp> = diff_ops_for_group
+                .diff_ops
+                .iter()
+                .filter(|op| **op != DiffOp::DeleteScheduleGroup)
+                .cloned()
+                .collect();
+            apply_schedule_group_diff_ops(
+                context,
+                &group_config_by_key,
+                diff_ops_for_group,
+                &diff_ops,
+            )
+            .await?;
+        }
+
+        let mut published_versions = vec![];
         for diff_ops_for_ss in diff_result.detail_diff_ops.iter() {
-            let ss_config = *ss_config_by_name.get(&diff_ops_for_ss.state_name).unwrap();
-            let state = &ss_config.state;
-
-            for diff_op in diff_ops_for_ss.diff_ops.iter() {
-                match diff_op {
-                    DiffOp::CreateState => {
-                        info!("Creating state machine: {}", state.name);
-                        sfn::create_state_machine(&context.sfn_client, state).await?;
-                    }
-                    DiffOp::UpdateState => {
-                        let state_arn = format!("{}{}", state_arn_prefix, state.name);
-                        info!("Updating state machine: {}", state.name);
-                        sfn::update_state_machine(&context.sfn_client, &state_arn, state).await?;
-                    }
-                    DiffOp::DeleteState => {
-                        let state_arn = format!("{}{}", state_arn_prefix, state.name);
-                        info!("Deleting state machine: {}", state.name);
-                        sfn::delete_state_machine(&context.sfn_client, &state_arn).await?;
-                    }
-                    DiffOp::AddStateTag => {
-                        let state_arn = format!("{}{}", state_arn_prefix, state.name);
-                        info!("Adding tags to state machine: {}", state.name);
-                        sfn::tag_resource(&context.sfn_client, &state_arn, &state.tags).await?;
-                    }
-                    DiffOp::RemoveStateTag(removed_keys) => {
-                        let state_arn = format!("{}{}", state_arn_prefix, state.name);
-                        info!("Removing tags from state machine: {}", state.name);
-                        sfn::untag_resource(&context.sfn_client, &state_arn, removed_keys).await?;
-                    }
-                    DiffOp::CreateSchedule => {
-                        let schedule = ss_config.schedule.as_ref().unwrap();
-                        info!("Creating schedule: {}", schedule.name);
-                        scheduler::create_schedule(&context.scheduler_client, schedule).await?;
-                    }
-                    DiffOp::UpdateSchedule => {
-                        let schedule = ss_config.schedule.as_ref().unwrap();
-                        info!("Updating schedule: {}", schedule.name);
-                        scheduler::update_schedule(&context.scheduler_client, schedule).await?;
-                    }
-                    DiffOp::DeleteSchedule => {
-                        let schedule = ss_config.schedule.as_ref().unwrap();
-                        info!("Deleting schedule: {}", schedule.name);
-                        scheduler::delete_schedule(&context.scheduler_client, schedule).await?;
+            if let SsName::ScheduleName(_) = diff_ops_for_ss.name {
+                let schedule_config = *schedule_config_by_key.get(&diff_ops_for_ss.key()).unwrap();
+                let context = context.for_deploy_target(&schedule_config.deploy_target)?;
+
+                if let Some(target) = &diff_ops_for_ss.target {
+                    info!("Applying to {}", target);
+                }
+
+                apply_standalone_schedule_diff_ops(
+                    context,
+                    schedule_config,
+                    &diff_ops_for_ss.diff_ops,
+                )
+                .instrument(telemetry::standalone_schedule_span(schedule_config))
+                .await?;
+                continue;
+            }
+
+            let ss_config = *ss_config_by_key.get(&diff_ops_for_ss.key()).unwrap();
+            let context = context.for_deploy_target(&ss_config.deploy_target)?;
+            let arn_builder = sts::build_arn_builder(context).await?;
+
+            if let Some(target) = &diff_ops_for_ss.target {
+                info!("Applying to {}", target);
+            }
+
+            let version_arn = apply_diff_ops(
+                context,
+                &arn_builder,
+                ss_config,
+                &diff_ops_for_ss.diff_ops,
+                canary_options,
+            )
+            .instrument(telemetry::ss_config_span(ss_config))
+            .await?;
+            published_versions.extend(version_arn);
+        }
+        diff_result.published_versions = published_versions;
+
+        for diff_ops_for_group in diff_result.detail_schedule_group_diff_ops.iter() {
+            if diff_ops_for_group
+                .diff_ops
+                .contains(&DiffOp::DeleteScheduleGroup)
+            {
+                apply_schedule_group_diff_ops(
+                    context,
+                    &group_config_by_key,
+                    diff_ops_for_group,
+                    &[DiffOp::DeleteScheduleGroup],
+                )
+                .await?;
+            }
+        }
+
+        if !diff_result.published_versions.is_empty() {
+            human_println!(context, "\nPublished versions:");
+            for version_arn in diff_result.published_versions.iter() {
+                human_println!(context, "    {}", version_arn);
+            }
+        }
+
+        print_skipped(context, &diff_result);
+
+        Ok(diff_result)
+    }
+}
+
+fn print_skipped(context: &FuburaContext, diff_result: &DiffResult) {
+    if !diff_result.skipped.is_empty() {
+        human_println!(context, "\nSkipped:");
+        for state_name in diff_result.skipped.iter() {
+            human_println!(context, "    {}", state_name);
+        }
+    }
+}
+
+// Returns arn of state machine version published by create or update.
+async fn apply_diff_ops(
+    context: &FuburaContext,
+    arn_builder: &ArnBuilder,
+    ss_config: &SsConfig,
+    diff_ops: &[DiffOp],
+    canary_options: &CanaryOptions,
+) -> Result<Option<String>> {
+    // Filled in the same way as diff
+    let ss_config = &ss_config.with_log_group_arn(arn_builder);
+    let state = &ss_config.state;
+    let mut version_arn = None;
+
+    for diff_op in diff_ops.iter() {
+        match diff_op {
+            DiffOp::CreateLogGroup => {
+                let log_group = state.log_group.as_ref().unwrap();
+                info!("Creating log group: {}", log_group.name);
+                logs::create_log_group(context.logs_client.as_ref(), log_group).await?;
+            }
+            DiffOp::UpdateLogGroup => {
+                let log_group = state.log_group.as_ref().unwrap();
+                let log_group_arn = arn_builder.log_group(&log_group.name);
+                info!("Updating log group: {}", log_group.name);
+                logs::update_log_group(context.logs_client.as_ref(), &log_group_arn, log_group)
+                    .await?;
+            }
+            DiffOp::CreateState => {
+                info!("Creating state machine: {}", state.name);
+                version_arn = sfn::create_state_machine(context.sfn_client.as_ref(), state).await?;
+            }
+            DiffOp::UpdateState if canary_options.enabled => {
+                version_arn = canary::deploy(context, arn_builder, state, canary_options).await?;
+            }
+            DiffOp::UpdateState => {
+                let state_arn = arn_builder.state_machine(&state.name);
+                info!("Updating state machine: {}", state.name);
+                version_arn =
+                    sfn::update_state_machine(context.sfn_client.as_ref(), &state_arn, state)
+                        .await?;
+            }
+            DiffOp::DeleteState => {
+                let state_arn = arn_builder.state_machine(&state.name);
+                info!("Deleting state machine: {}", state.name);
+                sfn::delete_state_machine(context.sfn_client.as_ref(), &state_arn).await?;
+            }
+            DiffOp::AddStateTag => {
+                let state_arn = arn_builder.state_machine(&state.name);
+                info!("Adding tags to state machine: {}", state.name);
+                sfn::tag_resource(context.sfn_client.as_ref(), &state_arn, &state.tags).await?;
+            }
+            DiffOp::RemoveStateTag(removed_keys) => {
+                let state_arn = arn_builder.state_machine(&state.name);
+                info!("Removing tags from state machine: {}", state.name);
+                sfn::untag_resource(context.sfn_client.as_ref(), &state_arn, removed_keys).await?;
+            }
+            DiffOp::CreateStateAlias(names) => {
+                for alias in state_aliases(state, names) {
+                    info!(
+                        "Creating alias of state machine: {}:{}",
+                        state.name, alias.name
+                    );
+                    sfn::create_state_machine_alias(context.sfn_client.as_ref(), alias).await?;
+                }
+            }
+            DiffOp::UpdateStateAlias(names) => {
+                for alias in state_aliases(state, names) {
+                    let alias_arn = arn_builder.state_machine_alias(&state.name, &alias.name);
+                    info!(
+                        "Updating alias of state machine: {}:{}",
+                        state.name, alias.name
+                    );
+                    sfn::update_state_machine_alias(context.sfn_client.as_ref(), &alias_arn, alias)
+                        .await?;
+                }
+            }
+            DiffOp::DeleteStateAlias(names) => {
+                for name in names {
+                    let alias_arn = arn_builder.state_machine_alias(&state.name, name);
+                    info!("Deleting alias of state machine: {}:{}", state.name, name);
+                    sfn::delete_state_machine_alias(context.sfn_client.as_ref(), &alias_arn)
+                        .await?;
+                }
+            }
+            DiffOp::CreateSchedule(name) => {
+                let schedule = ss_config.find_schedule(name).unwrap();
+                info!("Creating schedule: {}", name);
+                scheduler::create_schedule(context.scheduler_client.as_ref(), &schedule).await?;
+            }
+            DiffOp::UpdateSchedule(name) => {
+                let schedule = ss_config.find_schedule(name).unwrap();
+                info!("Updating schedule: {}", name);
+                scheduler::update_schedule(context.scheduler_client.as_ref(), &schedule).await?;
+            }
+            DiffOp::DeleteSchedule(name) => {
+                let schedule = ss_config.find_schedule(name).unwrap();
+                info!("Deleting schedule: {}", name);
+                scheduler::delete_schedule(context.scheduler_client.as_ref(), &schedule).await?;
+            }
+            DiffOp::CreateRule(name) => {
+                let state_arn = arn_builder.state_machine(&state.name);
+                let rule = ss_config.find_rule(name, &state_arn).unwrap();
+                info!("Creating rule: {}", name);
+                events::put_rule_with_target(context.events_client.as_ref(), &rule).await?;
+            }
+            DiffOp::UpdateRule(name) => {
+                let state_arn = arn_builder.state_machine(&state.name);
+                let rule = ss_config.find_rule(name, &state_arn).unwrap();
+                info!("Updating rule: {}", name);
+                events::put_rule_with_target(context.events_client.as_ref(), &rule).await?;
+            }
+            DiffOp::DeleteRule(name) => {
+                let state_arn = arn_builder.state_machine(&state.name);
+                let rule = ss_config.find_rule(name, &state_arn).unwrap();
+                info!("Deleting rule: {}", name);
+                events::delete_rule(context.events_client.as_ref(), &rule).await?;
+            }
+            DiffOp::CreateScheduleGroup
+            | DiffOp::UpdateScheduleGroup
+            | DiffOp::AddScheduleGroupTag
+            | DiffOp::RemoveScheduleGroupTag(_)
+            | DiffOp::DeleteScheduleGroup => {
+                unreachable!("schedule group ops are applied by apply_schedule_group_diff_ops")
+            }
+        }
+    }
+
+    Ok(version_arn)
+}
+
+async fn apply_standalone_schedule_diff_ops(
+    context: &FuburaContext,
+    schedule_config: &StandaloneScheduleConfig,
+    diff_ops: &[DiffOp],
+) -> Result<()> {
+    let client = context.scheduler_client.as_ref();
+    let schedule = &schedule_config.schedule;
+
+    for diff_op in diff_ops.iter() {
+        match diff_op {
+            DiffOp::CreateSchedule(name) => {
+                info!("Creating schedule: {}", name);
+                scheduler::create_schedule(client, schedule).await?;
+            }
+            DiffOp::UpdateSchedule(name) => {
+                info!("Updating schedule: {}", name);
+                scheduler::update_schedule(client, schedule).await?;
+            }
+            DiffOp::DeleteSchedule(name) => {
+                info!("Deleting schedule: {}", name);
+                scheduler::delete_schedule(client, schedule).await?;
+            }
+            _ => unreachable!("standalone schedule has only schedule ops"),
+        }
+    }
+
+    Ok(())
+}
+
+fn state_aliases<'a>(
+    state: &'a StateMachine,
+    names: &'a [String],
+) -> impl Iterator<Item = &'a StateMachineAlias> {
+    state
+        .aliases
+        .iter()
+        .flatten()
+        .filter(|alias| names.contains(&alias.name))
+}
+
+async fn apply_schedule_group_diff_ops(
+    context: &FuburaContext,
+    group_config_by_key: &HashMap<SsKey, &ScheduleGroupConfig>,
+    diff_ops_for_group: &DiffOpsForScheduleGroup,
+    diff_ops: &[DiffOp],
+) -> Result<()> {
+    if diff_ops.is_empty() {
+        return Ok(());
+    }
+
+    let key = (
+        diff_ops_for_group.target.clone(),
+        diff_ops_for_group.group_name.clone(),
+    );
+    let group_config = *group_config_by_key.get(&key).unwrap();
+    let group = &group_config.group;
+    let context = context.for_deploy_target(&group_config.deploy_target)?;
+    let client = context.scheduler_client.as_ref();
+
+    if let Some(target) = &diff_ops_for_group.target {
+        info!("Applying to {}", target);
+    }
+
+    for diff_op in diff_ops.iter() {
+        match diff_op {
+            DiffOp::CreateScheduleGroup => {
+                info!("Creating schedule group: {}", group.name);
+                scheduler::create_schedule_group(client, group).await?;
+            }
+            DiffOp::AddScheduleGroupTag => {
+                let group_arn = sts::build_arn_builder(context)
+                    .await?
+                    .schedule_group(&group.name);
+                info!("Adding tags to schedule group: {}", group.name);
+                scheduler::tag_resource(client, &group_arn, &group.tags).await?;
+            }
+            DiffOp::RemoveScheduleGroupTag(removed_keys) => {
+                let group_arn = sts::build_arn_builder(context)
+                    .await?
+                    .schedule_group(&group.name);
+                info!("Removing tags from schedule group: {}", group.name);
+                scheduler::untag_resource(client, &group_arn, removed_keys).await?;
+            }
+            DiffOp::DeleteScheduleGroup => {
+                // Schedules skipped on this apply would be deleted with the group
+                let schedule_names = scheduler::list_schedule_names(client, &group.name).await?;
+                if !schedule_names.is_empty() {
+                    return Err(FuburaError::Validation(format!(
+                        "schedule group '{}' still has schedules: {}, refuse to delete it.",
+                        group.name,
+                        schedule_names.join(", ")
+                    ))
+                    .into());
+                }
+
+                info!("Deleting schedule group: {}", group.name);
+                scheduler::delete_schedule_group(client, &group.name).await?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+enum Approval {
+    Approve,
+    Skip,
+    Quit,
+}
+
+fn parse_approval(response: &str) -> Option<Approval> {
+    match response.trim() {
+        "y" | "yes" => Some(Approval::Approve),
+        "n" | "no" | "s" | "skip" => Some(Approval::Skip),
+        "q" | "quit" => Some(Approval::Quit),
+        _ => None,
+    }
+}
+
+// Walk diff of each state and schedule group, and ask approval for it.
+// Skipped ones are dropped from diff_result, and so are remaining ones after quit.
+fn select_interactively(
+    context: &FuburaContext,
+    diff_result: &mut DiffResult,
+    read_response: &mut dyn FnMut() -> String,
+) {
+    let ss_keys_with_name: Vec<(SsKey, String)> = diff_result
+        .detail_diff_ops
+        .iter()
+        .map(|ops_for_ss| (ops_for_ss.key(), ops_for_ss.display_name()))
+        .collect();
+    let group_keys_with_name: Vec<(SsKey, String)> = diff_result
+        .detail_schedule_group_diff_ops
+        .iter()
+        .map(|ops_for_group| (ops_for_group.key(), ops_for_group.display_name()))
+        .collect();
+
+    let mut quit = false;
+    for (ss_key, display_name) in ss_keys_with_name.iter() {
+        if quit {
+            diff_result.skip(ss_key);
+            continue;
+        }
+
+        let text_diff = diff_result.text_diff_by_state.get(ss_key);
+        match ask_approval(context, display_name, text_diff, read_response) {
+            Approval::Approve => {}
+            Approval::Skip => diff_result.skip(ss_key),
+            Approval::Quit => {
+                diff_result.skip(ss_key);
+                quit = true;
+            }
+        }
+    }
+
+    for (group_key, display_name) in group_keys_with_name.iter() {
+        if quit {
+            diff_result.skip_schedule_group(group_key);
+            continue;
+        }
+
+        let display_name = format!("schedule group {}", display_name);
+        let text_diff = diff_result.text_diff_by_group.get(group_key);
+        match ask_approval(context, &display_name, text_diff, read_response) {
+            Approval::Approve => {}
+            Approval::Skip => diff_result.skip_schedule_group(group_key),
+            Approval::Quit => {
+                diff_result.skip_schedule_group(group_key);
+                quit = true;
+            }
+        }
+    }
+}
+
+fn ask_approval(
+    context: &FuburaContext,
+    display_name: &str,
+    text_diff: Option<&String>,
+    read_response: &mut dyn FnMut() -> String,
+) -> Approval {
+    if let Some(text_diff) = text_diff {
+        human_println!(context, "\n{}", text_diff);
+    }
+
+    loop {
+        human_print!(
+            context,
+            "Apply change of {}? [y]es, [s]kip, [q]uit: ",
+            display_name
+        );
+        let response = read_response();
+
+        if let Some(approval) = parse_approval(&response) {
+            break approval;
+        }
+    }
+}
+
+// Returns names of resources going to be deleted,
+// state machine and schedule group by its name, schedule by "group/name",
+// and alias by "state:alias".
+fn collect_destroy_targets(diff_result: &DiffResult) -> Vec<String> {
+    let mut targets = vec![];
+
+    for diff_ops_for_ss in diff_result.detail_diff_ops.iter() {
+        let state_name = diff_ops_for_ss.name.as_str();
+        let target = diff_ops_for_ss.target.as_deref();
+
+        for diff_op in diff_ops_for_ss.diff_ops.iter() {
+            match diff_op {
+                DiffOp::DeleteState => {
+                    targets.push(target_display_name(state_name, target));
+                }
+                DiffOp::DeleteSchedule(name) => {
+                    targets.push(target_display_name(name, target));
+                }
+                DiffOp::DeleteRule(name) => {
+                    targets.push(target_display_name(&format!("rule:{}", name), target));
+                }
+                DiffOp::DeleteStateAlias(names) => {
+                    for name in names {
+                        targets.push(target_display_name(
+                            &format!("{}:{}", state_name, name),
+                            target,
+                        ));
                     }
                 }
+                _ => {}
             }
         }
+    }
 
-        Ok(())
+    for diff_ops_for_group in diff_result.detail_schedule_group_diff_ops.iter() {
+        if diff_ops_for_group
+            .diff_ops
+            .contains(&DiffOp::DeleteScheduleGroup)
+        {
+            targets.push(diff_ops_for_group.display_name());
+        }
     }
+
+    targets
+}
+
+fn is_destroy_approved(destroy_targets: &[String], response: &str) -> bool {
+    let mut expected: Vec<&str> = destroy_targets.iter().map(|t| t.as_str()).collect();
+    expected.sort();
+
+    let mut actual: Vec<&str> = response
+        .split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .collect();
+    actual.sort();
+
+    expected == actual
 }
 
 fn write_result_to_path(output_path: &str, diff_result: &DiffResult) -> Result<()> {
@@ -104,3 +645,201 @@ fn write_result_to_path(output_path: &str, diff_result: &DiffResult) -> Result<(
     std::fs::write(output_path, json_diff)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::memory::{MemoryBackend, MemorySnapshot};
+    use crate::types::{Rule, RuleConfig, ScheduleGroup, StateMachine};
+
+    #[test]
+    fn test_collect_destroy_targets() {
+        let mut diff_result = DiffResult::default();
+        diff_result.append_diff_op(
+            "HelloWorld",
+            &DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
+        );
+        diff_result.append_diff_op(
+            "HelloWorld",
+            &DiffOp::DeleteStateAlias(vec!["live".to_string()]),
+        );
+        diff_result.append_diff_op(
+            "HelloWorld",
+            &DiffOp::DeleteRule("default/HelloWorldOnUpload".to_string()),
+        );
+        diff_result.append_diff_op("HelloWorld", &DiffOp::DeleteState);
+        diff_result.append_standalone_schedule_diff_op(
+            "batch/Nightly",
+            &DiffOp::DeleteSchedule("batch/Nightly".to_string()),
+        );
+        diff_result.append_schedule_group_diff_op("batch", &DiffOp::DeleteScheduleGroup);
+        // same names in another deploy target
+        diff_result.set_target(Some("210987654321/us-east-1".to_string()));
+        diff_result.append_diff_op("HelloWorld", &DiffOp::DeleteState);
+        diff_result.append_schedule_group_diff_op("batch", &DiffOp::DeleteScheduleGroup);
+
+        let actual = collect_destroy_targets(&diff_result);
+
+        similar_asserts::assert_eq!(
+            vec![
+                "default/HelloWorld".to_string(),
+                "HelloWorld:live".to_string(),
+                "rule:default/HelloWorldOnUpload".to_string(),
+                "HelloWorld".to_string(),
+                "batch/Nightly".to_string(),
+                "HelloWorld (210987654321/us-east-1)".to_string(),
+                "batch".to_string(),
+                "batch (210987654321/us-east-1)".to_string(),
+            ],
+            actual
+        );
+    }
+
+    #[test]
+    fn test_is_destroy_approved() {
+        let targets = vec!["default/HelloWorld".to_string(), "HelloWorld".to_string()];
+
+        assert!(is_destroy_approved(
+            &targets,
+            "HelloWorld, default/HelloWorld"
+        ));
+        assert!(!is_destroy_approved(&targets, "HelloWorld"));
+        assert!(!is_destroy_approved(&targets, "yes"));
+    }
+
+    #[test]
+    fn test_parse_approval() {
+        assert!(matches!(parse_approval("y"), Some(Approval::Approve)));
+        assert!(matches!(parse_approval("skip"), Some(Approval::Skip)));
+        assert!(matches!(parse_approval("q"), Some(Approval::Quit)));
+        assert!(parse_approval("maybe").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_apply_refuses_rule_deletion_without_allow_destroy() {
+        let snapshot = MemorySnapshot {
+            account: None,
+            state_machines: vec![StateMachine::test_default()],
+            schedules: vec![],
+            schedule_groups: vec![],
+            state_machine_versions: vec![],
+            executions: vec![],
+            rules: vec![Rule::test_default()],
+            log_groups: vec![],
+        };
+        let context = FuburaContext::from_memory_backend(std::sync::Arc::new(
+            MemoryBackend::from_snapshot(snapshot, "us-west-2"),
+        ));
+        let config = Config {
+            ss_configs: vec![SsConfig {
+                rules: vec![RuleConfig {
+                    rule: Rule::test_default(),
+                    delete: true,
+                }],
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+        let actual =
+            ApplyCommand::run(&context, &true, &false, &false, &canary_disabled(), &config)
+                .await
+                .unwrap_err();
+
+        assert_eq!(
+            "plan contains deletion of rule:default/HelloWorldOnUpload, but --allow-destroy is not given.",
+            actual.to_string()
+        );
+    }
+
+    fn group_context_and_config() -> (FuburaContext, Config) {
+        let snapshot = MemorySnapshot {
+            account: None,
+            state_machines: vec![StateMachine::test_default()],
+            schedules: vec![],
+            schedule_groups: vec![],
+            state_machine_versions: vec![],
+            executions: vec![],
+            rules: vec![],
+            log_groups: vec![],
+        };
+        let context = FuburaContext::from_memory_backend(std::sync::Arc::new(
+            MemoryBackend::from_snapshot(snapshot, "us-west-2"),
+        ));
+        let config = Config {
+            ss_configs: vec![SsConfig::test_default()],
+            schedules: vec![],
+            schedule_groups: vec![ScheduleGroupConfig {
+                group: ScheduleGroup::test_default(),
+                delete: false,
+                deploy_target: None,
+            }],
+        };
+
+        (context, config)
+    }
+
+    fn canary_disabled() -> CanaryOptions {
+        CanaryOptions {
+            enabled: false,
+            alias: "live".to_string(),
+            weight: 10,
+            bake_time: 0,
+            max_failure_rate: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_interactive_skips_schedule_group() {
+        let (context, config) = group_context_and_config();
+        let mut responses = vec!["s".to_string()].into_iter();
+
+        let actual = ApplyCommand::run_with_input(
+            &context,
+            &false,
+            &false,
+            &true,
+            &canary_disabled(),
+            &config,
+            &mut || responses.next().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert!(actual.no_change);
+        assert_eq!(vec!["batch".to_string()], actual.skipped);
+        assert_eq!(Some(&0), actual.summary.get("create_schedule_group"));
+        let remote_group =
+            scheduler::get_schedule_group_with_tags(context.scheduler_client.as_ref(), "batch")
+                .await
+                .unwrap();
+        assert!(remote_group.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_apply_interactive_approves_schedule_group() {
+        let (context, config) = group_context_and_config();
+        // No confirmation is asked after the approval of the group
+        let mut responses = vec!["y".to_string()].into_iter();
+
+        let actual = ApplyCommand::run_with_input(
+            &context,
+            &false,
+            &false,
+            &true,
+            &canary_disabled(),
+            &config,
+            &mut || responses.next().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert!(actual.skipped.is_empty());
+        let remote_group =
+            scheduler::get_schedule_group_with_tags(context.scheduler_client.as_ref(), "batch")
+                .await
+                .unwrap();
+        assert!(remote_group.is_some());
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+
+use crate::context::FuburaContext;
+use crate::types::{Config, IamPolicyResult};
+use crate::{human_println, iam, sts};
+
+pub struct IamPolicyCommand;
+
+impl IamPolicyCommand {
+    // Policies are listed per state machine config, or per role across them if merged.
+    pub async fn run(
+        context: &FuburaContext,
+        config: &Config,
+        merge: &bool,
+    ) -> Result<IamPolicyResult> {
+        let mut policies = vec![];
+
+        for resources in config.target_resources_by_deploy_target(&context.targets) {
+            let target_context = context.for_deploy_target(&resources.deploy_target)?;
+            let arn_builder = sts::build_arn_builder(target_context).await?;
+
+            for ss_config in resources.ss_configs {
+                // Nothing runs after deletion
+                if ss_config.delete_all {
+                    continue;
+                }
+
+                let role_policies = iam::build_role_policies(ss_config, &arn_builder);
+                if *merge {
+                    for role_policy in role_policies {
+                        iam::push_role_policy(&mut policies, role_policy);
+                    }
+                } else {
+                    policies.extend(role_policies);
+                }
+            }
+        }
+
+        for role_policy in policies.iter() {
+            human_println!(
+                context,
+                "{} ({}):\n{}\n",
+                role_policy.role_arn,
+                role_policy.state_names.join(", "),
+                serde_json::to_string_pretty(&role_policy.policy)?
+            );
+        }
+
+        Ok(IamPolicyResult { policies })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    use crate::types::{SsConfig, StateMachine};
+
+    fn ss_config(name: &str) -> SsConfig {
+        SsConfig {
+            state: StateMachine {
+                name: name.to_string(),
+                definition: json!({
+                    "StartAt": "Invoke",
+                    "States": {
+                        "Invoke": {
+                            "Type": "Task",
+                            "Resource": "arn:aws:states:::lambda:invoke",
+                            "Parameters": { "FunctionName": name },
+                            "End": true,
+                        },
+                    },
+                }),
+                logging_configuration: None,
+                ..StateMachine::test_default()
+            },
+            ..SsConfig::test_default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_iam_policy() {
+        let context = FuburaContext::from_memory(vec![], vec![]);
+        let mut deleted = ss_config("Deleted");
+        deleted.delete_all = true;
+        let config = Config {
+            ss_configs: vec![ss_config("HelloWorld"), ss_config("GoodBye"), deleted],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+
+        let actual = IamPolicyCommand::run(&context, &config, &false)
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(
+            vec![vec!["HelloWorld".to_string()], vec!["GoodBye".to_string()]],
+            actual
+                .policies
+                .iter()
+                .map(|role_policy| role_policy.state_names.clone())
+                .collect::<Vec<_>>()
+        );
+
+        // Both state machines share the execution role
+        let actual = IamPolicyCommand::run(&context, &config, &true)
+            .await
+            .unwrap();
+        assert_eq!(1, actual.policies.len());
+        similar_asserts::assert_eq!(
+            vec!["HelloWorld".to_string(), "GoodBye".to_string()],
+            actual.policies[0].state_names
+        );
+        similar_asserts::assert_eq!(
+            vec![
+                "arn:aws:lambda:us-west-2:123456789012:function:HelloWorld".to_string(),
+                "arn:aws:lambda:us-west-2:123456789012:function:HelloWorld:*".to_string(),
+                "arn:aws:lambda:us-west-2:123456789012:function:GoodBye".to_string(),
+                "arn:aws:lambda:us-west-2:123456789012:function:GoodBye:*".to_string(),
+            ],
+            actual.policies[0].policy.statement[0].resource
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::{Result, bail};
 
 use crate::context::FuburaContext;
-use crate::types::{Config, SsConfig};
+use crate::error::FuburaError;
+use crate::types::{Config, ImportResult, SsConfig};
 use crate::{scheduler, sfn, sts};
 
 pub struct ImportCommand;
@@ -12,7 +13,11 @@ fn ensure_not_exist_in_config(config: &Config, sfn_name: &str) -> Result<()> {
         .iter()
         .any(|ss_config| ss_config.state.name == sfn_name)
     {
-        bail!("state machine '{}' already exists in config", sfn_name);
+        return Err(FuburaError::Validation(format!(
+            "state machine '{}' already exists in config",
+            sfn_name
+        ))
+        .into());
     }
     Ok(())
 }
@@ -24,22 +29,30 @@ impl ImportCommand {
         mut config: Config,
         sfn_name: &str,
         schedule_name_with_group: &Option<String>,
-    ) -> Result<()> {
+    ) -> Result<ImportResult> {
         ensure_not_exist_in_config(&config, sfn_name)?;
 
-        let state_arn_prefix = sts::build_state_arn_prefix(context).await;
-        let state_arn = format!("{}{}", state_arn_prefix, sfn_name);
+        let arn_builder = sts::build_arn_builder(context).await?;
+        let state_arn = arn_builder.state_machine(sfn_name);
 
         let state_machine =
-            sfn::describe_state_machine_with_tags(&context.sfn_client, &state_arn).await?;
-        let state_machine = if let Some(state_machine) = state_machine {
+            sfn::describe_state_machine_with_tags(context.sfn_client.as_ref(), &state_arn).await?;
+        let mut state_machine = if let Some(state_machine) = state_machine {
             state_machine
         } else {
             bail!("state machine '{}' does not exist", sfn_name);
         };
 
+        // Leave aliases unmanaged if there is none
+        let aliases =
+            sfn::list_state_machine_aliases(context.sfn_client.as_ref(), &state_arn).await?;
+        if !aliases.is_empty() {
+            state_machine.aliases = Some(aliases);
+        }
+
         let scheduler_config = if let Some(schedule_name_with_group) = schedule_name_with_group {
-            scheduler::get_schedule(&context.scheduler_client, schedule_name_with_group).await?
+            scheduler::get_schedule(context.scheduler_client.as_ref(), schedule_name_with_group)
+                .await?
         } else {
             None
         };
@@ -47,18 +60,27 @@ impl ImportCommand {
         let ss_config = SsConfig {
             state: state_machine,
             schedule: scheduler_config,
+            schedules: vec![],
+            rules: vec![],
             delete_all: false,
             delete_schedule: false,
+            protect: false,
+            deploy_target: None,
         };
 
+        let schedule_name = ss_config
+            .schedule
+            .as_ref()
+            .map(|schedule| schedule.schedule_name_with_group());
         config.ss_configs.push(ss_config);
 
-        std::fs::write(
-            config_path,
-            serde_json::to_string_pretty(&config.ss_configs).unwrap(),
-        )?;
+        std::fs::write(config_path, config.to_json_string_pretty())?;
 
-        Ok(())
+        Ok(ImportResult {
+            config_path: config_path.to_string(),
+            state_name: sfn_name.to_string(),
+            schedule_name,
+        })
     }
 }
 
@@ -68,120 +90,16 @@ mod test {
 
     use super::*;
 
-    use aws_sdk_scheduler::{
-        operation::get_schedule::builders::GetScheduleOutputBuilder, types::builders::TargetBuilder,
-    };
-    use aws_sdk_sfn::operation::list_tags_for_resource::builders::ListTagsForResourceOutputBuilder;
-    use aws_sdk_sfn::types::LogLevel;
-    use aws_sdk_sfn::types::builders::{
-        CloudWatchLogsLogGroupBuilder, LogDestinationBuilder, LoggingConfigurationBuilder,
-        TagBuilder,
-    };
-    use aws_sdk_sfn::{
-        operation::describe_state_machine::builders::DescribeStateMachineOutputBuilder,
-        primitives::{DateTime, DateTimeFormat},
-        types::StateMachineType,
-    };
-    use aws_sdk_sts::operation::get_caller_identity::builders::GetCallerIdentityOutputBuilder;
-
-    use mockall::predicate::eq;
-
     #[tokio::test]
     async fn test_sfn_name_schedule_name_given() {
-        let mut context = FuburaContext::async_default().await;
-
-        context
-            .sts_client
-            .expect_get_caller_identity()
-            .return_once(|| {
-                Ok(GetCallerIdentityOutputBuilder::default()
-                    .account("123456789012".to_string())
-                    .build())
-            });
+        let dir = tempfile::tempdir().unwrap();
+        let imported_config_path = dir.path().join("hello-world.jsonnet");
+        let imported_config_path = imported_config_path.to_str().unwrap();
 
-        context
-            .sfn_client
-            .expect_describe_state_machine()
-            .with(eq(
-                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-            ))
-            .return_once(|_| {
-                Ok(DescribeStateMachineOutputBuilder::default()
-                    .state_machine_arn(
-                        "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-                    )
-                    .name("HelloWorld".to_string())
-                    .r#type(StateMachineType::Standard)
-                    .definition("{ \"StartAt\": \"FirstState\" }".to_string())
-                    .role_arn(
-                        "arn:aws:iam::123456789012:role/service-role/HelloWorldRole".to_string(),
-                    )
-                    .logging_configuration(
-                        LoggingConfigurationBuilder::default()
-                            .level(LogLevel::All)
-                            .include_execution_data(true)
-                            .destinations(
-                                LogDestinationBuilder::default()
-                                    .cloud_watch_logs_log_group(
-                                        CloudWatchLogsLogGroupBuilder::default()
-                                            .log_group_arn("arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup")
-                                            .build(),
-                                    )
-                                    .build(),
-                            )
-                            .build(),
-                    )
-                    .creation_date(
-                        DateTime::from_str("2021-01-01T00:00:00Z", DateTimeFormat::DateTime)
-                            .unwrap(),
-                    )
-                    .build()
-                    .unwrap())
-            });
-
-        context
-            .sfn_client
-            .expect_list_tags_for_resource()
-            .with(eq(
-                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-            ))
-            .return_once(|_| {
-                Ok(ListTagsForResourceOutputBuilder::default()
-                    .tags(TagBuilder::default().key("Env").value("Test").build())
-                    .tags(
-                        TagBuilder::default()
-                            .key("Name")
-                            .value("HelloWorld")
-                            .build(),
-                    )
-                    .build())
-            });
-
-        context
-            .scheduler_client
-            .expect_get_schedule()
-            .with(eq("default"), eq("HelloWorld"))
-            .return_once(|_, _| {
-                Ok(GetScheduleOutputBuilder::default()
-                    .arn("arn:aws:scheduler:us-west-2:123456789012:schedule:default/HelloWorld")
-                    .group_name("default")
-                    .name("HelloWorld")
-                    .description("HellowWorld schedule")
-                    .schedule_expression("rate(1 minute)")
-                    .schedule_expression_timezone("UTC")
-                    .state(aws_sdk_scheduler::types::ScheduleState::Enabled)
-                    .target(
-                        TargetBuilder::default()
-                            .arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
-                            .role_arn("arn:aws:iam::123456789012:role/service-role/HelloWorldRole")
-                            .build()
-                            .unwrap(),
-                    )
-                    .build())
-            });
-
-        let imported_config_path = "tmp/hello-world.jsonnet";
-        std::fs::remove_file(imported_config_path).ok();
+        let context = FuburaContext::from_memory(
+            vec![StateMachine::test_default()],
+            vec![Schedule::test_default()],
+        );
 
         ImportCommand::run(
             &context,
@@ -199,10 +117,8 @@ mod test {
             serde_json::from_str(&config_str).expect("imported config is not valid json");
 
         let expected_config = vec![SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         }];
 
         similar_asserts::assert_eq!(actual_config, expected_config);
@@ -210,77 +126,11 @@ mod test {
 
     #[tokio::test]
     async fn test_sfn_name_given() {
-        let mut context = FuburaContext::async_default().await;
-
-        context
-            .sts_client
-            .expect_get_caller_identity()
-            .return_once(|| {
-                Ok(GetCallerIdentityOutputBuilder::default()
-                    .account("123456789012".to_string())
-                    .build())
-            });
-
-        context
-            .sfn_client
-            .expect_describe_state_machine()
-            .with(eq(
-                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-            ))
-            .return_once(|_| {
-                Ok(DescribeStateMachineOutputBuilder::default()
-                    .state_machine_arn(
-                        "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-                    )
-                    .name("HelloWorld".to_string())
-                    .r#type(StateMachineType::Standard)
-                    .definition("{ \"StartAt\": \"FirstState\" }".to_string())
-                    .role_arn(
-                        "arn:aws:iam::123456789012:role/service-role/HelloWorldRole".to_string(),
-                    )
-                    .creation_date(
-                        DateTime::from_str("2021-01-01T00:00:00Z", DateTimeFormat::DateTime)
-                            .unwrap(),
-                    )
-                    .logging_configuration(
-                        LoggingConfigurationBuilder::default()
-                            .level(LogLevel::All)
-                            .include_execution_data(true)
-                            .destinations(
-                                LogDestinationBuilder::default()
-                                    .cloud_watch_logs_log_group(
-                                        CloudWatchLogsLogGroupBuilder::default()
-                                            .log_group_arn("arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup")
-                                            .build(),
-                                    )
-                                    .build(),
-                            )
-                            .build(),
-                    )
-                    .build()
-                    .unwrap())
-            });
-
-        context
-            .sfn_client
-            .expect_list_tags_for_resource()
-            .with(eq(
-                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-            ))
-            .return_once(|_| {
-                Ok(ListTagsForResourceOutputBuilder::default()
-                    .tags(TagBuilder::default().key("Env").value("Test").build())
-                    .tags(
-                        TagBuilder::default()
-                            .key("Name")
-                            .value("HelloWorld")
-                            .build(),
-                    )
-                    .build())
-            });
+        let dir = tempfile::tempdir().unwrap();
+        let imported_config_path = dir.path().join("hello-world-without-schedule.jsonnet");
+        let imported_config_path = imported_config_path.to_str().unwrap();
 
-        let imported_config_path = "tmp/hello-world-without-schedule.jsonnet";
-        std::fs::remove_file(imported_config_path).ok();
+        let context = FuburaContext::from_memory(vec![StateMachine::test_default()], vec![]);
 
         ImportCommand::run(
             &context,
@@ -296,12 +146,7 @@ mod test {
             std::fs::read_to_string(imported_config_path).expect("imported config not found");
         let actual_config: Vec<SsConfig> =
             serde_json::from_str(&config_str).expect("imported config is not valid");
-        let expected_config = vec![SsConfig {
-            state: StateMachine::test_default(),
-            schedule: None,
-            delete_all: false,
-            delete_schedule: false,
-        }];
+        let expected_config = vec![SsConfig::test_default()];
 
         similar_asserts::assert_eq!(actual_config, expected_config);
     }
@@ -309,18 +154,21 @@ mod test {
     #[tokio::test]
     #[should_panic(expected = "state machine 'HelloWorld' already exists in config")]
     async fn test_import_fail_with_already_exists() {
-        let context = FuburaContext::async_default().await;
+        let context = FuburaContext::from_memory(vec![], vec![]);
 
-        let imported_config_path = "tmp/test-import-fail-with-already-exists.jsonnet";
-        std::fs::remove_file(imported_config_path).ok();
+        let dir = tempfile::tempdir().unwrap();
+        let imported_config_path = dir
+            .path()
+            .join("test-import-fail-with-already-exists.jsonnet");
+        let imported_config_path = imported_config_path.to_str().unwrap();
 
         let config = Config {
             ss_configs: vec![SsConfig {
-                state: StateMachine::test_default(),
                 schedule: Some(Schedule::test_default()),
-                delete_all: false,
-                delete_schedule: false,
+                ..SsConfig::test_default()
             }],
+            schedules: vec![],
+            schedule_groups: vec![],
         };
         std::fs::write(
             imported_config_path,
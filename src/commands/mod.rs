@@ -1,3 +1,6 @@
 pub mod apply;
+pub mod iam_policy;
 pub mod import;
 pub mod plan;
+pub mod rollback;
+pub mod versions;
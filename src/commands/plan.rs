@@ -7,14 +7,14 @@ use crate::types::{Config, DiffResult};
 pub struct PlanCommand;
 
 impl PlanCommand {
-    pub async fn run(context: &FuburaContext, config: &Config) -> Result<()> {
+    pub async fn run(context: &FuburaContext, config: &Config) -> Result<DiffResult> {
         let diff_result = diff(context, config).await?;
 
         if let Some(json_diff_path) = &context.json_diff_path {
             write_result_to_path(json_diff_path, &diff_result)?;
         }
 
-        Ok(())
+        Ok(diff_result)
     }
 }
 
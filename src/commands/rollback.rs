@@ -0,0 +1,399 @@
+use anyhow::{Result, bail};
+
+use crate::context::FuburaContext;
+use crate::differ::format_resource_diff;
+use crate::error::FuburaError;
+use crate::types::{
+    RollbackResult, RoutingConfigurationListItem, StateMachine, StateMachineAlias,
+    StateMachineVersion, state_machine_version_number,
+};
+use crate::{human_print, human_println, sfn, sts};
+
+pub struct RollbackCommand;
+
+// Version just before the given one. Versions are ordered from the latest.
+fn previous_version(versions: &[StateMachineVersion], version: u32) -> Option<u32> {
+    versions.iter().map(|v| v.version).find(|v| *v < version)
+}
+
+fn routed_version_arn(alias: &StateMachineAlias, state_name: &str) -> Result<String> {
+    match alias.routing_configuration.as_slice() {
+        [item] => Ok(item.state_machine_version_arn.clone()),
+        _ => Err(FuburaError::Validation(format!(
+            "alias '{}' of state machine({}) routes to more than one version, roll back with --to-version after it's settled",
+            alias.name, state_name
+        ))
+        .into()),
+    }
+}
+
+impl RollbackCommand {
+    pub async fn run(
+        context: &FuburaContext,
+        state_name: &str,
+        to_version: &Option<u32>,
+        alias_name: &Option<String>,
+        auto_approve: &bool,
+    ) -> Result<RollbackResult> {
+        let client = context.sfn_client.as_ref();
+        let arn_builder = sts::build_arn_builder(context).await?;
+        let state_arn = arn_builder.state_machine(state_name);
+
+        let Some(mut current_state) =
+            sfn::describe_state_machine_with_tags(client, &state_arn).await?
+        else {
+            bail!("state machine '{}' does not exist", state_name);
+        };
+        let versions = sfn::list_state_machine_versions(client, &state_arn).await?;
+
+        // Alias runs the version it routes to, and state machine runs its latest definition
+        let mut alias = None;
+        let from_version = if let Some(alias_name) = alias_name {
+            let alias_arn = arn_builder.state_machine_alias(state_name, alias_name);
+            let Some(described) = sfn::describe_state_machine_alias(client, &alias_arn).await?
+            else {
+                return Err(FuburaError::Validation(format!(
+                    "alias '{}' of state machine({}) does not exist",
+                    alias_name, state_name
+                ))
+                .into());
+            };
+
+            let version_arn = routed_version_arn(&described, state_name)?;
+            let tags = current_state.tags;
+            current_state = sfn::describe_state_machine_version(client, &version_arn).await?;
+            current_state.tags = tags;
+            alias = Some((alias_arn, described));
+
+            state_machine_version_number(&version_arn)
+        } else if let Some(latest) = versions.first() {
+            // Changes not published yet are rolled back to the latest version
+            let mut latest_state =
+                sfn::describe_state_machine_version(client, &latest.version_arn).await?;
+            latest_state.tags.clone_from(&current_state.tags);
+
+            (latest_state == current_state).then_some(latest.version)
+        } else {
+            None
+        };
+
+        let to_version = match (to_version, from_version) {
+            (Some(to_version), _) => *to_version,
+            (None, Some(from_version)) => {
+                previous_version(&versions, from_version).ok_or_else(|| {
+                    FuburaError::Validation(format!(
+                        "state machine({}) has no version before {} to roll back to",
+                        state_name, from_version
+                    ))
+                })?
+            }
+            (None, None) => match versions.first() {
+                Some(latest) if alias.is_none() => latest.version,
+                _ => {
+                    return Err(FuburaError::Validation(format!(
+                        "state machine({}) has no versions to roll back to, publish them with publish: true",
+                        state_name
+                    ))
+                    .into());
+                }
+            },
+        };
+        let Some(target) = versions.iter().find(|v| v.version == to_version) else {
+            return Err(FuburaError::Validation(format!(
+                "version {} of state machine({}) does not exist",
+                to_version, state_name
+            ))
+            .into());
+        };
+
+        let mut target_state =
+            sfn::describe_state_machine_version(client, &target.version_arn).await?;
+        target_state.tags.clone_from(&current_state.tags);
+
+        let text_diff = format_resource_diff(
+            state_name,
+            &serde_json::to_string_pretty(&current_state).unwrap(),
+            &serde_json::to_string_pretty(&target_state).unwrap(),
+        );
+        let mut result = RollbackResult {
+            state_name: state_name.to_string(),
+            alias: alias_name.clone(),
+            from_version,
+            to_version,
+            rolled_back: false,
+        };
+
+        let changed = if alias.is_some() {
+            from_version != Some(to_version)
+        } else {
+            !text_diff.is_empty()
+        };
+        if !changed {
+            human_println!(context, "no difference");
+            return Ok(result);
+        }
+
+        human_println!(context, "{}", text_diff);
+        if let Some((alias_arn, _)) = &alias {
+            human_println!(
+                context,
+                "Alias({}) is going to route all executions to version {}",
+                alias_arn,
+                to_version
+            );
+        }
+
+        if !auto_approve {
+            use text_io::read;
+
+            human_print!(
+                context,
+                r#"
+Do you want to roll back to version {}?
+Only 'yes' will be accepted to approve.
+
+Enter a value: "#,
+                to_version
+            );
+            let response: String = read!("{}\n");
+
+            if response != "yes" {
+                return Err(FuburaError::Cancelled("rollback cancelled!".to_string()).into());
+            }
+        }
+
+        if let Some((alias_arn, mut alias)) = alias {
+            alias.routing_configuration = vec![RoutingConfigurationListItem {
+                state_machine_version_arn: target.version_arn.clone(),
+                weight: 100,
+            }];
+            sfn::update_state_machine_alias(client, &alias_arn, &alias).await?;
+
+            human_println!(
+                context,
+                "\nRolled back alias({}) to version {}. Update routingConfiguration of the alias in config, if it's managed in aliases:",
+                alias_arn,
+                to_version
+            );
+            human_println!(
+                context,
+                "{}",
+                serde_json::to_string_pretty(&alias.routing_configuration).unwrap()
+            );
+        } else {
+            // Rolled back state is published, so that the next rollback can return from it
+            let rolled_back_state = StateMachine {
+                publish: true,
+                version_description: Some(format!("rollback to v{}", to_version)),
+                ..target_state.clone()
+            };
+            sfn::update_state_machine(client, &state_arn, &rolled_back_state).await?;
+
+            human_println!(
+                context,
+                "\nRolled back state machine({}) to version {}. Update state in config as follows, to keep config the source of truth:",
+                state_name,
+                to_version
+            );
+            human_println!(
+                context,
+                "{}",
+                serde_json::to_string_pretty(&target_state).unwrap()
+            );
+        }
+
+        result.rolled_back = true;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::error::ErrorCategory;
+
+    const STATE_ARN: &str = "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld";
+
+    // HelloWorld with version 1 and 2, whose definitions differ
+    async fn versioned_context() -> FuburaContext {
+        let context = FuburaContext::from_memory(vec![], vec![]);
+        let client = context.sfn_client.as_ref();
+
+        let mut state = StateMachine::test_default();
+        state.publish = true;
+        sfn::create_state_machine(client, &state).await.unwrap();
+        state.definition = serde_json::json!({ "StartAt": "SecondState" });
+        sfn::update_state_machine(client, STATE_ARN, &state)
+            .await
+            .unwrap();
+
+        context
+    }
+
+    #[tokio::test]
+    async fn test_rollback_state_machine() {
+        let context = versioned_context().await;
+
+        let actual = RollbackCommand::run(&context, "HelloWorld", &None, &None, &true)
+            .await
+            .unwrap();
+
+        assert_eq!(Some(2), actual.from_version);
+        assert_eq!(1, actual.to_version);
+        assert!(actual.rolled_back);
+
+        let state = sfn::describe_state_machine_with_tags(context.sfn_client.as_ref(), STATE_ARN)
+            .await
+            .unwrap()
+            .unwrap();
+        similar_asserts::assert_eq!(StateMachine::test_default().definition, state.definition);
+
+        // $LATEST is same as version 1 now
+        let actual = RollbackCommand::run(&context, "HelloWorld", &Some(1), &None, &true)
+            .await
+            .unwrap();
+        assert!(!actual.rolled_back);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_publishes_version() {
+        let context = versioned_context().await;
+        let client = context.sfn_client.as_ref();
+
+        RollbackCommand::run(&context, "HelloWorld", &None, &None, &true)
+            .await
+            .unwrap();
+
+        let versions = sfn::list_state_machine_versions(client, STATE_ARN)
+            .await
+            .unwrap();
+        assert_eq!(3, versions[0].version);
+        assert_eq!(Some("rollback to v1".to_string()), versions[0].description);
+
+        // next rollback returns to the version rolled back from
+        let actual = RollbackCommand::run(&context, "HelloWorld", &None, &None, &true)
+            .await
+            .unwrap();
+
+        assert_eq!(Some(3), actual.from_version);
+        assert_eq!(2, actual.to_version);
+        assert!(actual.rolled_back);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_unpublished_changes() {
+        let context = versioned_context().await;
+        let client = context.sfn_client.as_ref();
+
+        let mut state = StateMachine::test_default();
+        state.definition = serde_json::json!({ "StartAt": "ThirdState" });
+        sfn::update_state_machine(client, STATE_ARN, &state)
+            .await
+            .unwrap();
+
+        let actual = RollbackCommand::run(&context, "HelloWorld", &None, &None, &true)
+            .await
+            .unwrap();
+
+        assert_eq!(None, actual.from_version);
+        assert_eq!(2, actual.to_version);
+        assert!(actual.rolled_back);
+
+        let state = sfn::describe_state_machine_with_tags(client, STATE_ARN)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            serde_json::json!({ "StartAt": "SecondState" }),
+            state.definition
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_alias() {
+        let context = versioned_context().await;
+        let client = context.sfn_client.as_ref();
+        sfn::create_state_machine_alias(
+            client,
+            &StateMachineAlias {
+                name: "live".to_string(),
+                description: None,
+                routing_configuration: vec![RoutingConfigurationListItem {
+                    state_machine_version_arn: format!("{}:2", STATE_ARN),
+                    weight: 100,
+                }],
+            },
+        )
+        .await
+        .unwrap();
+
+        let actual = RollbackCommand::run(
+            &context,
+            "HelloWorld",
+            &None,
+            &Some("live".to_string()),
+            &true,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(Some(2), actual.from_version);
+        assert_eq!(1, actual.to_version);
+        assert!(actual.rolled_back);
+
+        let alias = sfn::describe_state_machine_alias(client, &format!("{}:live", STATE_ARN))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            vec![RoutingConfigurationListItem {
+                state_machine_version_arn: format!("{}:1", STATE_ARN),
+                weight: 100,
+            }],
+            alias.routing_configuration
+        );
+
+        // State machine itself is untouched
+        let state = sfn::describe_state_machine_with_tags(client, STATE_ARN)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            serde_json::json!({ "StartAt": "SecondState" }),
+            state.definition
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_to_missing_version() {
+        let context = versioned_context().await;
+
+        let actual = RollbackCommand::run(&context, "HelloWorld", &Some(3), &None, &true)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "version 3 of state machine(HelloWorld) does not exist",
+            actual.to_string()
+        );
+        assert_eq!(
+            ErrorCategory::Validation,
+            actual.downcast_ref::<FuburaError>().unwrap().category()
+        );
+
+        let actual = RollbackCommand::run(
+            &context,
+            "HelloWorld",
+            &None,
+            &Some("live".to_string()),
+            &true,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            "alias 'live' of state machine(HelloWorld) does not exist",
+            actual.to_string()
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::{Result, bail};
+
+use crate::context::FuburaContext;
+use crate::human_println;
+use crate::types::VersionsResult;
+use crate::{sfn, sts};
+
+pub struct VersionsCommand;
+
+impl VersionsCommand {
+    pub async fn run(context: &FuburaContext, state_name: &str) -> Result<VersionsResult> {
+        let arn_builder = sts::build_arn_builder(context).await?;
+        let state_arn = arn_builder.state_machine(state_name);
+
+        // ListStateMachineVersions doesn't tell missing state machine from one without versions
+        if sfn::describe_state_machine_with_tags(context.sfn_client.as_ref(), &state_arn)
+            .await?
+            .is_none()
+        {
+            bail!("state machine '{}' does not exist", state_name);
+        }
+
+        let versions =
+            sfn::list_state_machine_versions(context.sfn_client.as_ref(), &state_arn).await?;
+
+        if versions.is_empty() {
+            human_println!(context, "state machine '{}' has no versions", state_name);
+        }
+        for version in versions.iter() {
+            human_println!(
+                context,
+                "{}\t{}\t{}",
+                version.version,
+                version.creation_date,
+                version.description.as_deref().unwrap_or("-")
+            );
+        }
+
+        Ok(VersionsResult {
+            state_name: state_name.to_string(),
+            versions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::StateMachine;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_versions() {
+        let context = FuburaContext::from_memory(vec![], vec![]);
+        let mut state = StateMachine::test_default();
+        state.publish = true;
+        state.version_description = Some("deploy abc123".to_string());
+        sfn::create_state_machine(context.sfn_client.as_ref(), &state)
+            .await
+            .unwrap();
+
+        let actual = VersionsCommand::run(&context, "HelloWorld").await.unwrap();
+
+        assert_eq!(1, actual.versions.len());
+        assert_eq!(
+            "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld:1",
+            actual.versions[0].version_arn
+        );
+        assert_eq!(
+            Some("deploy abc123".to_string()),
+            actual.versions[0].description
+        );
+
+        let actual = VersionsCommand::run(&context, "Missing").await.unwrap_err();
+        assert_eq!("state machine 'Missing' does not exist", actual.to_string());
+    }
+}
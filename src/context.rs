@@ -1,68 +1,416 @@
-use crate::scheduler::Scheduler;
-use crate::sfn::Sfn;
-use crate::sts::Sts;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use tokio::sync::OnceCell;
+
+use crate::cassette::{self, Cassette, Recorder, Replayer};
+use crate::cli::{AwsOptions, Backend, BackendOptions, OutputFormat};
+use crate::error::FuburaError;
+use crate::events::{Events, EventsImpl};
+use crate::logs::{Logs, LogsImpl};
+use crate::memory::MemoryBackend;
+use crate::scheduler::{Scheduler, SchedulerImpl};
+use crate::sfn::{Sfn, SfnImpl};
+use crate::sts::{self, Sts, StsImpl};
+use crate::telemetry::TraceInterceptor;
+use crate::types::{CallerIdentity, Config, DeployTarget};
+
+/// Future returned by client traits, which keeps them object safe.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 pub struct FuburaContext {
-    pub scheduler_client: Scheduler,
-    pub sfn_client: Sfn,
-    pub sts_client: Sts,
+    pub scheduler_client: Arc<dyn Scheduler>,
+    pub sfn_client: Arc<dyn Sfn>,
+    pub sts_client: Arc<dyn Sts>,
+    pub events_client: Arc<dyn Events>,
+    pub logs_client: Arc<dyn Logs>,
     pub aws_region: String,
+    // fetched once on first use, see `caller_identity`
+    pub caller_identity: OnceCell<CallerIdentity>,
+    pub aws_options: AwsOptions,
+    // set when clients are backed by memory, see `MemoryBackend`
+    pub memory_backend: Option<Arc<MemoryBackend>>,
+    // set on --record or --replay, see `Cassette`
+    pub cassette: Option<Arc<Cassette>>,
+    // contexts for deploy targets declared in config, see `prepare_deploy_targets`
+    pub target_contexts: HashMap<DeployTarget, FuburaContext>,
     pub targets: Option<Vec<String>>,
     pub json_diff_path: Option<String>,
+    pub output_format: OutputFormat,
+    // fail on remote fields fubura doesn't model, instead of warning
+    pub strict: bool,
 }
 
-impl FuburaContext {
-    #[cfg(not(test))]
-    pub async fn async_default() -> Self {
-        use std::time::Duration;
-
-        use aws_config::{BehaviorVersion, retry::RetryConfig};
-
-        let max_attempts = 100;
-        let max_backoff = 3;
-
-        let retry_config = RetryConfig::standard()
-            .with_max_attempts(max_attempts)
-            .with_max_backoff(Duration::from_secs(max_backoff));
-        let aws_config = aws_config::defaults(BehaviorVersion::latest())
-            .retry_config(retry_config)
-            .load()
-            .await;
-
-        let scheduler_client = Scheduler::new(aws_sdk_scheduler::Client::new(&aws_config));
-        let sfn_client = Sfn::new(aws_sdk_sfn::Client::new(&aws_config));
-        let sts_client = Sts::new(aws_sdk_sts::Client::new(&aws_config));
-        let aws_region = aws_config
-            .region()
-            .unwrap_or_else(|| {
-                eprintln!("AWS region not set");
-                std::process::exit(1);
-            })
-            .to_string();
+fn config_loader(aws_options: &AwsOptions) -> aws_config::ConfigLoader {
+    use std::time::Duration;
+
+    use aws_config::{BehaviorVersion, Region, retry::RetryConfig};
+
+    let max_attempts = 100;
+    let max_backoff = 3;
+
+    let retry_config = RetryConfig::standard()
+        .with_max_attempts(max_attempts)
+        .with_max_backoff(Duration::from_secs(max_backoff));
+    let mut loader = aws_config::defaults(BehaviorVersion::latest()).retry_config(retry_config);
+
+    if let Some(profile) = &aws_options.profile {
+        loader = loader.profile_name(profile);
+    }
+    if let Some(region) = &aws_options.region {
+        loader = loader.region(Region::new(region.clone()));
+    }
+    if let Some(endpoint_url) = &aws_options.endpoint_url {
+        loader = loader.endpoint_url(endpoint_url);
+    }
 
+    loader
+}
+
+async fn load_aws_config(aws_options: &AwsOptions) -> aws_config::SdkConfig {
+    use aws_config::sts::AssumeRoleProvider;
+
+    let aws_config = config_loader(aws_options).load().await;
+
+    let Some(role_arn) = &aws_options.role_arn else {
+        return aws_config;
+    };
+
+    // Assume role with credentials resolved by the default chain(or given profile).
+    let mut builder = AssumeRoleProvider::builder(role_arn)
+        .configure(&aws_config)
+        .session_name(
+            aws_options
+                .role_session_name
+                .clone()
+                .unwrap_or_else(|| "fubura".to_string()),
+        );
+    if let Some(external_id) = &aws_options.external_id {
+        builder = builder.external_id(external_id);
+    }
+    let provider = builder.build().await;
+
+    config_loader(aws_options)
+        .credentials_provider(provider)
+        .load()
+        .await
+}
+
+impl FuburaContext {
+    /// Build context with given clients, e.g. fakes or clients with custom middleware.
+    pub fn new(
+        scheduler_client: Arc<dyn Scheduler>,
+        sfn_client: Arc<dyn Sfn>,
+        sts_client: Arc<dyn Sts>,
+        events_client: Arc<dyn Events>,
+        logs_client: Arc<dyn Logs>,
+        aws_region: &str,
+    ) -> Self {
         Self {
             scheduler_client,
             sfn_client,
             sts_client,
-            aws_region,
+            events_client,
+            logs_client,
+            aws_region: aws_region.to_string(),
+            caller_identity: OnceCell::new(),
+            aws_options: AwsOptions::default(),
+            memory_backend: None,
+            cassette: None,
+            target_contexts: HashMap::new(),
             targets: None,
             json_diff_path: None,
+            output_format: OutputFormat::default(),
+            strict: false,
         }
     }
 
     #[cfg(test)]
-    pub async fn async_default() -> Self {
-        use crate::scheduler::MockSchedulerImpl;
-        use crate::sfn::MockSfnImpl;
-        use crate::sts::MockStsImpl;
+    pub fn from_mocks(
+        scheduler_client: crate::scheduler::MockSchedulerImpl,
+        sfn_client: crate::sfn::MockSfnImpl,
+        sts_client: crate::sts::MockStsImpl,
+        events_client: crate::events::MockEventsImpl,
+        logs_client: crate::logs::MockLogsImpl,
+    ) -> Self {
+        Self::new(
+            Arc::new(scheduler_client),
+            Arc::new(sfn_client),
+            Arc::new(sts_client),
+            Arc::new(events_client),
+            Arc::new(logs_client),
+            "us-west-2",
+        )
+    }
 
-        Self {
-            scheduler_client: MockSchedulerImpl::default(),
-            sfn_client: MockSfnImpl::default(),
-            sts_client: MockStsImpl::default(),
-            aws_region: "us-west-2".to_string(),
-            targets: None,
-            json_diff_path: None,
+    #[cfg(test)]
+    pub fn from_memory(
+        state_machines: Vec<crate::types::StateMachine>,
+        schedules: Vec<crate::types::Schedule>,
+    ) -> Self {
+        let snapshot = crate::memory::MemorySnapshot {
+            account: None,
+            state_machines,
+            schedules,
+            schedule_groups: vec![],
+            rules: vec![],
+            log_groups: vec![],
+            state_machine_versions: vec![],
+            executions: vec![],
+        };
+
+        Self::from_memory_backend(Arc::new(MemoryBackend::from_snapshot(
+            snapshot,
+            "us-west-2",
+        )))
+    }
+
+    pub fn from_memory_backend(backend: Arc<MemoryBackend>) -> Self {
+        let mut context = Self::new(
+            backend.clone(),
+            backend.clone(),
+            backend.clone(),
+            backend.clone(),
+            backend.clone(),
+            backend.region(),
+        );
+        context.memory_backend = Some(backend);
+
+        context
+    }
+
+    pub async fn async_default() -> Result<Self> {
+        Self::async_new(&AwsOptions::default()).await
+    }
+
+    pub async fn async_new(aws_options: &AwsOptions) -> Result<Self> {
+        let aws_config = load_aws_config(aws_options).await;
+
+        let scheduler_client = SchedulerImpl::new(aws_sdk_scheduler::Client::from_conf(
+            aws_sdk_scheduler::config::Builder::from(&aws_config)
+                .interceptor(TraceInterceptor)
+                .build(),
+        ));
+        let sfn_client = SfnImpl::new(aws_sdk_sfn::Client::from_conf(
+            aws_sdk_sfn::config::Builder::from(&aws_config)
+                .interceptor(TraceInterceptor)
+                .build(),
+        ));
+        let sts_client = StsImpl::new(aws_sdk_sts::Client::from_conf(
+            aws_sdk_sts::config::Builder::from(&aws_config)
+                .interceptor(TraceInterceptor)
+                .build(),
+        ));
+        let events_client = EventsImpl::new(aws_sdk_eventbridge::Client::from_conf(
+            aws_sdk_eventbridge::config::Builder::from(&aws_config)
+                .interceptor(TraceInterceptor)
+                .build(),
+        ));
+        let logs_client = LogsImpl::new(aws_sdk_cloudwatchlogs::Client::from_conf(
+            aws_sdk_cloudwatchlogs::config::Builder::from(&aws_config)
+                .interceptor(TraceInterceptor)
+                .build(),
+        ));
+        let Some(aws_region) = aws_config.region().map(|region| region.to_string()) else {
+            return Err(FuburaError::Config(
+                "AWS region not set, specify it with --region or AWS_REGION".to_string(),
+            )
+            .into());
+        };
+
+        let mut context = Self::new(
+            Arc::new(scheduler_client),
+            Arc::new(sfn_client),
+            Arc::new(sts_client),
+            Arc::new(events_client),
+            Arc::new(logs_client),
+            &aws_region,
+        );
+        context.aws_options = aws_options.clone();
+
+        Ok(context)
+    }
+
+    pub async fn from_options(
+        aws_options: &AwsOptions,
+        backend_options: &BackendOptions,
+    ) -> Result<Self> {
+        // Paths given by options are part of configuration
+        let config_error = |e: anyhow::Error| FuburaError::Config(format!("{:#}", e));
+
+        if let Some(dir) = &backend_options.replay_dir {
+            let cassette = Cassette::replay(dir).map_err(config_error)?;
+            let region = cassette
+                .region(cassette::DEFAULT_TARGET)
+                .or_else(|| aws_options.region.clone())
+                .unwrap_or_else(|| "us-east-1".to_string());
+
+            let mut context =
+                Self::from_replay(Arc::new(cassette), cassette::DEFAULT_TARGET, &region);
+            context.aws_options = aws_options.clone();
+
+            return Ok(context);
+        }
+
+        let mut context = match backend_options.backend {
+            Backend::Aws => Self::async_new(aws_options).await?,
+            Backend::Memory => {
+                let region = aws_options.region.as_deref().unwrap_or("us-east-1");
+                let backend = match &backend_options.memory_snapshot_path {
+                    Some(path) => MemoryBackend::load(path, region).map_err(config_error)?,
+                    None => MemoryBackend::new(crate::memory::DEFAULT_ACCOUNT, region),
+                };
+
+                let mut context = Self::from_memory_backend(Arc::new(backend));
+                context.aws_options = aws_options.clone();
+
+                context
+            }
+        };
+
+        if let Some(dir) = &backend_options.record_dir {
+            let cassette = Cassette::record(dir).map_err(config_error)?;
+            context.record(Arc::new(cassette), cassette::DEFAULT_TARGET);
+        }
+
+        Ok(context)
+    }
+
+    pub fn from_replay(cassette: Arc<Cassette>, target: &str, aws_region: &str) -> Self {
+        let replayer = Arc::new(Replayer::new(cassette.clone(), target));
+        let mut context = Self::new(
+            replayer.clone(),
+            replayer.clone(),
+            replayer.clone(),
+            replayer.clone(),
+            replayer,
+            aws_region,
+        );
+        context.cassette = Some(cassette);
+
+        context
+    }
+
+    // Wrap clients of this context to record their interactions into cassette.
+    pub fn record(&mut self, cassette: Arc<Cassette>, target: &str) {
+        let recorder = Arc::new(Recorder::new(self, cassette.clone(), target));
+
+        self.sfn_client = recorder.clone();
+        self.scheduler_client = recorder.clone();
+        self.sts_client = recorder.clone();
+        self.events_client = recorder.clone();
+        self.logs_client = recorder;
+        self.cassette = Some(cassette);
+    }
+
+    // Build clients for each deploy target in config.
+    // Deploy target overrides region and role of global AWS options.
+    pub async fn prepare_deploy_targets(&mut self, config: &Config) -> Result<()> {
+        for deploy_target in config.deploy_targets() {
+            if self.target_contexts.contains_key(&deploy_target) {
+                continue;
+            }
+
+            let label = cassette::target_label(&deploy_target);
+            let replay_cassette = self.cassette.as_ref().filter(|c| c.is_replay());
+
+            let mut target_context = if let Some(cassette) = replay_cassette {
+                Self::from_replay(cassette.clone(), &label, &deploy_target.region)
+            } else if let Some(memory_backend) = &self.memory_backend {
+                Self::memory_target_context(memory_backend, &deploy_target)
+            } else {
+                let mut aws_options = self.aws_options.clone();
+                aws_options.region = Some(deploy_target.region.clone());
+                if let Some(role_arn) = &deploy_target.role_arn {
+                    aws_options.role_arn = Some(role_arn.clone());
+                    aws_options
+                        .external_id
+                        .clone_from(&deploy_target.external_id);
+                }
+
+                Self::async_new(&aws_options).await?
+            };
+
+            if let Some(cassette) = &self.cassette
+                && !cassette.is_replay()
+            {
+                target_context.record(cassette.clone(), &label);
+            }
+            target_context.output_format = self.output_format;
+            target_context.strict = self.strict;
+
+            self.target_contexts.insert(deploy_target, target_context);
+        }
+
+        Ok(())
+    }
+
+    // Deploy target on memory backend gets its own empty backend, unless it points to
+    // the same account and region as default one. Account is taken from role arn.
+    fn memory_target_context(
+        memory_backend: &Arc<MemoryBackend>,
+        deploy_target: &DeployTarget,
+    ) -> Self {
+        let account = deploy_target
+            .role_arn
+            .as_deref()
+            .and_then(|role_arn| role_arn.split(':').nth(4))
+            .unwrap_or(memory_backend.account());
+
+        if account == memory_backend.account() && deploy_target.region == memory_backend.region() {
+            Self::from_memory_backend(memory_backend.clone())
+        } else {
+            Self::from_memory_backend(Arc::new(MemoryBackend::new(account, &deploy_target.region)))
+        }
+    }
+
+    pub async fn caller_identity(&self) -> Result<&CallerIdentity> {
+        self.caller_identity
+            .get_or_try_init(|| sts::get_caller_identity(self.sts_client.as_ref()))
+            .await
+    }
+
+    pub fn for_deploy_target(&self, deploy_target: &Option<DeployTarget>) -> Result<&Self> {
+        let Some(deploy_target) = deploy_target else {
+            return Ok(self);
+        };
+
+        if let Some(target_context) = self.target_contexts.get(deploy_target) {
+            Ok(target_context)
+        } else {
+            bail!(
+                "AWS clients for deploy target {:?} are not prepared",
+                deploy_target
+            );
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::error::ErrorCategory;
+
+    #[tokio::test]
+    async fn test_from_options_fails_on_missing_cassette() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend_options = BackendOptions {
+            replay_dir: Some(dir.path().to_str().unwrap().to_string()),
+            ..BackendOptions::default()
+        };
+
+        let actual = FuburaContext::from_options(&AwsOptions::default(), &backend_options)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(ErrorCategory::Config, ErrorCategory::of(&actual));
+        assert!(actual.to_string().starts_with(&format!(
+            "failed to read cassette '{}'",
+            dir.path().join("cassette.json").display()
+        )));
+    }
+}
@@ -1,17 +1,25 @@
 use std::collections::HashSet;
+use std::time::SystemTime;
 
-use anyhow::{Result, bail};
+use anyhow::Result;
+use aws_sdk_scheduler::primitives::DateTime;
 use console::Style;
 use similar::{ChangeTag, TextDiff};
-use tracing::{debug, info};
+use tracing::{Instrument, debug, error, info};
 
 use crate::{
     context::FuburaContext,
-    scheduler, sfn, sts,
-    types::{Config, DiffOp, DiffResult, ResourceTag, Schedule, SsConfig, StateMachine},
+    error::FuburaError,
+    events, human_println, logs, scheduler, sfn, sts, telemetry,
+    types::{
+        ArnBuilder, Config, DEFAULT_SCHEDULE_GROUP_NAME, DiffOp, DiffResult, LogGroup,
+        PROTECTION_TAG_KEY, ResourceTag, Rule, RuleConfig, Schedule, ScheduleConfig, ScheduleGroup,
+        ScheduleGroupConfig, SsConfig, StandaloneScheduleConfig, StateMachine, StateMachineAlias,
+        state_machine_version_number,
+    },
 };
 
-fn format_resource_diff(target: &str, remote: &str, local: &str) -> String {
+pub(crate) fn format_resource_diff(target: &str, remote: &str, local: &str) -> String {
     let mut buffer = String::new();
 
     let remote_name = format!("remote: {}", target);
@@ -40,16 +48,17 @@ fn format_resource_diff(target: &str, remote: &str, local: &str) -> String {
     buffer
 }
 
+// Remote schedules and rules are in the same order as those of local config
 fn format_config_diff(
     local_config: &SsConfig,
     remote_state: &Option<StateMachine>,
-    remote_schedule: &Option<Schedule>,
+    remote_schedules: &[Option<Schedule>],
+    rule_configs: &[RuleConfig],
+    remote_rules: &[Option<Rule>],
     diff_ops: &[DiffOp],
 ) -> Option<String> {
     let mut change_state = false;
     let mut delete_state = false;
-    let mut change_schedule = false;
-    let mut delete_schedule = false;
 
     let mut buffer = String::new();
 
@@ -58,22 +67,30 @@ fn format_config_diff(
             DiffOp::CreateState
             | DiffOp::UpdateState
             | DiffOp::AddStateTag
-            | DiffOp::RemoveStateTag(_) => {
+            | DiffOp::RemoveStateTag(_)
+            | DiffOp::CreateStateAlias(_)
+            | DiffOp::UpdateStateAlias(_)
+            | DiffOp::DeleteStateAlias(_)
+            | DiffOp::CreateLogGroup
+            | DiffOp::UpdateLogGroup => {
                 change_state = true;
             }
             DiffOp::DeleteState => {
                 delete_state = true;
             }
-            DiffOp::CreateSchedule | DiffOp::UpdateSchedule => {
-                change_schedule = true;
-            }
-            DiffOp::DeleteSchedule => {
-                delete_schedule = true;
-            }
+            DiffOp::CreateSchedule(_) | DiffOp::UpdateSchedule(_) | DiffOp::DeleteSchedule(_) => {}
+            DiffOp::CreateRule(_) | DiffOp::UpdateRule(_) | DiffOp::DeleteRule(_) => {}
+            DiffOp::CreateScheduleGroup
+            | DiffOp::UpdateScheduleGroup
+            | DiffOp::AddScheduleGroupTag
+            | DiffOp::RemoveScheduleGroupTag(_)
+            | DiffOp::DeleteScheduleGroup => {}
         }
     }
+    let has_schedule_op = diff_ops.iter().any(|op| op.schedule_name().is_some());
+    let has_rule_op = diff_ops.iter().any(|op| op.rule_name().is_some());
 
-    if !change_state && !change_schedule && !delete_state && !delete_schedule {
+    if !change_state && !delete_state && !has_schedule_op && !has_rule_op {
         return None;
     }
 
@@ -96,30 +113,84 @@ fn format_config_diff(
         buffer.push_str(str.as_str());
     }
 
-    if change_schedule {
-        let target_schedule_name = local_config.schedule.as_ref().unwrap().name.as_str();
-        let remote_schedule_json_string = serde_json::to_string_pretty(&remote_schedule).unwrap();
-        let local_schedule_json_string =
-            serde_json::to_string_pretty(&local_config.schedule).unwrap();
+    for (schedule_config, remote_schedule) in
+        local_config.schedule_configs().iter().zip(remote_schedules)
+    {
+        let local_schedule = &schedule_config.schedule;
+        let name = local_schedule.schedule_name_with_group();
+
+        if diff_ops.contains(&DiffOp::CreateSchedule(name.clone()))
+            || diff_ops.contains(&DiffOp::UpdateSchedule(name.clone()))
+        {
+            let remote_schedule_json_string =
+                serde_json::to_string_pretty(&remote_schedule).unwrap();
+            let local_schedule_json_string =
+                serde_json::to_string_pretty(&Some(local_schedule)).unwrap();
+
+            let text_diff = format_resource_diff(
+                &local_schedule.name,
+                &remote_schedule_json_string,
+                &local_schedule_json_string,
+            );
+            buffer.push_str(format!("{}\n", text_diff).as_str());
+        } else if diff_ops.contains(&DiffOp::DeleteSchedule(name)) {
+            let str = format!(
+                "Schedule({}) is going to be deleted\n",
+                remote_schedule.as_ref().unwrap().name
+            );
+            buffer.push_str(str.as_str());
+        }
+    }
 
-        let text_diff = format_resource_diff(
-            target_schedule_name,
-            &remote_schedule_json_string,
-            &local_schedule_json_string,
-        );
-        let str = format!("{}\n", text_diff);
-        buffer.push_str(str.as_str());
-    } else if delete_schedule {
-        let str = format!(
-            "Schedule({}) is going to be deleted\n",
-            remote_schedule.as_ref().unwrap().name
-        );
-        buffer.push_str(str.as_str());
+    for (rule_config, remote_rule) in rule_configs.iter().zip(remote_rules) {
+        let local_rule = &rule_config.rule;
+        let name = local_rule.rule_name_with_bus();
+
+        if diff_ops.contains(&DiffOp::CreateRule(name.clone()))
+            || diff_ops.contains(&DiffOp::UpdateRule(name.clone()))
+        {
+            let text_diff = format_resource_diff(
+                &format!("rule/{}", name),
+                &serde_json::to_string_pretty(&remote_rule).unwrap(),
+                &serde_json::to_string_pretty(&Some(local_rule)).unwrap(),
+            );
+            buffer.push_str(format!("{}\n", text_diff).as_str());
+        } else if diff_ops.contains(&DiffOp::DeleteRule(name.clone())) {
+            buffer.push_str(format!("Rule({}) is going to be deleted\n", name).as_str());
+        }
     }
 
     Some(buffer)
 }
 
+fn format_schedule_group_diff(
+    local_config: &ScheduleGroupConfig,
+    remote_group: &Option<ScheduleGroup>,
+    diff_ops: &[DiffOp],
+) -> Option<String> {
+    if diff_ops.is_empty() {
+        return None;
+    }
+
+    let group_name = &local_config.group.name;
+    if diff_ops.contains(&DiffOp::DeleteScheduleGroup) {
+        return Some(format!(
+            "Schedule group({}) is going to be deleted\n",
+            group_name
+        ));
+    }
+
+    let remote_group_json_string = serde_json::to_string_pretty(&remote_group).unwrap();
+    let local_group_json_string = serde_json::to_string_pretty(&local_config.group).unwrap();
+    let text_diff = format_resource_diff(
+        &format!("schedule-group/{}", group_name),
+        &remote_group_json_string,
+        &local_group_json_string,
+    );
+
+    Some(format!("{}\n", text_diff))
+}
+
 fn split_sfn_and_tags(sfn: Option<StateMachine>) -> (Option<StateMachine>, Vec<ResourceTag>) {
     if sfn.is_none() {
         return (None, vec![]);
@@ -132,10 +203,36 @@ fn split_sfn_and_tags(sfn: Option<StateMachine>) -> (Option<StateMachine>, Vec<R
     (Some(sfn), tags)
 }
 
+fn is_completed_schedule(schedule: &Schedule) -> bool {
+    schedule.is_completed_at(DateTime::from(SystemTime::now()))
+}
+
+fn build_schedule_diff_op(
+    schedule_config: &ScheduleConfig,
+    remote_schedule: &Option<Schedule>,
+) -> Option<DiffOp> {
+    let local_schedule = &schedule_config.schedule;
+    let name = local_schedule.schedule_name_with_group();
+
+    if schedule_config.delete {
+        remote_schedule
+            .as_ref()
+            .map(|_| DiffOp::DeleteSchedule(name))
+    } else if let Some(remote_schedule) = remote_schedule {
+        (local_schedule != remote_schedule).then_some(DiffOp::UpdateSchedule(name))
+    } else if is_completed_schedule(local_schedule) {
+        // No change, it has been deleted after completion
+        None
+    } else {
+        Some(DiffOp::CreateSchedule(name))
+    }
+}
+
+// Remote schedules are in the same order as schedules of local config
 fn build_diff_ops(
     local_config: &SsConfig,
     remote_state: &Option<StateMachine>,
-    remote_schedule: &Option<Schedule>,
+    remote_schedules: &[Option<Schedule>],
 ) -> Result<Vec<DiffOp>> {
     let mut expected_ops = vec![];
 
@@ -143,17 +240,29 @@ fn build_diff_ops(
     let remote_state = remote_state.clone();
 
     let (local_state, local_state_tags) = split_sfn_and_tags(Some(local_state));
-    let local_state = local_state.unwrap();
-    let (remote_state, remote_state_tags) = split_sfn_and_tags(remote_state);
+    let mut local_state = local_state.unwrap();
+    let (mut remote_state, remote_state_tags) = split_sfn_and_tags(remote_state);
+
+    // Aliases are changed by their own api, so compare them separately
+    let local_aliases = local_state.aliases.take();
+    let remote_aliases = remote_state
+        .as_mut()
+        .and_then(|remote_state| remote_state.aliases.take());
+    // Log group is compared by build_log_group_diff_ops
+    local_state.log_group = None;
+    if let Some(remote_state) = remote_state.as_mut() {
+        remote_state.log_group = None;
+    }
 
-    let local_schedule = local_config.schedule.clone();
-    let remote_schedule = remote_schedule.clone();
+    let schedule_configs = local_config.schedule_configs();
 
     if local_config.delete_all {
-        if local_schedule.is_some() && remote_schedule.is_some() {
-            expected_ops.push(DiffOp::DeleteSchedule);
-        } else {
-            // No change
+        for (schedule_config, remote_schedule) in schedule_configs.iter().zip(remote_schedules) {
+            if remote_schedule.is_some() {
+                expected_ops.push(DiffOp::DeleteSchedule(
+                    schedule_config.schedule.schedule_name_with_group(),
+                ));
+            }
         }
 
         if remote_state.is_some() {
@@ -165,32 +274,17 @@ fn build_diff_ops(
         return Ok(expected_ops);
     }
 
-    if local_config.delete_schedule {
-        if local_schedule.is_none() {
-            bail!(
-                "delete schedule flag(deleteSchedule) is on, but can't identify schedule since schedule config is not exist."
-            );
-        }
-
-        if remote_schedule.is_some() {
-            expected_ops.push(DiffOp::DeleteSchedule);
-        } else {
-            // No change
-        }
-    } else if local_schedule.is_none() {
-        // No change
-    } else {
-        let local_schedule = local_schedule.unwrap();
+    if local_config.delete_schedule && local_config.schedule.is_none() {
+        return Err(FuburaError::Validation(
+            "delete schedule flag(deleteSchedule) is on, but can't identify schedule since schedule config is not exist."
+                .to_string(),
+        )
+        .into());
+    }
 
-        if let Some(remote_schedule) = remote_schedule {
-            if local_schedule == remote_schedule {
-                // No change
-            } else {
-                expected_ops.push(DiffOp::UpdateSchedule);
-            }
-        } else {
-            expected_ops.push(DiffOp::CreateSchedule);
-        }
+    // Each schedule is diffed independently
+    for (schedule_config, remote_schedule) in schedule_configs.iter().zip(remote_schedules) {
+        expected_ops.extend(build_schedule_diff_op(schedule_config, remote_schedule));
     }
 
     if let Some(remote_state) = remote_state {
@@ -201,6 +295,7 @@ fn build_diff_ops(
         }
     } else {
         expected_ops.push(DiffOp::CreateState);
+        expected_ops.extend(build_alias_diff_ops(&local_aliases, &Some(vec![])));
         expected_ops.sort();
 
         // Create ops will handle tags as it is, so dont need to push tags ops, so return here
@@ -215,10 +310,292 @@ fn build_diff_ops(
         }
     }
 
+    expected_ops.extend(build_alias_diff_ops(&local_aliases, &remote_aliases));
+
     expected_ops.sort();
     Ok(expected_ops)
 }
 
+// Remote rules are in the same order as rule configs. Rules are deleted with delete_all.
+fn build_rule_diff_ops(
+    local_config: &SsConfig,
+    rule_configs: &[RuleConfig],
+    remote_rules: &[Option<Rule>],
+) -> Vec<DiffOp> {
+    rule_configs
+        .iter()
+        .zip(remote_rules)
+        .filter_map(|(rule_config, remote_rule)| {
+            let name = rule_config.rule.rule_name_with_bus();
+            let delete = local_config.delete_all || rule_config.delete;
+
+            match remote_rule {
+                Some(_) if delete => Some(DiffOp::DeleteRule(name)),
+                Some(remote_rule) => {
+                    (*remote_rule != rule_config.rule).then_some(DiffOp::UpdateRule(name))
+                }
+                None if delete => None,
+                None => Some(DiffOp::CreateRule(name)),
+            }
+        })
+        .collect()
+}
+
+fn validate_rules(local_config: &SsConfig) -> Result<()> {
+    let state_name = &local_config.state.name;
+    let mut rule_names = HashSet::new();
+
+    for rule_config in &local_config.rules {
+        let name = rule_config.rule.rule_name_with_bus();
+        if !rule_names.insert(name.clone()) {
+            return Err(FuburaError::Validation(format!(
+                "rule({}) is declared more than once for state machine({})",
+                name, state_name
+            ))
+            .into());
+        }
+        if rule_config.rule.target.is_none() && !rule_config.delete {
+            return Err(FuburaError::Validation(format!(
+                "rule({}) of state machine({}) has no target",
+                name, state_name
+            ))
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+// Aliases are managed only if local state has them, and remote aliases missing in local are deleted.
+fn build_alias_diff_ops(
+    local_aliases: &Option<Vec<StateMachineAlias>>,
+    remote_aliases: &Option<Vec<StateMachineAlias>>,
+) -> Vec<DiffOp> {
+    let Some(local_aliases) = local_aliases else {
+        return vec![];
+    };
+    let remote_aliases = remote_aliases.as_deref().unwrap_or_default();
+
+    let mut created = vec![];
+    let mut updated = vec![];
+    for local_alias in local_aliases {
+        match remote_aliases
+            .iter()
+            .find(|remote_alias| remote_alias.name == local_alias.name)
+        {
+            Some(remote_alias) if remote_alias.is_same_as(local_alias) => {}
+            Some(_) => updated.push(local_alias.name.clone()),
+            None => created.push(local_alias.name.clone()),
+        }
+    }
+
+    let deleted: Vec<String> = remote_aliases
+        .iter()
+        .filter(|remote_alias| {
+            !local_aliases
+                .iter()
+                .any(|local_alias| local_alias.name == remote_alias.name)
+        })
+        .map(|remote_alias| remote_alias.name.clone())
+        .collect();
+
+    let mut diff_ops = vec![];
+    if !created.is_empty() {
+        diff_ops.push(DiffOp::CreateStateAlias(created));
+    }
+    if !updated.is_empty() {
+        diff_ops.push(DiffOp::UpdateStateAlias(updated));
+    }
+    if !deleted.is_empty() {
+        diff_ops.push(DiffOp::DeleteStateAlias(deleted));
+    }
+
+    diff_ops
+}
+
+fn validate_aliases(local_config: &SsConfig, state_arn: &str) -> Result<()> {
+    let state = &local_config.state;
+    let Some(aliases) = &state.aliases else {
+        return Ok(());
+    };
+
+    let mut names = HashSet::new();
+    for alias in aliases {
+        if !names.insert(alias.name.as_str()) {
+            return Err(FuburaError::Validation(format!(
+                "alias '{}' of state machine({}) is defined more than once",
+                alias.name, state.name
+            ))
+            .into());
+        }
+    }
+
+    // Schedule targeting an alias, like "<state machine arn>:<alias name>", should target a managed one
+    for schedule_config in local_config.schedule_configs() {
+        let schedule = &schedule_config.schedule;
+        let Some(alias_name) = schedule
+            .target
+            .arn
+            .strip_prefix(state_arn)
+            .and_then(|suffix| suffix.strip_prefix(':'))
+        else {
+            continue;
+        };
+        if state_machine_version_number(&schedule.target.arn).is_none()
+            && !names.contains(alias_name)
+        {
+            return Err(FuburaError::Validation(format!(
+                "schedule({}) targets alias '{}', which is not in aliases of state machine({})",
+                schedule.name, alias_name, state.name
+            ))
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+fn build_schedule_group_diff_ops(
+    local_config: &ScheduleGroupConfig,
+    remote_group: &Option<ScheduleGroup>,
+) -> Vec<DiffOp> {
+    let Some(remote_group) = remote_group else {
+        return if local_config.delete {
+            vec![]
+        } else {
+            // Create ops will handle tags as it is
+            vec![DiffOp::CreateScheduleGroup]
+        };
+    };
+
+    if local_config.delete {
+        return vec![DiffOp::DeleteScheduleGroup];
+    }
+
+    // Tags are compared in the same way as state machine's
+    build_sfn_tags_diff_ops(&local_config.group.tags, &remote_group.tags)
+        .into_iter()
+        .map(|op| match op {
+            DiffOp::RemoveStateTag(keys) => DiffOp::RemoveScheduleGroupTag(keys),
+            _ => DiffOp::AddScheduleGroupTag,
+        })
+        .collect()
+}
+
+// Log group is managed only if local state has it, and never deleted to keep its logs.
+fn build_log_group_diff_ops(
+    local_config: &SsConfig,
+    remote_log_group: &Option<LogGroup>,
+) -> Vec<DiffOp> {
+    let Some(local_log_group) = &local_config.state.log_group else {
+        return vec![];
+    };
+    if local_config.delete_all {
+        return vec![];
+    }
+
+    match remote_log_group {
+        None => vec![DiffOp::CreateLogGroup],
+        Some(remote_log_group) if remote_log_group != local_log_group => {
+            vec![DiffOp::UpdateLogGroup]
+        }
+        Some(_) => vec![],
+    }
+}
+
+// Deleting a schedule group deletes all schedules in it too.
+// So refuse it unless all of them are going to be deleted by this plan.
+async fn ensure_schedule_group_empty(
+    context: &FuburaContext,
+    ss_configs: &[&SsConfig],
+    schedules: &[&StandaloneScheduleConfig],
+    group_name: &str,
+) -> Result<()> {
+    let mut deleted_schedules = HashSet::new();
+    let mut remaining_schedules = vec![];
+
+    for ss_config in ss_configs {
+        for schedule_config in ss_config.schedule_configs() {
+            let schedule = schedule_config.schedule;
+            if schedule.group_name != group_name {
+                continue;
+            }
+
+            if ss_config.delete_all || schedule_config.delete {
+                deleted_schedules.insert(schedule.name);
+            } else {
+                remaining_schedules.push(schedule.name);
+            }
+        }
+    }
+    for schedule_config in schedules {
+        let schedule = &schedule_config.schedule;
+        if schedule.group_name != group_name {
+            continue;
+        }
+
+        if schedule_config.delete {
+            deleted_schedules.insert(schedule.name.clone());
+        } else {
+            remaining_schedules.push(schedule.name.clone());
+        }
+    }
+
+    for schedule_name in
+        scheduler::list_schedule_names(context.scheduler_client.as_ref(), group_name).await?
+    {
+        if !deleted_schedules.contains(&schedule_name)
+            && !remaining_schedules.contains(&schedule_name)
+        {
+            remaining_schedules.push(schedule_name);
+        }
+    }
+
+    if !remaining_schedules.is_empty() {
+        return Err(FuburaError::Validation(format!(
+            "schedule group '{}' is going to be deleted, but schedules remain in it: {}. delete them first.",
+            group_name,
+            remaining_schedules.join(", ")
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+fn ensure_not_protected(
+    local_config: &SsConfig,
+    remote_state: &Option<StateMachine>,
+    diff_ops: &[DiffOp],
+) -> Result<()> {
+    if !diff_ops.iter().any(|op| op.is_destructive()) {
+        return Ok(());
+    }
+
+    let state_name = &local_config.state.name;
+
+    if local_config.protect {
+        return Err(FuburaError::Validation(format!(
+            "state machine '{}' has `protect: true`; refusing to delete its resources",
+            state_name
+        ))
+        .into());
+    }
+
+    if remote_state
+        .as_ref()
+        .is_some_and(|remote_state| remote_state.is_protected_by_tag())
+    {
+        return Err(FuburaError::Validation(format!(
+            "state machine '{}' is protected by '{}' tag, refuse to delete its resources.",
+            state_name, PROTECTION_TAG_KEY
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
 fn build_sfn_tags_diff_ops(
     local_state_tags: &[ResourceTag],
     remote_state_tags: &[ResourceTag],
@@ -272,12 +649,61 @@ pub fn sort_tags_by_local_tags_order(
     remote_state: Option<StateMachine>,
     local_tags: &[ResourceTag],
 ) -> Option<StateMachine> {
-    if remote_state.is_none() {
-        return remote_state;
+    let mut remote_state = remote_state?;
+    remote_state.tags = sort_tags(remote_state.tags, local_tags);
+
+    Some(remote_state)
+}
+
+// Publish settings are not a part of remote state, so take them from local state
+// to not report them as diff
+fn copy_publish_settings(
+    remote_state: Option<StateMachine>,
+    local_state: &StateMachine,
+) -> Option<StateMachine> {
+    let mut remote_state = remote_state?;
+    remote_state.publish = local_state.publish;
+    remote_state
+        .version_description
+        .clone_from(&local_state.version_description);
+
+    Some(remote_state)
+}
+
+// Sort remote aliases by local aliases order, and take routing order of local alias
+// if it's the same, to make sure the diff result is consistent
+fn sort_aliases(
+    remote_aliases: Vec<StateMachineAlias>,
+    local_aliases: &[StateMachineAlias],
+) -> Vec<StateMachineAlias> {
+    let mut sorted_remote_aliases = vec![];
+
+    for local_alias in local_aliases {
+        if let Some(remote_alias) = remote_aliases
+            .iter()
+            .find(|alias| alias.name == local_alias.name)
+        {
+            if remote_alias.is_same_as(local_alias) {
+                sorted_remote_aliases.push(local_alias.clone());
+            } else {
+                sorted_remote_aliases.push(remote_alias.clone());
+            }
+        }
     }
-    let mut remote_state = remote_state.unwrap();
-    let remote_tags = remote_state.tags;
 
+    for remote_alias in remote_aliases.iter() {
+        if !local_aliases
+            .iter()
+            .any(|alias| alias.name == remote_alias.name)
+        {
+            sorted_remote_aliases.push(remote_alias.clone());
+        }
+    }
+
+    sorted_remote_aliases
+}
+
+fn sort_tags(remote_tags: Vec<ResourceTag>, local_tags: &[ResourceTag]) -> Vec<ResourceTag> {
     let local_tag_keys: HashSet<&String> = local_tags.iter().map(|tag| &tag.key).collect();
     let remote_tag_keys: HashSet<&String> = remote_tags.iter().map(|tag| &tag.key).collect();
     let remote_only_tag_keys = remote_tag_keys.difference(&local_tag_keys);
@@ -295,92 +721,441 @@ pub fn sort_tags_by_local_tags_order(
         }
     }
 
-    remote_state.tags = sorted_remote_tags;
-
-    Some(remote_state)
+    sorted_remote_tags
 }
 
 pub async fn diff(context: &FuburaContext, config: &Config) -> Result<DiffResult> {
     let mut diff_result = DiffResult::default();
 
-    let state_arn_prefix = sts::build_state_arn_prefix(context).await;
-
-    let target_ss_configs = config.target_ss_configs(&context.targets);
+    for resources in config.target_resources_by_deploy_target(&context.targets) {
+        let target_context = context.for_deploy_target(&resources.deploy_target)?;
 
-    for ss_config in target_ss_configs {
-        let state_arn = format!("{}{}", state_arn_prefix, ss_config.state.name);
-
-        info!("Describing state machine: {}", &state_arn);
-        let remote_state =
-            sfn::describe_state_machine_with_tags(&context.sfn_client, &state_arn).await?;
-        let remote_state = sort_tags_by_local_tags_order(remote_state, &ss_config.state.tags);
-
-        info!("Describing schedule: {}", &state_arn);
-        let remote_schedule = if let Some(schedule_config) = &ss_config.schedule {
-            scheduler::get_schedule(
-                &context.scheduler_client,
-                &schedule_config.schedule_name_with_group(),
-            )
-            .await?
+        let target_label = if resources.deploy_target.is_some() {
+            let target_label = sts::build_target_label(target_context).await?;
+            human_println!(context, "\n# {}\n", target_label);
+            Some(target_label)
         } else {
             None
         };
+        diff_result.set_target(target_label);
+
+        diff_ss_configs(target_context, &resources.ss_configs, &mut diff_result).await?;
+        diff_standalone_schedules(target_context, &resources.schedules, &mut diff_result).await?;
+        diff_schedule_groups(
+            target_context,
+            &resources.ss_configs,
+            &resources.schedules,
+            &resources.schedule_groups,
+            &mut diff_result,
+        )
+        .await?;
+    }
+
+    if diff_result.no_change {
+        human_println!(context, "\nNo diff found. Fubura will do nothing.");
+    } else {
+        human_println!(context, "\nFubura will:");
+        for (op, count) in diff_result.summary.iter() {
+            human_println!(context, "    {}: {}", op, count);
+        }
+    }
+
+    Ok(diff_result)
+}
+
+async fn diff_ss_configs(
+    context: &FuburaContext,
+    ss_configs: &[&SsConfig],
+    diff_result: &mut DiffResult,
+) -> Result<()> {
+    let arn_builder = sts::build_arn_builder(context).await?;
+
+    for ss_config in ss_configs {
+        diff_ss_config(context, &arn_builder, ss_config, diff_result)
+            .instrument(telemetry::ss_config_span(ss_config))
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn diff_standalone_schedules(
+    context: &FuburaContext,
+    schedule_configs: &[&StandaloneScheduleConfig],
+    diff_result: &mut DiffResult,
+) -> Result<()> {
+    for schedule_config in schedule_configs {
+        diff_standalone_schedule(context, schedule_config, diff_result)
+            .instrument(telemetry::standalone_schedule_span(schedule_config))
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn diff_standalone_schedule(
+    context: &FuburaContext,
+    schedule_config: &StandaloneScheduleConfig,
+    diff_result: &mut DiffResult,
+) -> Result<()> {
+    let local_schedule = &schedule_config.schedule;
+    let name = schedule_config.schedule_name_with_group();
+
+    info!("Describing schedule: {}", &name);
+    let remote_schedule =
+        scheduler::get_schedule_with_unmodeled_fields(context.scheduler_client.as_ref(), &name)
+            .await?;
+    let mut unmodeled_fields = vec![];
+    let remote_schedule = remote_schedule.map(|(remote_schedule, fields)| {
+        unmodeled_fields.extend(fields.into_iter().map(|f| f.to_string()));
+        remote_schedule
+    });
+    if !schedule_config.delete {
+        check_unmodeled_fields(
+            context,
+            &format!("schedule({})", name),
+            &unmodeled_fields,
+            diff_result,
+        )?;
+    }
+
+    let diff_op = build_schedule_diff_op(&schedule_config.schedule_config(), &remote_schedule);
+    if schedule_config.protect && diff_op.as_ref().is_some_and(|op| op.is_destructive()) {
+        return Err(FuburaError::Validation(format!(
+            "schedule '{}' has `protect: true`; refusing to delete it",
+            name
+        ))
+        .into());
+    }
+    if remote_schedule.is_none() && !schedule_config.delete && is_completed_schedule(local_schedule)
+    {
+        human_println!(
+            context,
+            "Schedule({}) has completed, and been deleted after completion",
+            local_schedule.name
+        );
+    }
+    debug!("schedule name: {}", &name);
+    debug!("generated diff op: {:?}", &diff_op);
+
+    let Some(diff_op) = diff_op else {
+        human_println!(context, "no difference");
+        return Ok(());
+    };
+    diff_result.append_standalone_schedule_diff_op(&name, &diff_op);
+
+    let text_diff = if diff_op.is_destructive() {
+        format!("Schedule({}) is going to be deleted\n", local_schedule.name)
+    } else {
+        let text_diff = format_resource_diff(
+            &local_schedule.name,
+            &serde_json::to_string_pretty(&remote_schedule).unwrap(),
+            &serde_json::to_string_pretty(&Some(local_schedule)).unwrap(),
+        );
+        format!("{}\n", text_diff)
+    };
+    human_println!(context, "{}", text_diff);
+    diff_result.append_text_diff(&name, text_diff);
+
+    Ok(())
+}
 
-        let diff_ops = build_diff_ops(ss_config, &remote_state, &remote_schedule)?;
-        debug!("state machine name: {}", &ss_config.state.name);
+async fn diff_schedule_groups(
+    context: &FuburaContext,
+    ss_configs: &[&SsConfig],
+    schedules: &[&StandaloneScheduleConfig],
+    group_configs: &[&ScheduleGroupConfig],
+    diff_result: &mut DiffResult,
+) -> Result<()> {
+    for group_config in group_configs {
+        let group = &group_config.group;
+        if group.name == DEFAULT_SCHEDULE_GROUP_NAME {
+            return Err(FuburaError::Validation(format!(
+                "schedule group '{}' is managed by AWS, and can't be configured.",
+                DEFAULT_SCHEDULE_GROUP_NAME
+            ))
+            .into());
+        }
+
+        info!("Describing schedule group: {}", &group.name);
+        let remote_group =
+            scheduler::get_schedule_group_with_tags(context.scheduler_client.as_ref(), &group.name)
+                .await?
+                .map(|mut remote_group| {
+                    remote_group.tags = sort_tags(remote_group.tags, &group.tags);
+                    remote_group
+                });
+
+        let diff_ops = build_schedule_group_diff_ops(group_config, &remote_group);
+        if diff_ops.contains(&DiffOp::DeleteScheduleGroup) {
+            ensure_schedule_group_empty(context, ss_configs, schedules, &group.name).await?;
+        }
+        debug!("schedule group name: {}", &group.name);
         debug!("generated diff ops: {:?}", &diff_ops);
 
         for diff_op in diff_ops.iter() {
-            diff_result.append_diff_op(&ss_config.state.name, diff_op)
+            diff_result.append_schedule_group_diff_op(&group.name, diff_op);
         }
 
-        let text_diff = format_config_diff(ss_config, &remote_state, &remote_schedule, &diff_ops);
+        let text_diff = format_schedule_group_diff(group_config, &remote_group, &diff_ops);
         if let Some(text_diff) = text_diff {
-            println!("{}", text_diff);
-            diff_result.append_text_diff(text_diff);
+            human_println!(context, "{}", text_diff);
+            diff_result.append_schedule_group_text_diff(&group.name, text_diff);
         } else {
-            println!("no difference");
-            // do not append empty diff which is too verbose
+            human_println!(context, "no difference");
         }
     }
 
-    if diff_result.no_change {
-        println!("\nNo diff found. Fubura will do nothing.");
-    } else {
-        println!("\nFubura will:");
-        for (op, count) in diff_result.summary.iter() {
-            println!("    {}: {}", op, count);
+    Ok(())
+}
+
+// Update sends the whole configuration, so remote fields fubura doesn't model are reset on it
+fn check_unmodeled_fields(
+    context: &FuburaContext,
+    resource_name: &str,
+    unmodeled_fields: &[String],
+    diff_result: &mut DiffResult,
+) -> Result<()> {
+    if unmodeled_fields.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "{} has remote fields fubura doesn't model, which are reset on update: {}",
+        resource_name,
+        unmodeled_fields.join(", ")
+    );
+    if context.strict {
+        return Err(FuburaError::Validation(message).into());
+    }
+    human_println!(context, "{}", message);
+    diff_result.warnings.push(message);
+
+    Ok(())
+}
+
+// Remote resource which fubura fails to read is reported and kept, to fail after reading
+// the rest of ss config
+fn collect_conversion_error<T>(
+    res: Result<Option<T>>,
+    conversion_errors: &mut Vec<anyhow::Error>,
+) -> Result<Option<T>> {
+    match res {
+        Ok(resource) => Ok(resource),
+        Err(e)
+            if e.chain()
+                .find_map(|e| e.downcast_ref::<FuburaError>())
+                .is_some_and(FuburaError::is_conversion) =>
+        {
+            error!("{:#}", e);
+            conversion_errors.push(e);
+            Ok(None)
         }
+        Err(e) => Err(e),
+    }
+}
+
+async fn diff_ss_config(
+    context: &FuburaContext,
+    arn_builder: &ArnBuilder,
+    ss_config: &SsConfig,
+    diff_result: &mut DiffResult,
+) -> Result<()> {
+    let state_arn = arn_builder.state_machine(&ss_config.state.name);
+
+    if ss_config.state.version_description.is_some() && !ss_config.state.publish {
+        return Err(FuburaError::Validation(format!(
+            "versionDescription of state machine({}) is only used with publish: true",
+            ss_config.state.name
+        ))
+        .into());
+    }
+    validate_aliases(ss_config, &state_arn)?;
+    let ss_config = &ss_config.with_log_group_arn(arn_builder);
+
+    let mut conversion_errors = vec![];
+    info!("Describing state machine: {}", &state_arn);
+    let remote_state = collect_conversion_error(
+        sfn::describe_state_machine_with_unmodeled_fields(context.sfn_client.as_ref(), &state_arn)
+            .await,
+        &mut conversion_errors,
+    )?;
+    let mut unmodeled_fields = vec![];
+    let remote_state = remote_state.map(|(remote_state, fields)| {
+        unmodeled_fields.extend(fields.into_iter().map(|f| format!("state.{}", f)));
+        remote_state
+    });
+    let remote_state = sort_tags_by_local_tags_order(remote_state, &ss_config.state.tags);
+    let mut remote_state = copy_publish_settings(remote_state, &ss_config.state);
+
+    // Aliases are not described unless they are managed
+    if let (Some(remote_state), Some(local_aliases)) =
+        (remote_state.as_mut(), &ss_config.state.aliases)
+        && !ss_config.delete_all
+    {
+        info!("Listing aliases of state machine: {}", &state_arn);
+        let remote_aliases =
+            sfn::list_state_machine_aliases(context.sfn_client.as_ref(), &state_arn).await?;
+        remote_state.aliases = Some(sort_aliases(remote_aliases, local_aliases));
     }
 
-    Ok(diff_result)
+    // Log group is not described unless it is managed
+    let mut remote_log_group = None;
+    if let Some(local_log_group) = &ss_config.state.log_group
+        && !ss_config.delete_all
+    {
+        info!("Describing log group: {}", &local_log_group.name);
+        remote_log_group =
+            logs::describe_log_group(context.logs_client.as_ref(), &local_log_group.name)
+                .await?
+                .map(|mut log_group| {
+                    log_group.tags = sort_tags(log_group.tags, &local_log_group.tags);
+                    log_group
+                });
+        if let Some(remote_state) = remote_state.as_mut() {
+            remote_state.log_group = remote_log_group.clone();
+        }
+    }
+
+    let schedule_configs = ss_config.schedule_configs();
+    let mut schedule_names = HashSet::new();
+    for schedule_config in &schedule_configs {
+        let name = schedule_config.schedule.schedule_name_with_group();
+        if !schedule_names.insert(name.clone()) {
+            return Err(FuburaError::Validation(format!(
+                "schedule({}) is declared more than once for state machine({})",
+                name, ss_config.state.name
+            ))
+            .into());
+        }
+    }
+
+    let mut remote_schedules = vec![];
+    for schedule_config in &schedule_configs {
+        let name = schedule_config.schedule.schedule_name_with_group();
+        info!("Describing schedule: {}", &name);
+        let remote_schedule = collect_conversion_error(
+            scheduler::get_schedule_with_unmodeled_fields(context.scheduler_client.as_ref(), &name)
+                .await,
+            &mut conversion_errors,
+        )?;
+        remote_schedules.push(remote_schedule.map(|(remote_schedule, fields)| {
+            unmodeled_fields.extend(
+                fields
+                    .into_iter()
+                    .map(|f| format!("schedule({}).{}", name, f)),
+            );
+            remote_schedule
+        }));
+    }
+    match conversion_errors.len() {
+        0 => {}
+        1 => return Err(conversion_errors.remove(0)),
+        size => {
+            return Err(anyhow::anyhow!(
+                "failed to read {} remote resources of state machine({}): {}",
+                size,
+                ss_config.state.name,
+                conversion_errors
+                    .iter()
+                    .map(|e| format!("{:#}", e))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+    if !ss_config.delete_all {
+        check_unmodeled_fields(
+            context,
+            &format!("state machine({})", ss_config.state.name),
+            &unmodeled_fields,
+            diff_result,
+        )?;
+    }
+
+    validate_rules(ss_config)?;
+    let rule_configs = ss_config.rule_configs(&state_arn);
+    let mut remote_rules = vec![];
+    for rule_config in &rule_configs {
+        let rule = &rule_config.rule;
+        let name = rule.rule_name_with_bus();
+        info!("Describing rule: {}", &name);
+        remote_rules.push(
+            events::describe_rule_with_target(
+                context.events_client.as_ref(),
+                &name,
+                rule.target_id(),
+            )
+            .await?,
+        );
+    }
+
+    let mut diff_ops = build_diff_ops(ss_config, &remote_state, &remote_schedules)?;
+    diff_ops.extend(build_rule_diff_ops(ss_config, &rule_configs, &remote_rules));
+    diff_ops.extend(build_log_group_diff_ops(ss_config, &remote_log_group));
+    diff_ops.sort();
+    ensure_not_protected(ss_config, &remote_state, &diff_ops)?;
+    for (schedule_config, remote_schedule) in schedule_configs.iter().zip(&remote_schedules) {
+        if remote_schedule.is_none()
+            && !ss_config.delete_all
+            && !schedule_config.delete
+            && is_completed_schedule(&schedule_config.schedule)
+        {
+            human_println!(
+                context,
+                "Schedule({}) has completed, and been deleted after completion",
+                schedule_config.schedule.name
+            );
+        }
+    }
+    debug!("state machine name: {}", &ss_config.state.name);
+    debug!("generated diff ops: {:?}", &diff_ops);
+
+    for diff_op in diff_ops.iter() {
+        diff_result.append_diff_op(&ss_config.state.name, diff_op)
+    }
+
+    let text_diff = format_config_diff(
+        ss_config,
+        &remote_state,
+        &remote_schedules,
+        &rule_configs,
+        &remote_rules,
+        &diff_ops,
+    );
+    if let Some(text_diff) = text_diff {
+        human_println!(context, "{}", text_diff);
+        diff_result.append_text_diff(&ss_config.state.name, text_diff);
+    } else {
+        human_println!(context, "no difference");
+        // do not append empty diff which is too verbose
+    }
+
+    Ok(())
 }
 
+// Mock expectations return SdkError as is
 #[cfg(test)]
+#[allow(clippy::result_large_err)]
 mod test {
     use serde_json::json;
 
     use super::*;
 
-    use crate::types::{DiffOp, Schedule, SsConfig, StateMachine};
+    use crate::events::MockEventsImpl;
+    use crate::logs::MockLogsImpl;
+    use crate::scheduler::MockSchedulerImpl;
+    use crate::sfn::MockSfnImpl;
+    use crate::sts::MockStsImpl;
 
-    use aws_sdk_scheduler::operation::get_schedule::GetScheduleError;
-    use aws_sdk_scheduler::{
-        operation::get_schedule::builders::GetScheduleOutputBuilder, types::builders::TargetBuilder,
+    use crate::types::{
+        DeployTarget, DiffOp, EncryptionConfiguration, EncryptionType, LogGroup, Schedule,
+        ScheduleConfig, ScheduleGroup, ScheduleGroupConfig, SsConfig, StateMachine,
+        StateMachineAlias,
     };
+
     use aws_sdk_sfn::error::SdkError;
     use aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineError;
-    use aws_sdk_sfn::operation::list_tags_for_resource::builders::ListTagsForResourceOutputBuilder;
-    use aws_sdk_sfn::types::LogLevel;
-    use aws_sdk_sfn::types::builders::{
-        CloudWatchLogsLogGroupBuilder, LogDestinationBuilder, LoggingConfigurationBuilder,
-        TagBuilder,
-    };
-    use aws_sdk_sfn::{
-        operation::describe_state_machine::builders::DescribeStateMachineOutputBuilder,
-        primitives::{DateTime, DateTimeFormat},
-        types::StateMachineType,
-    };
     use aws_sdk_sts::operation::get_caller_identity::builders::GetCallerIdentityOutputBuilder;
 
     use aws_smithy_runtime_api::http::{Response, StatusCode};
@@ -390,33 +1165,26 @@ mod test {
     #[test]
     fn test_build_diff_ops_returns_no_diff() {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let remote_state = Some(StateMachine::test_default());
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![]);
     }
 
     #[test]
     fn test_build_diff_ops_returns_create_state() {
-        let local_config = SsConfig {
-            state: StateMachine::test_default(),
-            schedule: None,
-            delete_all: false,
-            delete_schedule: false,
-        };
+        let local_config = SsConfig::test_default();
 
         let remote_state = None;
         let remote_schedule = None;
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![DiffOp::CreateState]);
     }
@@ -424,30 +1192,29 @@ mod test {
     #[test]
     fn test_build_diff_ops_returns_create_state_and_schedule() {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let remote_state = None;
         let remote_schedule = None;
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(
             actual_ops,
-            vec![DiffOp::CreateState, DiffOp::CreateSchedule]
+            vec![
+                DiffOp::CreateState,
+                DiffOp::CreateSchedule("default/HelloWorld".to_string())
+            ]
         );
     }
 
     #[test]
     fn test_build_diff_ops_returns_update_state() {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let mut remote_state = StateMachine::test_default();
@@ -458,7 +1225,7 @@ mod test {
 
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![DiffOp::UpdateState]);
     }
@@ -466,10 +1233,8 @@ mod test {
     #[test]
     fn test_build_diff_ops_returns_update_state_and_add_tag() {
         let mut local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
         local_config.state.tags.push(ResourceTag {
             key: "new_key".to_string(),
@@ -484,7 +1249,7 @@ mod test {
 
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![DiffOp::UpdateState, DiffOp::AddStateTag]);
     }
@@ -492,10 +1257,8 @@ mod test {
     #[test]
     fn test_build_diff_ops_returns_update_state_and_remove_tag() {
         let mut local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
         local_config.state.tags.pop();
 
@@ -507,7 +1270,7 @@ mod test {
 
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(
             actual_ops,
@@ -521,10 +1284,8 @@ mod test {
     #[test]
     fn test_build_diff_ops_returns_update_state_and_add_remove_tag() {
         let mut local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
         local_config.state.tags.pop();
         local_config.state.tags.push(ResourceTag {
@@ -540,7 +1301,7 @@ mod test {
 
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(
             actual_ops,
@@ -555,10 +1316,8 @@ mod test {
     #[test]
     fn test_build_diff_ops_returns_add_tag() {
         let mut local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
         local_config.state.tags.push(ResourceTag {
             key: "new_key".to_string(),
@@ -570,7 +1329,7 @@ mod test {
 
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![DiffOp::AddStateTag]);
     }
@@ -578,10 +1337,8 @@ mod test {
     #[test]
     fn test_build_diff_ops_returns_remove_tag() {
         let mut local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
         local_config.state.tags.pop();
 
@@ -590,7 +1347,7 @@ mod test {
 
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(
             actual_ops,
@@ -601,10 +1358,8 @@ mod test {
     #[test]
     fn test_build_diff_ops_returns_add_remove_tag() {
         let mut local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
         local_config.state.tags.pop();
         local_config.state.tags.push(ResourceTag {
@@ -617,7 +1372,7 @@ mod test {
 
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(
             actual_ops,
@@ -628,13 +1383,38 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_build_diff_ops_returns_no_diff_for_completed_schedule() {
+        let mut schedule = Schedule::test_default();
+        schedule.schedule_expression = "at(2024-01-01T00:00:00)".to_string();
+        schedule.action_after_completion = crate::types::ActionAfterCompletion::Delete;
+        let local_config = SsConfig {
+            schedule: Some(schedule.clone()),
+            ..SsConfig::test_default()
+        };
+
+        let remote_state = Some(StateMachine::test_default());
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[None]).unwrap();
+        assert_eq!(actual_ops, vec![]);
+
+        // schedule kept after completion is created again
+        schedule.action_after_completion = crate::types::ActionAfterCompletion::None;
+        let local_config = SsConfig {
+            schedule: Some(schedule),
+            ..local_config
+        };
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[None]).unwrap();
+        assert_eq!(
+            actual_ops,
+            vec![DiffOp::CreateSchedule("default/HelloWorld".to_string())]
+        );
+    }
+
     #[test]
     fn test_build_diff_ops_returns_update_schedule() {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let remote_state = Some(StateMachine::test_default());
@@ -643,41 +1423,45 @@ mod test {
         remote_schedule.schedule_expression = "rate(1 hour)".to_string();
         let remote_schedule = Some(remote_schedule);
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
-        assert_eq!(actual_ops, vec![DiffOp::UpdateSchedule]);
+        assert_eq!(
+            actual_ops,
+            vec![DiffOp::UpdateSchedule("default/HelloWorld".to_string())]
+        );
     }
 
     #[test]
     fn test_build_diff_ops_with_delete_schedule_flag_returns_delete_schedule() {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
             delete_schedule: true,
+            ..SsConfig::test_default()
         };
 
         let remote_state = Some(StateMachine::test_default());
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
-        assert_eq!(actual_ops, vec![DiffOp::DeleteSchedule]);
+        assert_eq!(
+            actual_ops,
+            vec![DiffOp::DeleteSchedule("default/HelloWorld".to_string())]
+        );
     }
 
     #[test]
     fn test_build_diff_ops_with_delete_schedule_flag_returns_no_diff() {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
-            delete_all: false,
             delete_schedule: true,
+            ..SsConfig::test_default()
         };
 
         let remote_state = Some(StateMachine::test_default());
         let remote_schedule = None;
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![]);
     }
@@ -685,16 +1469,14 @@ mod test {
     #[test]
     fn test_build_diff_ops_with_delete_schedule_flag_returns_error() {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
-            schedule: None,
-            delete_all: false,
             delete_schedule: true,
+            ..SsConfig::test_default()
         };
 
         let remote_state = Some(StateMachine::test_default());
         let remote_schedule = None;
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule);
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]);
         assert!(actual_ops.is_err());
         let err = actual_ops.unwrap_err();
         assert_eq!(
@@ -703,23 +1485,93 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_build_diff_ops_with_multiple_schedules() {
+        let mut nightly = Schedule::test_default();
+        nightly.name = "Nightly".to_string();
+        let mut weekly = Schedule::test_default();
+        weekly.name = "Weekly".to_string();
+        let mut retired = Schedule::test_default();
+        retired.name = "Retired".to_string();
+
+        let local_config = SsConfig {
+            schedule: Some(Schedule::test_default()),
+            schedules: vec![
+                ScheduleConfig {
+                    schedule: nightly.clone(),
+                    delete: false,
+                },
+                ScheduleConfig {
+                    schedule: weekly,
+                    delete: false,
+                },
+                ScheduleConfig {
+                    schedule: retired.clone(),
+                    delete: true,
+                },
+            ],
+            ..SsConfig::test_default()
+        };
+
+        let remote_state = Some(StateMachine::test_default());
+        let mut remote_nightly = nightly;
+        remote_nightly.schedule_expression = "rate(1 hour)".to_string();
+        let remote_schedules = [
+            Some(Schedule::test_default()),
+            Some(remote_nightly),
+            None,
+            Some(retired),
+        ];
+
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedules).unwrap();
+
+        assert_eq!(
+            actual_ops,
+            vec![
+                DiffOp::CreateSchedule("default/Weekly".to_string()),
+                DiffOp::UpdateSchedule("default/Nightly".to_string()),
+                DiffOp::DeleteSchedule("default/Retired".to_string()),
+            ]
+        );
+
+        // delete all removes every existing schedule
+        let local_config = SsConfig {
+            rules: vec![],
+            delete_all: true,
+            ..local_config
+        };
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedules).unwrap();
+
+        assert_eq!(
+            actual_ops,
+            vec![
+                DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
+                DiffOp::DeleteSchedule("default/Nightly".to_string()),
+                DiffOp::DeleteSchedule("default/Retired".to_string()),
+                DiffOp::DeleteState,
+            ]
+        );
+    }
+
     #[test]
     fn test_build_diff_ops_with_delete_all_flag_returns_delete_state_and_schedule() {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
             delete_all: true,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let remote_state = Some(StateMachine::test_default());
         let remote_schedule = Some(Schedule::test_default());
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(
             actual_ops,
-            vec![DiffOp::DeleteSchedule, DiffOp::DeleteState]
+            vec![
+                DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
+                DiffOp::DeleteState
+            ]
         );
     }
 
@@ -727,16 +1579,15 @@ mod test {
     fn test_build_diff_ops_with_delete_all_flag_returns_deletes_state_when_local_schedule_exist_but_remote_not()
      {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
             delete_all: true,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let remote_state = Some(StateMachine::test_default());
         let remote_schedule = None;
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![DiffOp::DeleteState]);
     }
@@ -745,16 +1596,14 @@ mod test {
     fn test_build_diff_ops_with_delete_all_flag_returns_deletes_state_when_local_and_remote_schedule_not_exist()
      {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
-            schedule: None,
             delete_all: true,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let remote_state = Some(StateMachine::test_default());
         let remote_schedule = None;
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![DiffOp::DeleteState]);
     }
@@ -763,16 +1612,15 @@ mod test {
     fn test_build_diff_ops_with_delete_all_flag_returns_no_diff_when_local_resource_exist_but_remote_resources_not_exist()
      {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
             schedule: Some(Schedule::test_default()),
             delete_all: true,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let remote_state = None;
         let remote_schedule = None;
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![]);
     }
@@ -781,293 +1629,1005 @@ mod test {
     fn test_build_diff_ops_with_delete_all_flag_returns_no_diff_when_local_state_exist_but_remote_state_not_exist()
      {
         let local_config = SsConfig {
-            state: StateMachine::test_default(),
-            schedule: None,
             delete_all: true,
-            delete_schedule: false,
+            ..SsConfig::test_default()
         };
 
         let remote_state = None;
         let remote_schedule = None;
 
-        let actual_ops = build_diff_ops(&local_config, &remote_state, &remote_schedule).unwrap();
+        let actual_ops = build_diff_ops(&local_config, &remote_state, &[remote_schedule]).unwrap();
 
         assert_eq!(actual_ops, vec![]);
     }
 
+    #[test]
+    fn test_ensure_not_protected_with_protect_flag_returns_error() {
+        let local_config = SsConfig {
+            schedule: Some(Schedule::test_default()),
+            delete_all: true,
+            protect: true,
+            ..SsConfig::test_default()
+        };
+
+        let remote_state = Some(StateMachine::test_default());
+        let diff_ops = vec![
+            DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteState,
+        ];
+
+        let actual = ensure_not_protected(&local_config, &remote_state, &diff_ops);
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "state machine 'HelloWorld' has `protect: true`; refusing to delete its resources"
+        );
+    }
+
+    #[test]
+    fn test_ensure_not_protected_with_protection_tag_returns_error() {
+        let local_config = SsConfig {
+            schedule: Some(Schedule::test_default()),
+            delete_schedule: true,
+            ..SsConfig::test_default()
+        };
+
+        let mut remote_state = StateMachine::test_default();
+        remote_state.tags.push(ResourceTag {
+            key: PROTECTION_TAG_KEY.to_string(),
+            value: "true".to_string(),
+        });
+        let remote_state = Some(remote_state);
+        let diff_ops = vec![DiffOp::DeleteSchedule("default/HelloWorld".to_string())];
+
+        let actual = ensure_not_protected(&local_config, &remote_state, &diff_ops);
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "state machine 'HelloWorld' is protected by 'fubura:protect' tag, refuse to delete its resources."
+        );
+    }
+
+    #[test]
+    fn test_ensure_not_protected_allows_non_destructive_ops() {
+        let local_config = SsConfig {
+            schedule: Some(Schedule::test_default()),
+            protect: true,
+            ..SsConfig::test_default()
+        };
+
+        let remote_state = Some(StateMachine::test_default());
+        let diff_ops = vec![
+            DiffOp::UpdateState,
+            DiffOp::UpdateSchedule("default/HelloWorld".to_string()),
+        ];
+
+        assert!(ensure_not_protected(&local_config, &remote_state, &diff_ops).is_ok());
+    }
+
     #[tokio::test]
     async fn test_diff_no_diff() {
-        let mut context = FuburaContext::async_default().await;
-
-        context
-            .sts_client
-            .expect_get_caller_identity()
-            .return_once(|| {
-                Ok(GetCallerIdentityOutputBuilder::default()
-                    .account("123456789012".to_string())
-                    .build())
-            });
+        let config = Config {
+            ss_configs: vec![SsConfig {
+                schedule: Some(Schedule::test_default()),
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
 
-        context
-            .sfn_client
-            .expect_describe_state_machine()
-            .with(eq(
-                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-            ))
-            .return_once(|_| {
-                Ok(DescribeStateMachineOutputBuilder::default()
-                    .state_machine_arn(
-                        "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-                    )
-                    .name("HelloWorld".to_string())
-                    .r#type(StateMachineType::Standard)
-                    .definition("{ \"StartAt\": \"FirstState\" }".to_string())
-                    .role_arn(
-                        "arn:aws:iam::123456789012:role/service-role/HelloWorldRole".to_string(),
-                    )
-                    .logging_configuration(
-                        LoggingConfigurationBuilder::default()
-                            .level(LogLevel::All)
-                            .include_execution_data(true)
-                            .destinations(
-                                LogDestinationBuilder::default()
-                                    .cloud_watch_logs_log_group(
-                                        CloudWatchLogsLogGroupBuilder::default()
-                                            .log_group_arn("arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup")
-                                            .build(),
-                                    )
-                                    .build(),
-                            )
-                            .build(),
-                    )
-                    .creation_date(
-                        DateTime::from_str("2021-01-01T00:00:00Z", DateTimeFormat::DateTime)
-                            .unwrap(),
-                    )
-                    .build()
-                    .unwrap())
-            });
+        let context = FuburaContext::from_memory(
+            vec![StateMachine::test_default()],
+            vec![Schedule::test_default()],
+        );
 
-        context
-            .sfn_client
-            .expect_list_tags_for_resource()
-            .with(eq(
-                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-            ))
-            .return_once(|_| {
-                Ok(ListTagsForResourceOutputBuilder::default()
-                    .tags(TagBuilder::default().key("Env").value("Test").build())
-                    .tags(
-                        TagBuilder::default()
-                            .key("Name")
-                            .value("HelloWorld")
-                            .build(),
-                    )
-                    .build())
-            });
+        let diff_result = diff(&context, &config).await.unwrap();
+        assert!(diff_result.no_change);
+    }
 
-        context
-            .scheduler_client
-            .expect_get_schedule()
-            .with(eq("default"), eq("HelloWorld"))
-            .return_once(|_, _| {
-                Ok(GetScheduleOutputBuilder::default()
-                    .arn("arn:aws:scheduler:us-west-2:123456789012:schedule:default/HelloWorld")
-                    .group_name("default")
-                    .name("HelloWorld")
-                    .description("HellowWorld schedule")
-                    .schedule_expression("rate(1 minute)")
-                    .schedule_expression_timezone("UTC")
-                    .state(aws_sdk_scheduler::types::ScheduleState::Enabled)
-                    .target(
-                        TargetBuilder::default()
-                            .arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
-                            .role_arn("arn:aws:iam::123456789012:role/service-role/HelloWorldRole")
-                            .build()
-                            .unwrap(),
-                    )
-                    .build())
-            });
+    #[tokio::test]
+    async fn test_diff_no_diff_with_different_tag_order() {
+        let mut remote_state = StateMachine::test_default();
+        remote_state.tags.reverse();
 
         let config = Config {
             ss_configs: vec![SsConfig {
-                state: StateMachine::test_default(),
                 schedule: Some(Schedule::test_default()),
-                delete_all: false,
-                delete_schedule: false,
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+
+        let context =
+            FuburaContext::from_memory(vec![remote_state], vec![Schedule::test_default()]);
+
+        let diff_result = diff(&context, &config).await.unwrap();
+        assert!(diff_result.no_change);
+    }
+
+    fn alias(name: &str, weights: &[(u32, i32)]) -> StateMachineAlias {
+        StateMachineAlias {
+            name: name.to_string(),
+            description: None,
+            routing_configuration: weights
+                .iter()
+                .map(
+                    |(version, weight)| crate::types::RoutingConfigurationListItem {
+                        state_machine_version_arn: format!(
+                            "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld:{}",
+                            version
+                        ),
+                        weight: *weight,
+                    },
+                )
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_build_diff_ops_returns_alias_ops() {
+        let mut local_config = SsConfig::test_default();
+        local_config.state.aliases = Some(vec![
+            alias("live", &[(1, 90), (2, 10)]),
+            alias("beta", &[(2, 100)]),
+            alias("canary", &[(2, 100)]),
+        ]);
+
+        let mut remote_state = StateMachine::test_default();
+        remote_state.aliases = Some(vec![
+            alias("live", &[(2, 10), (1, 90)]),
+            alias("beta", &[(1, 100)]),
+            alias("old", &[(1, 100)]),
+        ]);
+
+        let actual_ops = build_diff_ops(&local_config, &Some(remote_state), &[None]).unwrap();
+
+        assert_eq!(
+            actual_ops,
+            vec![
+                DiffOp::CreateStateAlias(vec!["canary".to_string()]),
+                DiffOp::UpdateStateAlias(vec!["beta".to_string()]),
+                DiffOp::DeleteStateAlias(vec!["old".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_diff_ops_returns_create_state_and_alias() {
+        let mut local_config = SsConfig::test_default();
+        local_config.state.publish = true;
+        local_config.state.aliases = Some(vec![alias("live", &[(1, 100)])]);
+
+        let actual_ops = build_diff_ops(&local_config, &None, &[None]).unwrap();
+
+        assert_eq!(
+            actual_ops,
+            vec![
+                DiffOp::CreateState,
+                DiffOp::CreateStateAlias(vec!["live".to_string()])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_diff_ops_ignores_unmanaged_aliases() {
+        let local_config = SsConfig::test_default();
+
+        let mut remote_state = StateMachine::test_default();
+        remote_state.aliases = Some(vec![alias("live", &[(1, 100)])]);
+
+        let actual_ops = build_diff_ops(&local_config, &Some(remote_state), &[None]).unwrap();
+
+        assert_eq!(actual_ops, vec![]);
+    }
+
+    #[test]
+    fn test_validate_aliases_with_schedule_targeting_alias() {
+        let state_arn = "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld";
+        let mut local_config = SsConfig {
+            schedule: Some(Schedule::test_default()),
+            ..SsConfig::test_default()
+        };
+        local_config.state.aliases = Some(vec![alias("live", &[(1, 100)])]);
+
+        let schedule = local_config.schedule.as_mut().unwrap();
+        schedule.target.arn = format!("{}:live", state_arn);
+        assert!(validate_aliases(&local_config, state_arn).is_ok());
+
+        let schedule = local_config.schedule.as_mut().unwrap();
+        schedule.target.arn = format!("{}:1", state_arn);
+        assert!(validate_aliases(&local_config, state_arn).is_ok());
+
+        let schedule = local_config.schedule.as_mut().unwrap();
+        schedule.target.arn = format!("{}:beta", state_arn);
+        let actual = validate_aliases(&local_config, state_arn).unwrap_err();
+        assert_eq!(
+            "schedule(HelloWorld) targets alias 'beta', which is not in aliases of state machine(HelloWorld)",
+            actual.to_string()
+        );
+
+        local_config.state.aliases =
+            Some(vec![alias("live", &[(1, 100)]), alias("live", &[(2, 100)])]);
+        let actual = validate_aliases(&local_config, state_arn).unwrap_err();
+        assert_eq!(
+            "alias 'live' of state machine(HelloWorld) is defined more than once",
+            actual.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_diff_no_diff_with_different_routing_order() {
+        let mut remote_state = StateMachine::test_default();
+        remote_state.aliases = Some(vec![alias("live", &[(1, 90), (2, 10)])]);
+
+        let mut local_state = StateMachine::test_default();
+        local_state.aliases = Some(vec![alias("live", &[(2, 10), (1, 90)])]);
+        let mut config = Config {
+            ss_configs: vec![SsConfig {
+                state: local_state,
+                ..SsConfig::test_default()
             }],
+            schedules: vec![],
+            schedule_groups: vec![],
         };
 
+        let context = FuburaContext::from_memory(vec![remote_state], vec![]);
+
         let diff_result = diff(&context, &config).await.unwrap();
         assert!(diff_result.no_change);
+
+        config.ss_configs[0].state.aliases = Some(vec![]);
+        let diff_result = diff(&context, &config).await.unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::DeleteStateAlias(vec!["live".to_string()])],
+            diff_result.detail_diff_ops[0].diff_ops
+        );
     }
 
     #[tokio::test]
-    async fn test_diff_no_diff_with_different_tag_order() {
-        let mut context = FuburaContext::async_default().await;
-
-        context
-            .sts_client
-            .expect_get_caller_identity()
-            .return_once(|| {
-                Ok(GetCallerIdentityOutputBuilder::default()
-                    .account("123456789012".to_string())
-                    .build())
-            });
+    async fn test_diff_no_diff_with_publish() {
+        let mut local_state = StateMachine::test_default();
+        local_state.publish = true;
+        local_state.version_description = Some("deploy abc123".to_string());
 
-        context
-            .sfn_client
-            .expect_describe_state_machine()
-            .with(eq(
-                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-            ))
-            .return_once(|_| {
-                Ok(DescribeStateMachineOutputBuilder::default()
-                    .state_machine_arn(
-                        "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-                    )
-                    .name("HelloWorld".to_string())
-                    .r#type(StateMachineType::Standard)
-                    .definition("{ \"StartAt\": \"FirstState\" }".to_string())
-                    .role_arn(
-                        "arn:aws:iam::123456789012:role/service-role/HelloWorldRole".to_string(),
-                    )
-                    .logging_configuration(
-                        LoggingConfigurationBuilder::default()
-                            .level(LogLevel::All)
-                            .include_execution_data(true)
-                            .destinations(
-                                LogDestinationBuilder::default()
-                                    .cloud_watch_logs_log_group(
-                                        CloudWatchLogsLogGroupBuilder::default()
-                                            .log_group_arn("arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup")
-                                            .build(),
-                                    )
-                                    .build(),
-                            )
-                            .build(),
-                    )
-                    .creation_date(
-                        DateTime::from_str("2021-01-01T00:00:00Z", DateTimeFormat::DateTime)
-                            .unwrap(),
-                    )
-                    .build()
-                    .unwrap())
-            });
+        let mut config = Config {
+            ss_configs: vec![SsConfig {
+                state: local_state,
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
 
-        context
-            .sfn_client
-            .expect_list_tags_for_resource()
-            .with(eq(
-                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
-            ))
-            .return_once(|_| {
-                Ok(ListTagsForResourceOutputBuilder::default()
-                    .tags(
-                        TagBuilder::default()
-                            .key("Name")
-                            .value("HelloWorld")
-                            .build(),
-                    )
-                    .tags(TagBuilder::default().key("Env").value("Test").build())
-                    .build())
-            });
+        let context = FuburaContext::from_memory(vec![StateMachine::test_default()], vec![]);
 
-        context
-            .scheduler_client
-            .expect_get_schedule()
-            .with(eq("default"), eq("HelloWorld"))
-            .return_once(|_, _| {
-                Ok(GetScheduleOutputBuilder::default()
-                    .arn("arn:aws:scheduler:us-west-2:123456789012:schedule:default/HelloWorld")
-                    .group_name("default")
-                    .name("HelloWorld")
-                    .description("HellowWorld schedule")
-                    .schedule_expression("rate(1 minute)")
-                    .schedule_expression_timezone("UTC")
-                    .state(aws_sdk_scheduler::types::ScheduleState::Enabled)
-                    .target(
-                        TargetBuilder::default()
-                            .arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
-                            .role_arn("arn:aws:iam::123456789012:role/service-role/HelloWorldRole")
-                            .build()
-                            .unwrap(),
-                    )
-                    .build())
-            });
+        let diff_result = diff(&context, &config).await.unwrap();
+        assert!(diff_result.no_change);
 
-        let config = Config {
+        config.ss_configs[0].state.publish = false;
+        let actual = diff(&context, &config).await.unwrap_err();
+        assert_eq!(
+            "versionDescription of state machine(HelloWorld) is only used with publish: true",
+            actual.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_diff_encryption_configuration() {
+        let mut remote_state = StateMachine::test_default();
+        remote_state.encryption_configuration = Some(EncryptionConfiguration {
+            kms_key_id: Some("alias/fubura".to_string()),
+            kms_data_key_reuse_period_seconds: Some(300),
+            r#type: EncryptionType::CustomerManagedKmsKey,
+        });
+        let mut config = Config {
             ss_configs: vec![SsConfig {
-                state: StateMachine::test_default(),
-                schedule: Some(Schedule::test_default()),
-                delete_all: false,
-                delete_schedule: false,
+                state: remote_state.clone(),
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+
+        let context = FuburaContext::from_memory(vec![remote_state], vec![]);
+
+        let diff_result = diff(&context, &config).await.unwrap();
+        assert!(diff_result.no_change);
+
+        // Removed on console, or in config
+        config.ss_configs[0].state.encryption_configuration = None;
+        let diff_result = diff(&context, &config).await.unwrap();
+        assert_eq!(1, diff_result.summary["update_state"]);
+    }
+
+    fn log_group_context(log_groups: Vec<LogGroup>) -> FuburaContext {
+        let mut remote_state = StateMachine::test_default();
+        remote_state
+            .logging_configuration
+            .as_mut()
+            .unwrap()
+            .destinations[0]
+            .cloud_watch_logs_log_group
+            .as_mut()
+            .unwrap()
+            .log_group_arn =
+            Some("arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup:*".to_string());
+        let snapshot = crate::memory::MemorySnapshot {
+            account: None,
+            state_machines: vec![remote_state],
+            schedules: vec![],
+            schedule_groups: vec![],
+            state_machine_versions: vec![],
+            executions: vec![],
+            rules: vec![],
+            log_groups,
+        };
+
+        FuburaContext::from_memory_backend(std::sync::Arc::new(
+            crate::memory::MemoryBackend::from_snapshot(snapshot, "us-west-2"),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_diff_log_group() {
+        // Destination is filled with the arn of managed log group
+        let mut local_state = StateMachine::test_default();
+        local_state.log_group = Some(LogGroup::test_default());
+        local_state
+            .logging_configuration
+            .as_mut()
+            .unwrap()
+            .destinations = vec![];
+        let mut config = Config {
+            ss_configs: vec![SsConfig {
+                state: local_state,
+                ..SsConfig::test_default()
             }],
+            schedules: vec![],
+            schedule_groups: vec![],
         };
 
+        let diff_result = diff(&log_group_context(vec![]), &config).await.unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::CreateLogGroup],
+            diff_result.detail_diff_ops[0].diff_ops
+        );
+
+        let context = log_group_context(vec![LogGroup::test_default()]);
         let diff_result = diff(&context, &config).await.unwrap();
         assert!(diff_result.no_change);
+
+        // Retention changed on console, or in config
+        config.ss_configs[0]
+            .state
+            .log_group
+            .as_mut()
+            .unwrap()
+            .retention_in_days = Some(7);
+        let diff_result = diff(&context, &config).await.unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::UpdateLogGroup],
+            diff_result.detail_diff_ops[0].diff_ops
+        );
+
+        // Log group is kept on delete_all
+        config.ss_configs[0].delete_all = true;
+        let diff_result = diff(&context, &config).await.unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::DeleteState],
+            diff_result.detail_diff_ops[0].diff_ops
+        );
     }
 
     #[tokio::test]
     async fn test_create_state_and_schedule() {
-        let mut context = FuburaContext::async_default().await;
-
-        context
-            .sts_client
-            .expect_get_caller_identity()
-            .return_once(|| {
-                Ok(GetCallerIdentityOutputBuilder::default()
-                    .account("123456789012".to_string())
-                    .build())
-            });
+        let config = Config {
+            ss_configs: vec![SsConfig {
+                schedule: Some(Schedule::test_default()),
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+
+        let context = FuburaContext::from_memory(vec![], vec![]);
+
+        let mut actual_diff_result = diff(&context, &config).await.unwrap();
+        // do not check text_diff
+        actual_diff_result.text_diff.clear();
+        actual_diff_result.text_diff_by_state.clear();
+        let mut expected_diff_result = DiffResult::default();
+        expected_diff_result.append_diff_op("HelloWorld", &DiffOp::CreateState);
+        expected_diff_result.append_diff_op(
+            "HelloWorld",
+            &DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+        );
+
+        similar_asserts::assert_eq!(expected_diff_result, actual_diff_result);
+    }
+
+    #[tokio::test]
+    async fn test_diff_refuses_duplicated_schedules() {
+        let config = Config {
+            ss_configs: vec![SsConfig {
+                schedule: Some(Schedule::test_default()),
+                schedules: vec![ScheduleConfig {
+                    schedule: Schedule::test_default(),
+                    delete: false,
+                }],
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+
+        let context = FuburaContext::from_memory(vec![], vec![]);
+
+        let actual = diff(&context, &config).await.unwrap_err();
+
+        assert_eq!(
+            "schedule(default/HelloWorld) is declared more than once for state machine(HelloWorld)",
+            actual.to_string()
+        );
+    }
+
+    fn rule_config(rule: Rule, delete: bool) -> Config {
+        Config {
+            ss_configs: vec![SsConfig {
+                rules: vec![RuleConfig { rule, delete }],
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        }
+    }
+
+    fn rule_context(rules: Vec<Rule>) -> FuburaContext {
+        let snapshot = crate::memory::MemorySnapshot {
+            account: None,
+            state_machines: vec![StateMachine::test_default()],
+            schedules: vec![],
+            schedule_groups: vec![],
+            state_machine_versions: vec![],
+            executions: vec![],
+            rules,
+            log_groups: vec![],
+        };
+
+        FuburaContext::from_memory_backend(std::sync::Arc::new(
+            crate::memory::MemoryBackend::from_snapshot(snapshot, "us-west-2"),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_diff_rule() {
+        // target arn defaults to the state machine
+        let mut local_rule = Rule::test_default();
+        local_rule.target.as_mut().unwrap().arn = None;
+        let config = rule_config(local_rule, false);
+
+        let actual_diff_result = diff(&rule_context(vec![]), &config).await.unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::CreateRule("default/HelloWorldOnUpload".to_string())],
+            actual_diff_result.detail_diff_ops[0].diff_ops
+        );
+        assert_eq!(1, actual_diff_result.summary["create_rule"]);
+
+        let actual_diff_result = diff(&rule_context(vec![Rule::test_default()]), &config)
+            .await
+            .unwrap();
+        assert!(actual_diff_result.no_change);
+
+        let mut remote_rule = Rule::test_default();
+        remote_rule.event_pattern = json!({ "source": ["aws.ec2"] });
+        let actual_diff_result = diff(&rule_context(vec![remote_rule]), &config)
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::UpdateRule("default/HelloWorldOnUpload".to_string())],
+            actual_diff_result.detail_diff_ops[0].diff_ops
+        );
+    }
+
+    #[tokio::test]
+    async fn test_diff_delete_rule() {
+        let config = rule_config(Rule::test_default(), true);
+
+        let actual_diff_result = diff(&rule_context(vec![Rule::test_default()]), &config)
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::DeleteRule("default/HelloWorldOnUpload".to_string())],
+            actual_diff_result.detail_diff_ops[0].diff_ops
+        );
+
+        // already deleted
+        let actual_diff_result = diff(&rule_context(vec![]), &config).await.unwrap();
+        assert!(actual_diff_result.no_change);
+    }
+
+    #[tokio::test]
+    async fn test_diff_refuses_rule_without_target() {
+        let mut rule = Rule::test_default();
+        rule.target = None;
+        let config = rule_config(rule, false);
+
+        let actual = diff(&rule_context(vec![]), &config).await.unwrap_err();
+
+        assert_eq!(
+            "rule(default/HelloWorldOnUpload) of state machine(HelloWorld) has no target",
+            actual.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_state_on_deploy_target() {
+        let mut context = FuburaContext::from_memory(vec![StateMachine::test_default()], vec![]);
+
+        let deploy_target = DeployTarget {
+            region: "us-west-2".to_string(),
+            role_arn: Some("arn:aws:iam::210987654321:role/fubura".to_string()),
+            external_id: None,
+        };
+        let config = Config {
+            ss_configs: vec![SsConfig {
+                deploy_target: Some(deploy_target),
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+        context.prepare_deploy_targets(&config).await.unwrap();
+
+        let mut actual_diff_result = diff(&context, &config).await.unwrap();
+        // do not check text_diff
+        actual_diff_result.text_diff.clear();
+        actual_diff_result.text_diff_by_state.clear();
+        let mut expected_diff_result = DiffResult::default();
+        expected_diff_result.set_target(Some("210987654321/us-west-2".to_string()));
+        expected_diff_result.append_diff_op("HelloWorld", &DiffOp::CreateState);
+
+        similar_asserts::assert_eq!(expected_diff_result, actual_diff_result);
+    }
+
+    fn schedule_group_context(
+        schedule_groups: Vec<ScheduleGroup>,
+        schedules: Vec<Schedule>,
+    ) -> FuburaContext {
+        let snapshot = crate::memory::MemorySnapshot {
+            account: None,
+            state_machines: vec![StateMachine::test_default()],
+            schedules,
+            schedule_groups,
+            state_machine_versions: vec![],
+            executions: vec![],
+            rules: vec![],
+            log_groups: vec![],
+        };
+
+        FuburaContext::from_memory_backend(std::sync::Arc::new(
+            crate::memory::MemoryBackend::from_snapshot(snapshot, "us-west-2"),
+        ))
+    }
+
+    fn batch_schedule() -> Schedule {
+        let mut schedule = Schedule::test_default();
+        schedule.group_name = "batch".to_string();
+        schedule
+    }
+
+    fn schedule_group_config(
+        schedule: Schedule,
+        delete_schedule: bool,
+        delete_group: bool,
+    ) -> Config {
+        Config {
+            ss_configs: vec![SsConfig {
+                schedule: Some(schedule),
+                delete_schedule,
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![ScheduleGroupConfig {
+                group: ScheduleGroup::test_default(),
+                delete: delete_group,
+                deploy_target: None,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_schedule_group_with_schedule() {
+        let config = schedule_group_config(batch_schedule(), false, false);
+        let context = schedule_group_context(vec![], vec![]);
+
+        let mut actual_diff_result = diff(&context, &config).await.unwrap();
+        actual_diff_result.text_diff.clear();
+        actual_diff_result.text_diff_by_state.clear();
+        actual_diff_result.text_diff_by_group.clear();
+        let mut expected_diff_result = DiffResult::default();
+        expected_diff_result.append_diff_op(
+            "HelloWorld",
+            &DiffOp::CreateSchedule("batch/HelloWorld".to_string()),
+        );
+        expected_diff_result.append_schedule_group_diff_op("batch", &DiffOp::CreateScheduleGroup);
+
+        similar_asserts::assert_eq!(expected_diff_result, actual_diff_result);
+    }
+
+    #[tokio::test]
+    async fn test_update_schedule_group_tags() {
+        let config = schedule_group_config(batch_schedule(), false, false);
+        let remote_group = ScheduleGroup {
+            name: "batch".to_string(),
+            tags: vec![ResourceTag {
+                key: "Env".to_string(),
+                value: "Prod".to_string(),
+            }],
+        };
+        let context = schedule_group_context(vec![remote_group], vec![batch_schedule()]);
+
+        let actual_diff_result = diff(&context, &config).await.unwrap();
+
+        similar_asserts::assert_eq!(
+            vec![
+                DiffOp::AddScheduleGroupTag,
+                DiffOp::RemoveScheduleGroupTag(vec!["Env".to_string()])
+            ],
+            actual_diff_result.detail_schedule_group_diff_ops[0].diff_ops
+        );
+        similar_asserts::assert_eq!(
+            vec![DiffOp::UpdateScheduleGroup],
+            actual_diff_result.schedule_group_diff_ops[0].diff_ops
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_schedule_group_with_its_schedules() {
+        let config = schedule_group_config(batch_schedule(), true, true);
+        let context =
+            schedule_group_context(vec![ScheduleGroup::test_default()], vec![batch_schedule()]);
+
+        let mut actual_diff_result = diff(&context, &config).await.unwrap();
+        actual_diff_result.text_diff.clear();
+        actual_diff_result.text_diff_by_state.clear();
+        actual_diff_result.text_diff_by_group.clear();
+        let mut expected_diff_result = DiffResult::default();
+        expected_diff_result.append_diff_op(
+            "HelloWorld",
+            &DiffOp::DeleteSchedule("batch/HelloWorld".to_string()),
+        );
+        expected_diff_result.append_schedule_group_diff_op("batch", &DiffOp::DeleteScheduleGroup);
+
+        similar_asserts::assert_eq!(expected_diff_result, actual_diff_result);
+    }
+
+    fn standalone_schedule_config(schedule: Schedule, delete: bool) -> Config {
+        Config {
+            ss_configs: vec![],
+            schedules: vec![StandaloneScheduleConfig {
+                schedule,
+                delete,
+                protect: false,
+                deploy_target: None,
+            }],
+            schedule_groups: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diff_standalone_schedule() {
+        let mut schedule = batch_schedule();
+        schedule.target.arn = "arn:aws:lambda:us-west-2:123456789012:function:Nightly".to_string();
+        let config = standalone_schedule_config(schedule.clone(), false);
+
+        let context = schedule_group_context(vec![ScheduleGroup::test_default()], vec![]);
+        let mut actual_diff_result = diff(&context, &config).await.unwrap();
+        actual_diff_result.text_diff.clear();
+        actual_diff_result.text_diff_by_state.clear();
+        let mut expected_diff_result = DiffResult::default();
+        expected_diff_result.append_standalone_schedule_diff_op(
+            "batch/HelloWorld",
+            &DiffOp::CreateSchedule("batch/HelloWorld".to_string()),
+        );
+        similar_asserts::assert_eq!(expected_diff_result, actual_diff_result);
+
+        let context =
+            schedule_group_context(vec![ScheduleGroup::test_default()], vec![schedule.clone()]);
+        let actual_diff_result = diff(&context, &config).await.unwrap();
+        assert!(actual_diff_result.no_change);
+
+        let mut remote_schedule = schedule.clone();
+        remote_schedule.schedule_expression = "rate(1 hour)".to_string();
+        let context =
+            schedule_group_context(vec![ScheduleGroup::test_default()], vec![remote_schedule]);
+        let actual_diff_result = diff(&context, &config).await.unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::UpdateSchedule("batch/HelloWorld".to_string())],
+            actual_diff_result.detail_diff_ops[0].diff_ops
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_protected_standalone_schedule() {
+        let mut config = standalone_schedule_config(batch_schedule(), true);
+        let context =
+            schedule_group_context(vec![ScheduleGroup::test_default()], vec![batch_schedule()]);
+
+        let actual_diff_result = diff(&context, &config).await.unwrap();
+        similar_asserts::assert_eq!(
+            vec![DiffOp::DeleteSchedule("batch/HelloWorld".to_string())],
+            actual_diff_result.detail_diff_ops[0].diff_ops
+        );
+
+        config.schedules[0].protect = true;
+        let actual = diff(&context, &config).await.unwrap_err();
+        assert_eq!(
+            "schedule 'batch/HelloWorld' has `protect: true`; refusing to delete it",
+            actual.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_schedule_group_refuses_remaining_schedules() {
+        let mut other_schedule = batch_schedule();
+        other_schedule.name = "Other".to_string();
+        let config = schedule_group_config(batch_schedule(), true, true);
+        let context = schedule_group_context(
+            vec![ScheduleGroup::test_default()],
+            vec![batch_schedule(), other_schedule],
+        );
+
+        let actual = diff(&context, &config).await.unwrap_err();
+        similar_asserts::assert_eq!(
+            "schedule group 'batch' is going to be deleted, but schedules remain in it: Other. delete them first.",
+            actual.to_string()
+        );
+
+        // schedule kept in config also remains
+        let config = schedule_group_config(batch_schedule(), false, true);
+        let context =
+            schedule_group_context(vec![ScheduleGroup::test_default()], vec![batch_schedule()]);
+
+        let actual = diff(&context, &config).await.unwrap_err();
+        assert!(
+            actual
+                .to_string()
+                .contains("schedules remain in it: HelloWorld")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_schedule_group_is_not_configurable() {
+        let mut config = schedule_group_config(Schedule::test_default(), false, false);
+        config.schedule_groups[0].group.name = "default".to_string();
+        let context = schedule_group_context(vec![], vec![Schedule::test_default()]);
+
+        let actual = diff(&context, &config).await.unwrap_err();
+        assert!(
+            actual
+                .to_string()
+                .starts_with("schedule group 'default' is managed by AWS")
+        );
+    }
 
-        context
-            .sfn_client
+    // Following tests stay on mocks. The memory backend keeps resources as fubura models and
+    // answers with responses rebuilt from them, so it has no way to return service errors other
+    // than missing resources, responses fubura can't read, or fields it doesn't model.
+
+    #[tokio::test]
+    async fn test_diff_fails_on_unexpected_error() {
+        let mut sts_client = MockStsImpl::default();
+        let mut sfn_client = MockSfnImpl::default();
+
+        sts_client.expect_get_caller_identity().return_once(|| {
+            Ok(GetCallerIdentityOutputBuilder::default()
+                .account("123456789012".to_string())
+                .build())
+        });
+
+        sfn_client
             .expect_describe_state_machine()
             .with(eq(
                 "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld",
             ))
             .return_once(|_| {
                 Err(SdkError::service_error(
-                    DescribeStateMachineError::StateMachineDoesNotExist(
-                        aws_sdk_sfn::types::error::StateMachineDoesNotExist::builder().build(),
+                    DescribeStateMachineError::InvalidArn(
+                        aws_sdk_sfn::types::error::InvalidArn::builder()
+                            .message("Invalid Arn")
+                            .build(),
                     ),
-                    Response::new(StatusCode::try_from(404).unwrap(), SdkBody::empty()),
+                    Response::new(StatusCode::try_from(400).unwrap(), SdkBody::empty()),
                 ))
             });
 
-        context
-            .scheduler_client
+        let config = Config {
+            ss_configs: vec![SsConfig::test_default()],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+
+        let context = FuburaContext::from_mocks(
+            MockSchedulerImpl::default(),
+            sfn_client,
+            sts_client,
+            MockEventsImpl::default(),
+            MockLogsImpl::default(),
+        );
+
+        let actual = diff(&context, &config).await;
+        assert!(
+            actual
+                .unwrap_err()
+                .to_string()
+                .starts_with("failed to describe state machine")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_diff_reports_every_conversion_error_of_ss_config() {
+        let mut sts_client = MockStsImpl::default();
+        let mut sfn_client = MockSfnImpl::default();
+        let mut scheduler_client = MockSchedulerImpl::default();
+
+        sts_client.expect_get_caller_identity().return_once(|| {
+            Ok(GetCallerIdentityOutputBuilder::default()
+                .account("123456789012".to_string())
+                .build())
+        });
+
+        let state = StateMachine::test_default();
+        let output =
+            aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput::builder()
+                .state_machine_arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
+                .name(&state.name)
+                .definition("{")
+                .role_arn(&state.role_arn)
+                .r#type(state.r#type.clone().into())
+                .creation_date(aws_sdk_sfn::primitives::DateTime::from_secs(0))
+                .build()
+                .unwrap();
+        sfn_client
+            .expect_describe_state_machine()
+            .return_once(move |_| Ok(output));
+        sfn_client.expect_list_tags_for_resource().return_once(|_| {
+            Ok(
+                aws_sdk_sfn::operation::list_tags_for_resource::ListTagsForResourceOutput::builder(
+                )
+                .build(),
+            )
+        });
+
+        // Both schedules are returned without target, the second one is also read
+        scheduler_client
             .expect_get_schedule()
-            .with(eq("default"), eq("HelloWorld"))
-            .return_once(|_, _| {
-                Err(aws_sdk_scheduler::error::SdkError::service_error(
-                    GetScheduleError::ResourceNotFoundException(
-                        aws_sdk_scheduler::types::error::ResourceNotFoundException::builder()
-                            .message("Resource not found")
-                            .build()
-                            .unwrap(),
-                    ),
-                    Response::new(StatusCode::try_from(404).unwrap(), SdkBody::empty()),
-                ))
+            .times(2)
+            .returning(|group_name, name| {
+                Ok(
+                    aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput::builder()
+                        .group_name(group_name)
+                        .name(name)
+                        .schedule_expression("rate(1 minute)")
+                        .build(),
+                )
             });
 
         let config = Config {
             ss_configs: vec![SsConfig {
-                state: StateMachine::test_default(),
+                state,
                 schedule: Some(Schedule::test_default()),
-                delete_all: false,
-                delete_schedule: false,
+                schedules: vec![ScheduleConfig {
+                    schedule: Schedule {
+                        name: "HelloWorld2".to_string(),
+                        ..Schedule::test_default()
+                    },
+                    delete: false,
+                }],
+                ..SsConfig::test_default()
             }],
+            schedules: vec![],
+            schedule_groups: vec![],
         };
 
-        let mut actual_diff_result = diff(&context, &config).await.unwrap();
-        actual_diff_result.text_diff.clear(); // do not check text_diff
-        let mut expected_diff_result = DiffResult::default();
-        expected_diff_result.append_diff_op("HelloWorld", &DiffOp::CreateState);
-        expected_diff_result.append_diff_op("HelloWorld", &DiffOp::CreateSchedule);
+        let context = FuburaContext::from_mocks(
+            scheduler_client,
+            sfn_client,
+            sts_client,
+            MockEventsImpl::default(),
+            MockLogsImpl::default(),
+        );
 
-        similar_asserts::assert_eq!(expected_diff_result, actual_diff_result);
+        let actual = diff(&context, &config).await.unwrap_err();
+        assert_eq!(
+            "failed to read 3 remote resources of state machine(HelloWorld): failed to read state machine(arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld): definition is not valid json: EOF while parsing an object at line 1 column 1, failed to read schedule(default/HelloWorld): target is missing in GetSchedule response, failed to read schedule(default/HelloWorld2): target is missing in GetSchedule response",
+            actual.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_diff_warns_on_unmodeled_fields() {
+        let mut sts_client = MockStsImpl::default();
+        let mut sfn_client = MockSfnImpl::default();
+
+        sts_client.expect_get_caller_identity().returning(|| {
+            Ok(GetCallerIdentityOutputBuilder::default()
+                .account("123456789012".to_string())
+                .build())
+        });
+
+        // Step Functions has a second log destination, which fubura can't keep
+        let state = StateMachine::test_default();
+        let logging_configuration: aws_sdk_sfn::types::LoggingConfiguration = state
+            .logging_configuration
+            .clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let destination = logging_configuration.destinations()[0].clone();
+        let output =
+            aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput::builder()
+                .state_machine_arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
+                .name(&state.name)
+                .definition(serde_json::to_string(&state.definition).unwrap())
+                .role_arn(&state.role_arn)
+                .r#type(state.r#type.clone().into())
+                .logging_configuration(
+                    aws_sdk_sfn::types::LoggingConfiguration::builder()
+                        .level(aws_sdk_sfn::types::LogLevel::All)
+                        .include_execution_data(true)
+                        .destinations(destination.clone())
+                        .destinations(destination)
+                        .build(),
+                )
+                .creation_date(aws_sdk_sfn::primitives::DateTime::from_secs(0))
+                .build()
+                .unwrap();
+        sfn_client
+            .expect_describe_state_machine()
+            .returning(move |_| Ok(output.clone()));
+        let tags: Vec<aws_sdk_sfn::types::Tag> =
+            state.tags.iter().cloned().map(|t| t.into()).collect();
+        sfn_client
+            .expect_list_tags_for_resource()
+            .returning(move |_| {
+                Ok(
+                aws_sdk_sfn::operation::list_tags_for_resource::ListTagsForResourceOutput::builder()
+                    .set_tags(Some(tags.clone()))
+                    .build(),
+            )
+            });
+
+        let config = Config {
+            ss_configs: vec![SsConfig {
+                state,
+                ..SsConfig::test_default()
+            }],
+            schedules: vec![],
+            schedule_groups: vec![],
+        };
+
+        let mut context = FuburaContext::from_mocks(
+            MockSchedulerImpl::default(),
+            sfn_client,
+            sts_client,
+            MockEventsImpl::default(),
+            MockLogsImpl::default(),
+        );
+
+        // Only warned by default
+        let actual = diff(&context, &config).await.unwrap();
+        assert_eq!(
+            vec![
+                "state machine(HelloWorld) has remote fields fubura doesn't model, which are reset on update: state.loggingConfiguration"
+            ],
+            actual.warnings
+        );
+
+        context.strict = true;
+        let actual = diff(&context, &config).await.unwrap_err();
+        assert_eq!(
+            "state machine(HelloWorld) has remote fields fubura doesn't model, which are reset on update: state.loggingConfiguration",
+            actual.to_string()
+        );
     }
 }
@@ -0,0 +1,205 @@
+use std::fmt;
+
+use aws_smithy_types::error::metadata::ProvideErrorMetadata;
+
+/// Category of error, which decides exit code of fubura.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ErrorCategory {
+    Unknown,
+    Config,
+    Validation,
+    Credential,
+    Throttling,
+    AccessDenied,
+    Conflict,
+    Cancelled,
+    CanaryFailed,
+}
+
+impl ErrorCategory {
+    // Exit code 2 is left for usage errors reported by clap.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorCategory::Unknown => 1,
+            ErrorCategory::Config => 3,
+            ErrorCategory::Validation => 4,
+            ErrorCategory::Credential => 5,
+            ErrorCategory::Throttling => 6,
+            ErrorCategory::AccessDenied => 7,
+            ErrorCategory::Conflict => 8,
+            ErrorCategory::Cancelled => 9,
+            ErrorCategory::CanaryFailed => 10,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::Unknown => "unknown",
+            ErrorCategory::Config => "config",
+            ErrorCategory::Validation => "validation",
+            ErrorCategory::Credential => "credential",
+            ErrorCategory::Throttling => "throttling",
+            ErrorCategory::AccessDenied => "access_denied",
+            ErrorCategory::Conflict => "conflict",
+            ErrorCategory::Cancelled => "cancelled",
+            ErrorCategory::CanaryFailed => "canary_failed",
+        }
+    }
+
+    /// Category of given error, taken from the first `FuburaError` in its chain.
+    pub fn of(err: &anyhow::Error) -> Self {
+        err.chain()
+            .find_map(|e| e.downcast_ref::<FuburaError>())
+            .map_or(ErrorCategory::Unknown, FuburaError::category)
+    }
+}
+
+/// Errors fubura reports, on converting resources between fubura config and AWS
+/// or on running commands.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum FuburaError {
+    // field fubura requires is not in AWS response
+    MissingField {
+        resource: &'static str,
+        field: &'static str,
+    },
+    InvalidDefinition(String),
+    MissingLogGroupArn,
+    TooManyLogDestinations(usize),
+    InvalidDateTime(String),
+    Config(String),
+    Validation(String),
+    Credential(String),
+    Throttling(String),
+    AccessDenied(String),
+    Conflict(String),
+    Cancelled(String),
+    // canary version failed and alias has been rolled back
+    CanaryFailed(String),
+}
+
+impl FuburaError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            FuburaError::MissingLogGroupArn | FuburaError::TooManyLogDestinations(_) => {
+                ErrorCategory::Validation
+            }
+            FuburaError::Config(_) => ErrorCategory::Config,
+            FuburaError::Validation(_) => ErrorCategory::Validation,
+            FuburaError::Credential(_) => ErrorCategory::Credential,
+            FuburaError::Throttling(_) => ErrorCategory::Throttling,
+            FuburaError::AccessDenied(_) => ErrorCategory::AccessDenied,
+            FuburaError::Conflict(_) => ErrorCategory::Conflict,
+            FuburaError::Cancelled(_) => ErrorCategory::Cancelled,
+            FuburaError::CanaryFailed(_) => ErrorCategory::CanaryFailed,
+            FuburaError::MissingField { .. }
+            | FuburaError::InvalidDefinition(_)
+            | FuburaError::InvalidDateTime(_) => ErrorCategory::Unknown,
+        }
+    }
+
+    /// Whether error is raised on converting AWS response to fubura resource.
+    pub fn is_conversion(&self) -> bool {
+        matches!(
+            self,
+            FuburaError::MissingField { .. }
+                | FuburaError::InvalidDefinition(_)
+                | FuburaError::MissingLogGroupArn
+                | FuburaError::TooManyLogDestinations(_)
+                | FuburaError::InvalidDateTime(_)
+        )
+    }
+
+    /// Build error from failed AWS call, categorized by its error code.
+    pub fn from_aws_error(err: &impl ProvideErrorMetadata, message: String) -> anyhow::Error {
+        let error = match err.code() {
+            Some(
+                "ThrottlingException"
+                | "Throttling"
+                | "TooManyRequestsException"
+                | "RequestLimitExceeded",
+            ) => FuburaError::Throttling(message),
+            Some("AccessDeniedException" | "AccessDenied" | "UnauthorizedOperation") => {
+                FuburaError::AccessDenied(message)
+            }
+            Some(
+                "ConflictException"
+                | "StateMachineAlreadyExists"
+                | "StateMachineDeleting"
+                | "ConflictingOperationException",
+            ) => FuburaError::Conflict(message),
+            Some(
+                "ExpiredToken"
+                | "ExpiredTokenException"
+                | "InvalidClientTokenId"
+                | "UnrecognizedClientException",
+            ) => FuburaError::Credential(message),
+            _ => return anyhow::anyhow!(message),
+        };
+
+        error.into()
+    }
+}
+
+impl fmt::Display for FuburaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FuburaError::MissingField { resource, field } => {
+                write!(f, "{} is missing in {}", field, resource)
+            }
+            FuburaError::InvalidDefinition(e) => {
+                write!(f, "definition is not valid json: {}", e)
+            }
+            FuburaError::MissingLogGroupArn => {
+                write!(f, "log_group_arn is required for CloudWatchLogsLogGroup")
+            }
+            FuburaError::TooManyLogDestinations(size) => {
+                write!(f, "destinations size is limited to 1, but got {}", size)
+            }
+            FuburaError::InvalidDateTime(e) => write!(f, "invalid datetime: {}", e),
+            FuburaError::Config(message)
+            | FuburaError::Validation(message)
+            | FuburaError::Credential(message)
+            | FuburaError::Throttling(message)
+            | FuburaError::AccessDenied(message)
+            | FuburaError::Conflict(message)
+            | FuburaError::Cancelled(message)
+            | FuburaError::CanaryFailed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for FuburaError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use aws_smithy_types::error::ErrorMetadata;
+
+    #[test]
+    fn test_category_of_aws_error() {
+        let err = |code| ErrorMetadata::builder().code(code).build();
+
+        let actual = FuburaError::from_aws_error(&err("ThrottlingException"), "".to_string());
+        assert_eq!(ErrorCategory::Throttling, ErrorCategory::of(&actual));
+
+        let actual = FuburaError::from_aws_error(&err("AccessDeniedException"), "".to_string());
+        assert_eq!(ErrorCategory::AccessDenied, ErrorCategory::of(&actual));
+
+        let actual = FuburaError::from_aws_error(&err("ConflictException"), "".to_string());
+        assert_eq!(ErrorCategory::Conflict, ErrorCategory::of(&actual));
+
+        let actual = FuburaError::from_aws_error(&err("InvalidArn"), "".to_string());
+        assert_eq!(ErrorCategory::Unknown, ErrorCategory::of(&actual));
+    }
+
+    #[test]
+    fn test_category_of_wrapped_error() {
+        let err = anyhow::Error::new(FuburaError::TooManyLogDestinations(2))
+            .context("failed to create state machine(HelloWorld)");
+
+        assert_eq!(ErrorCategory::Validation, ErrorCategory::of(&err));
+        assert_eq!(4, ErrorCategory::of(&err).exit_code());
+    }
+}
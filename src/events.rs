@@ -0,0 +1,513 @@
+// Mocks of EventsImpl and their expectations return SdkError as is
+#![cfg_attr(test, allow(clippy::result_large_err))]
+
+use anyhow::{Result, bail};
+use aws_sdk_eventbridge as eventbridge;
+use aws_sdk_eventbridge::operation::delete_rule::{DeleteRuleError, DeleteRuleOutput};
+use aws_sdk_eventbridge::operation::describe_rule::{DescribeRuleError, DescribeRuleOutput};
+use aws_sdk_eventbridge::operation::list_targets_by_rule::{
+    ListTargetsByRuleError, ListTargetsByRuleOutput,
+};
+use aws_sdk_eventbridge::operation::put_rule::builders::PutRuleInputBuilder;
+use aws_sdk_eventbridge::operation::put_rule::{PutRuleError, PutRuleInput, PutRuleOutput};
+use aws_sdk_eventbridge::operation::put_targets::builders::PutTargetsInputBuilder;
+use aws_sdk_eventbridge::operation::put_targets::{
+    PutTargetsError, PutTargetsInput, PutTargetsOutput,
+};
+use aws_sdk_eventbridge::operation::remove_targets::{RemoveTargetsError, RemoveTargetsOutput};
+
+#[allow(unused_imports)]
+use mockall::automock;
+
+use crate::context::BoxFuture;
+use crate::error::FuburaError;
+use crate::telemetry::traced;
+use crate::types::{Rule, RuleTarget};
+
+/// EventBridge operations used by fubura.
+///
+/// Implement this to inject your own client into `FuburaContext::new`.
+pub trait Events: Send + Sync {
+    fn describe_rule<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+    ) -> BoxFuture<'a, Result<DescribeRuleOutput, eventbridge::error::SdkError<DescribeRuleError>>>;
+
+    fn list_targets_by_rule<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+        next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<ListTargetsByRuleOutput, eventbridge::error::SdkError<ListTargetsByRuleError>>,
+    >;
+
+    fn put_rule<'a>(
+        &'a self,
+        rule: &'a Rule,
+    ) -> BoxFuture<'a, Result<PutRuleOutput, eventbridge::error::SdkError<PutRuleError>>>;
+
+    fn put_targets<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+        target: &'a RuleTarget,
+    ) -> BoxFuture<'a, Result<PutTargetsOutput, eventbridge::error::SdkError<PutTargetsError>>>;
+
+    fn remove_targets<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+        target_ids: &'a [String],
+    ) -> BoxFuture<'a, Result<RemoveTargetsOutput, eventbridge::error::SdkError<RemoveTargetsError>>>;
+
+    fn delete_rule<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+    ) -> BoxFuture<'a, Result<DeleteRuleOutput, eventbridge::error::SdkError<DeleteRuleError>>>;
+}
+
+// Implements `Events` by delegating to inherent methods of the given type,
+// which is shared by the aws-sdk based client and its mock.
+macro_rules! impl_events {
+    ($impl:ty) => {
+        impl Events for $impl {
+            fn describe_rule<'a>(
+                &'a self,
+                event_bus_name: &'a str,
+                rule_name: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<DescribeRuleOutput, eventbridge::error::SdkError<DescribeRuleError>>,
+            > {
+                Box::pin(<$impl>::describe_rule(self, event_bus_name, rule_name))
+            }
+
+            fn list_targets_by_rule<'a>(
+                &'a self,
+                event_bus_name: &'a str,
+                rule_name: &'a str,
+                next_token: Option<String>,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    ListTargetsByRuleOutput,
+                    eventbridge::error::SdkError<ListTargetsByRuleError>,
+                >,
+            > {
+                Box::pin(<$impl>::list_targets_by_rule(
+                    self,
+                    event_bus_name,
+                    rule_name,
+                    next_token,
+                ))
+            }
+
+            fn put_rule<'a>(
+                &'a self,
+                rule: &'a Rule,
+            ) -> BoxFuture<'a, Result<PutRuleOutput, eventbridge::error::SdkError<PutRuleError>>>
+            {
+                Box::pin(<$impl>::put_rule(self, rule))
+            }
+
+            fn put_targets<'a>(
+                &'a self,
+                event_bus_name: &'a str,
+                rule_name: &'a str,
+                target: &'a RuleTarget,
+            ) -> BoxFuture<
+                'a,
+                Result<PutTargetsOutput, eventbridge::error::SdkError<PutTargetsError>>,
+            > {
+                Box::pin(<$impl>::put_targets(
+                    self,
+                    event_bus_name,
+                    rule_name,
+                    target,
+                ))
+            }
+
+            fn remove_targets<'a>(
+                &'a self,
+                event_bus_name: &'a str,
+                rule_name: &'a str,
+                target_ids: &'a [String],
+            ) -> BoxFuture<
+                'a,
+                Result<RemoveTargetsOutput, eventbridge::error::SdkError<RemoveTargetsError>>,
+            > {
+                Box::pin(<$impl>::remove_targets(
+                    self,
+                    event_bus_name,
+                    rule_name,
+                    target_ids,
+                ))
+            }
+
+            fn delete_rule<'a>(
+                &'a self,
+                event_bus_name: &'a str,
+                rule_name: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<DeleteRuleOutput, eventbridge::error::SdkError<DeleteRuleError>>,
+            > {
+                Box::pin(<$impl>::delete_rule(self, event_bus_name, rule_name))
+            }
+        }
+    };
+}
+
+impl_events!(EventsImpl);
+#[cfg(test)]
+impl_events!(MockEventsImpl);
+
+pub struct EventsImpl {
+    inner: eventbridge::Client,
+}
+
+#[cfg_attr(test, automock)]
+impl EventsImpl {
+    pub fn new(inner: eventbridge::Client) -> Self {
+        Self { inner }
+    }
+
+    pub async fn describe_rule(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+    ) -> Result<DescribeRuleOutput, eventbridge::error::SdkError<DescribeRuleError>> {
+        self.inner
+            .describe_rule()
+            .event_bus_name(event_bus_name)
+            .name(rule_name)
+            .send()
+            .await
+    }
+
+    pub async fn list_targets_by_rule(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+        next_token: Option<String>,
+    ) -> Result<ListTargetsByRuleOutput, eventbridge::error::SdkError<ListTargetsByRuleError>> {
+        self.inner
+            .list_targets_by_rule()
+            .event_bus_name(event_bus_name)
+            .rule(rule_name)
+            .set_next_token(next_token)
+            .send()
+            .await
+    }
+
+    pub async fn put_rule(
+        &self,
+        rule: &Rule,
+    ) -> Result<PutRuleOutput, eventbridge::error::SdkError<PutRuleError>> {
+        put_rule_request(rule).send_with(&self.inner).await
+    }
+
+    pub async fn put_targets(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+        target: &RuleTarget,
+    ) -> Result<PutTargetsOutput, eventbridge::error::SdkError<PutTargetsError>> {
+        put_targets_request(event_bus_name, rule_name, target)
+            .send_with(&self.inner)
+            .await
+    }
+
+    pub async fn remove_targets(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+        target_ids: &[String],
+    ) -> Result<RemoveTargetsOutput, eventbridge::error::SdkError<RemoveTargetsError>> {
+        self.inner
+            .remove_targets()
+            .event_bus_name(event_bus_name)
+            .rule(rule_name)
+            .set_ids(Some(target_ids.to_vec()))
+            .send()
+            .await
+    }
+
+    pub async fn delete_rule(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+    ) -> Result<DeleteRuleOutput, eventbridge::error::SdkError<DeleteRuleError>> {
+        self.inner
+            .delete_rule()
+            .event_bus_name(event_bus_name)
+            .name(rule_name)
+            .send()
+            .await
+    }
+}
+
+// Requests which take fubura models are built apart from clients, so that cassette
+// records them as sent.
+
+pub fn put_rule_request(rule: &Rule) -> PutRuleInputBuilder {
+    PutRuleInput::builder()
+        .event_bus_name(&rule.event_bus_name)
+        .name(&rule.name)
+        .set_description(rule.description.clone())
+        .event_pattern(rule.event_pattern.to_string())
+        .state(rule.state.clone().into())
+}
+
+pub fn put_targets_request(
+    event_bus_name: &str,
+    rule_name: &str,
+    target: &RuleTarget,
+) -> PutTargetsInputBuilder {
+    PutTargetsInput::builder()
+        .event_bus_name(event_bus_name)
+        .rule(rule_name)
+        .targets(target.clone().into())
+}
+
+// Targets of the rule, following pagination.
+async fn list_targets(
+    client: &dyn Events,
+    event_bus_name: &str,
+    rule_name: &str,
+) -> Result<Vec<eventbridge::types::Target>> {
+    let mut targets = vec![];
+    let mut next_token = None;
+
+    loop {
+        let res = traced(
+            "events:ListTargetsByRule",
+            client.list_targets_by_rule(event_bus_name, rule_name, next_token),
+        )
+        .await;
+
+        let output = match res {
+            Ok(output) => output,
+            Err(err) => {
+                let service_error = err.into_service_error();
+                return Err(FuburaError::from_aws_error(
+                    &service_error,
+                    format!(
+                        "failed to list targets of rule({}/{}): {}",
+                        event_bus_name, rule_name, service_error
+                    ),
+                ));
+            }
+        };
+
+        targets.extend(output.targets().iter().cloned());
+
+        next_token = output.next_token().map(|token| token.to_string());
+        if next_token.is_none() {
+            return Ok(targets);
+        }
+    }
+}
+
+// Rule with its target of given id, see `Rule::target`
+pub async fn describe_rule_with_target(
+    client: &dyn Events,
+    rule_name_with_bus: &str,
+    target_id: &str,
+) -> Result<Option<Rule>> {
+    let Some((event_bus_name, rule_name)) = rule_name_with_bus.split_once('/') else {
+        bail!("invalid rule name with bus: {:?}", rule_name_with_bus);
+    };
+
+    let res = traced(
+        "events:DescribeRule",
+        client.describe_rule(event_bus_name, rule_name),
+    )
+    .await;
+
+    let output = match res {
+        Ok(output) => output,
+        Err(err) => {
+            let service_error = err.into_service_error();
+            if service_error.is_resource_not_found_exception() {
+                return Ok(None);
+            }
+            return Err(FuburaError::from_aws_error(
+                &service_error,
+                format!("failed to describe rule: {}", service_error),
+            ));
+        }
+    };
+
+    let targets = list_targets(client, event_bus_name, rule_name).await?;
+    let target = targets.iter().find(|target| target.id() == target_id);
+
+    match Rule::from_remote(&output, target) {
+        Ok(rule) => Ok(Some(rule)),
+        Err(e) => bail!("failed to read rule({}): {}", rule_name_with_bus, e),
+    }
+}
+
+// PutRule creates or updates the rule, and PutTargets does the same for its target.
+pub async fn put_rule_with_target(client: &dyn Events, rule: &Rule) -> Result<()> {
+    let res = traced("events:PutRule", client.put_rule(rule)).await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to put rule({}) with error: {}", rule.name, e),
+        ));
+    }
+
+    let Some(target) = &rule.target else {
+        return Ok(());
+    };
+
+    let res = traced(
+        "events:PutTargets",
+        client.put_targets(&rule.event_bus_name, &rule.name, target),
+    )
+    .await;
+
+    match res {
+        Ok(output) if output.failed_entry_count() > 0 => {
+            let reasons: Vec<String> = output
+                .failed_entries()
+                .iter()
+                .map(|entry| entry.error_message().unwrap_or_default().to_string())
+                .collect();
+            bail!(
+                "failed to put target of rule({}): {}",
+                rule.name,
+                reasons.join(", ")
+            );
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let e = e.into_service_error();
+            Err(FuburaError::from_aws_error(
+                &e,
+                format!("failed to put target of rule with error: {}", e),
+            ))
+        }
+    }
+}
+
+// Rule with targets can't be deleted, so the managed target is removed first.
+// Targets added outside of fubura are not touched, and the rule is kept then.
+pub async fn delete_rule(client: &dyn Events, rule: &Rule) -> Result<()> {
+    let (target_ids, foreign_target_ids): (Vec<String>, Vec<String>) =
+        list_targets(client, &rule.event_bus_name, &rule.name)
+            .await?
+            .iter()
+            .map(|target| target.id().to_string())
+            .partition(|id| id == rule.target_id());
+
+    if !foreign_target_ids.is_empty() {
+        return Err(FuburaError::Validation(format!(
+            "rule({}/{}) has targets not managed by fubura({}), refuse to delete it.",
+            rule.event_bus_name,
+            rule.name,
+            foreign_target_ids.join(", ")
+        ))
+        .into());
+    }
+
+    if !target_ids.is_empty() {
+        let res = traced(
+            "events:RemoveTargets",
+            client.remove_targets(&rule.event_bus_name, &rule.name, &target_ids),
+        )
+        .await;
+
+        if let Err(e) = res {
+            let e = e.into_service_error();
+            return Err(FuburaError::from_aws_error(
+                &e,
+                format!("failed to remove targets of rule with error: {}", e),
+            ));
+        }
+    }
+
+    let res = traced(
+        "events:DeleteRule",
+        client.delete_rule(&rule.event_bus_name, &rule.name),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to delete rule with error: {}", e),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use aws_sdk_eventbridge::types::Target;
+
+    use crate::types::DEFAULT_RULE_TARGET_ID;
+
+    fn list_targets_output(ids: &[&str]) -> ListTargetsByRuleOutput {
+        ListTargetsByRuleOutput::builder()
+            .set_targets(Some(
+                ids.iter()
+                    .map(|id| {
+                        Target::builder()
+                            .id(id.to_string())
+                            .arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
+                            .build()
+                            .unwrap()
+                    })
+                    .collect(),
+            ))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_delete_rule_removes_managed_target() {
+        let mut client = MockEventsImpl::default();
+        client
+            .expect_list_targets_by_rule()
+            .return_once(|_, _, _| Ok(list_targets_output(&[DEFAULT_RULE_TARGET_ID])));
+        client
+            .expect_remove_targets()
+            .withf(|_, _, target_ids| target_ids == [DEFAULT_RULE_TARGET_ID.to_string()])
+            .return_once(|_, _, _| Ok(RemoveTargetsOutput::builder().build()));
+        client
+            .expect_delete_rule()
+            .return_once(|_, _| Ok(DeleteRuleOutput::builder().build()));
+
+        delete_rule(&client, &Rule::test_default()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_rule_refuses_foreign_targets() {
+        let mut client = MockEventsImpl::default();
+        client
+            .expect_list_targets_by_rule()
+            .return_once(|_, _, _| Ok(list_targets_output(&[DEFAULT_RULE_TARGET_ID, "Audit"])));
+        client.expect_remove_targets().never();
+        client.expect_delete_rule().never();
+
+        let actual = delete_rule(&client, &Rule::test_default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "rule(default/HelloWorldOnUpload) has targets not managed by fubura(Audit), refuse to delete it.",
+            actual.to_string()
+        );
+    }
+}
@@ -0,0 +1,838 @@
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::types::{
+    ArnBuilder, DEFAULT_EVENT_BUS_NAME, EncryptionType, LogLevel, PolicyDocument, RolePolicy,
+    SsConfig, StateMachine,
+};
+
+// Rules which Step Functions puts to wait for ".sync" integrations
+const ECS_TASK_RULE: &str = "StepFunctionsGetEventsForECSTaskRule";
+const BATCH_JOB_RULE: &str = "StepFunctionsGetEventsForBatchJobsRule";
+const STATE_MACHINE_EXECUTION_RULE: &str = "StepFunctionsGetEventsForStepFunctionsExecutionRule";
+
+// SDK service names of "aws-sdk" integration which differ from IAM prefix
+const SDK_SERVICE_PREFIXES: &[(&str, &str)] = &[
+    ("sfn", "states"),
+    ("eventbridge", "events"),
+    ("cloudwatchlogs", "logs"),
+    ("sesv2", "ses"),
+    ("elasticloadbalancingv2", "elasticloadbalancing"),
+];
+
+// Policies needed by the execution role of state machine, and roles of schedules and rules starting it.
+// Policies of the same role are merged into one.
+pub fn build_role_policies(ss_config: &SsConfig, arn_builder: &ArnBuilder) -> Vec<RolePolicy> {
+    // Filled in the same way as apply
+    let ss_config = &ss_config.with_log_group_arn(arn_builder);
+    let state = &ss_config.state;
+    let state_arn = arn_builder.state_machine(&state.name);
+    let mut role_policies = vec![];
+
+    push_role_policy(
+        &mut role_policies,
+        RolePolicy {
+            role_arn: state.role_arn.clone(),
+            state_names: vec![state.name.clone()],
+            policy: build_execution_policy(state, arn_builder),
+        },
+    );
+
+    for schedule_config in ss_config.schedule_configs() {
+        if schedule_config.delete {
+            continue;
+        }
+
+        let target = &schedule_config.schedule.target;
+        let mut policy = PolicyDocument::default();
+        policy.allow(
+            &["states:StartExecution"],
+            std::slice::from_ref(&target.arn),
+        );
+        if let Some(arn) = target
+            .dead_letter_config
+            .as_ref()
+            .and_then(|dead_letter_config| dead_letter_config.arn.clone())
+        {
+            policy.allow(&["sqs:SendMessage"], &[arn]);
+        }
+        if let Some(kms_key_arn) = &schedule_config.schedule.kms_key_arn {
+            policy.allow(&["kms:Decrypt"], std::slice::from_ref(kms_key_arn));
+        }
+
+        push_role_policy(
+            &mut role_policies,
+            RolePolicy {
+                role_arn: target.role_arn.clone(),
+                state_names: vec![state.name.clone()],
+                policy,
+            },
+        );
+    }
+
+    for rule_config in ss_config.rule_configs(&state_arn) {
+        let Some(target) = rule_config
+            .rule
+            .target
+            .as_ref()
+            .filter(|_| !rule_config.delete)
+        else {
+            continue;
+        };
+
+        let mut policy = PolicyDocument::default();
+        policy.allow(
+            &["states:StartExecution"],
+            &Vec::from_iter(target.arn.clone()),
+        );
+
+        push_role_policy(
+            &mut role_policies,
+            RolePolicy {
+                role_arn: target.role_arn.clone(),
+                state_names: vec![state.name.clone()],
+                policy,
+            },
+        );
+    }
+
+    role_policies
+}
+
+// Merges into the policy of the same role if any, as a role can have only one policy per name.
+pub fn push_role_policy(role_policies: &mut Vec<RolePolicy>, role_policy: RolePolicy) {
+    if role_policy.policy.is_empty() {
+        return;
+    }
+
+    match role_policies
+        .iter_mut()
+        .find(|existing| existing.role_arn == role_policy.role_arn)
+    {
+        Some(existing) => {
+            existing.policy.merge(&role_policy.policy);
+            for state_name in role_policy.state_names {
+                if !existing.state_names.contains(&state_name) {
+                    existing.state_names.push(state_name);
+                }
+            }
+        }
+        None => role_policies.push(role_policy),
+    }
+}
+
+pub fn build_execution_policy(state: &StateMachine, arn_builder: &ArnBuilder) -> PolicyDocument {
+    let state_arn = arn_builder.state_machine(&state.name);
+    let mut policy = PolicyDocument::default();
+
+    add_states_policy(&state.definition, &state_arn, arn_builder, &mut policy);
+
+    // Log delivery apis don't support resource-level permissions
+    if let Some(logging_configuration) = &state.logging_configuration
+        && logging_configuration.level != Some(LogLevel::Off)
+        && !logging_configuration.destinations.is_empty()
+    {
+        policy.allow(
+            &[
+                "logs:CreateLogDelivery",
+                "logs:GetLogDelivery",
+                "logs:UpdateLogDelivery",
+                "logs:DeleteLogDelivery",
+                "logs:ListLogDeliveries",
+                "logs:PutResourcePolicy",
+                "logs:DescribeResourcePolicies",
+                "logs:DescribeLogGroups",
+            ],
+            &["*".to_string()],
+        );
+    }
+
+    if state
+        .tracing_configuration
+        .as_ref()
+        .is_some_and(|tracing_configuration| tracing_configuration.enabled)
+    {
+        policy.allow(
+            &[
+                "xray:PutTraceSegments",
+                "xray:PutTelemetryRecords",
+                "xray:GetSamplingRules",
+                "xray:GetSamplingTargets",
+            ],
+            &["*".to_string()],
+        );
+    }
+
+    if let Some(encryption_configuration) = &state.encryption_configuration
+        && encryption_configuration.r#type == EncryptionType::CustomerManagedKmsKey
+    {
+        // Key policies are evaluated against key ARN, not alias
+        let key_arn = match encryption_configuration.kms_key_id.as_deref() {
+            Some(key_id) if key_id.contains("alias/") => "*".to_string(),
+            Some(key_id) => arn_or(Some(key_id), |key_id| arn_builder.kms_key(key_id)),
+            None => "*".to_string(),
+        };
+        policy.allow(&["kms:Decrypt", "kms:GenerateDataKey"], &[key_arn]);
+    }
+
+    policy
+}
+
+// Walks states of definition, and branches of Parallel and Map states in it.
+fn add_states_policy(
+    definition: &Value,
+    state_arn: &str,
+    arn_builder: &ArnBuilder,
+    policy: &mut PolicyDocument,
+) {
+    for (state_name, state) in definition["States"].as_object().into_iter().flatten() {
+        match state["Type"].as_str() {
+            Some("Task") => add_task_policy(state_name, state, arn_builder, policy),
+            Some("Parallel") => {
+                for branch in state["Branches"].as_array().into_iter().flatten() {
+                    add_states_policy(branch, state_arn, arn_builder, policy);
+                }
+            }
+            Some("Map") => {
+                // Iterator is the older name of ItemProcessor
+                let item_processor = state.get("ItemProcessor").or(state.get("Iterator"));
+                if let Some(item_processor) = item_processor {
+                    add_states_policy(item_processor, state_arn, arn_builder, policy);
+
+                    // Distributed map runs child workflows as executions of the state machine itself
+                    if item_processor["ProcessorConfig"]["Mode"] == "DISTRIBUTED" {
+                        policy.allow(&["states:StartExecution"], &[state_arn.to_string()]);
+                        policy.allow(
+                            &["states:DescribeExecution", "states:StopExecution"],
+                            &[format!("{}/*", execution_arn_prefix(state_arn))],
+                        );
+                    }
+                }
+                for key in ["ItemReader", "ResultWriter"] {
+                    if let Some(task) = state.get(key) {
+                        add_task_policy(state_name, task, arn_builder, policy);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn add_task_policy(
+    state_name: &str,
+    state: &Value,
+    arn_builder: &ArnBuilder,
+    policy: &mut PolicyDocument,
+) {
+    let Some(resource) = state["Resource"].as_str() else {
+        return;
+    };
+    // JSONata states take "Arguments" instead
+    let parameters = state
+        .get("Parameters")
+        .or(state.get("Arguments"))
+        .unwrap_or(&Value::Null);
+
+    // "arn:aws:states:::ecs:runTask.sync" is split into ["arn", "aws", "states", "", "", "ecs:runTask.sync"]
+    let components: Vec<&str> = resource.splitn(6, ':').collect();
+    if components.len() != 6 || components[0] != "arn" {
+        warn_unsupported(state_name, resource);
+        return;
+    }
+
+    match (components[2], components[3]) {
+        // Integration with service
+        ("states", "") => {}
+        // Activity workers call Step Functions by their own role
+        ("states", _) => return,
+        // Lambda function given as resource, which is the older way to invoke it
+        ("lambda", _) => {
+            policy.allow(&["lambda:InvokeFunction"], &lambda_function_arns(resource));
+            return;
+        }
+        _ => {
+            warn_unsupported(state_name, resource);
+            return;
+        }
+    }
+
+    // "ecs:runTask.sync" to ("ecs:runTask", true), and ".waitForTaskToken" is same as request response
+    let (api, sync) = match components[5].split_once('.') {
+        Some((api, pattern)) => (api, pattern.starts_with("sync")),
+        None => (components[5], false),
+    };
+
+    if let Some(sdk_api) = api.strip_prefix("aws-sdk:") {
+        add_sdk_policy(state_name, resource, sdk_api, policy);
+        return;
+    }
+
+    match api {
+        "lambda:invoke" => {
+            let function = static_str(parameters, "FunctionName");
+            let resources = match function {
+                Some(function) if function.starts_with("arn:") => lambda_function_arns(function),
+                Some(function) => lambda_function_arns(&arn_builder.lambda_function(function)),
+                None => vec!["*".to_string()],
+            };
+            policy.allow(&["lambda:InvokeFunction"], &resources);
+        }
+        "ecs:runTask" => {
+            let task_definition = static_str(parameters, "TaskDefinition").map(|task_definition| {
+                let has_revision = task_definition
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|family| family.contains(':'));
+                match (task_definition.starts_with("arn:"), has_revision) {
+                    (true, true) => task_definition.to_string(),
+                    (true, false) => format!("{}:*", task_definition),
+                    (false, true) => arn_builder.ecs_task_definition(task_definition),
+                    (false, false) => {
+                        arn_builder.ecs_task_definition(&format!("{}:*", task_definition))
+                    }
+                }
+            });
+            policy.allow(
+                &["ecs:RunTask"],
+                &[task_definition.unwrap_or("*".to_string())],
+            );
+
+            // Roles in task definition can't be told from definition, unless they are overridden
+            let overrides = &parameters["Overrides"];
+            match (
+                static_str(overrides, "TaskRoleArn"),
+                static_str(overrides, "ExecutionRoleArn"),
+            ) {
+                (Some(task_role_arn), Some(execution_role_arn)) => policy.allow(
+                    &["iam:PassRole"],
+                    &[task_role_arn.to_string(), execution_role_arn.to_string()],
+                ),
+                _ => policy.add(
+                    &["iam:PassRole"],
+                    &["*".to_string()],
+                    Some(json!({
+                        "StringEquals": { "iam:PassedToService": "ecs-tasks.amazonaws.com" }
+                    })),
+                ),
+            }
+
+            if sync {
+                policy.allow(&["ecs:StopTask", "ecs:DescribeTasks"], &["*".to_string()]);
+                add_sync_rule_policy(ECS_TASK_RULE, arn_builder, policy);
+            }
+        }
+        "states:startExecution" => {
+            let state_machine_arn = static_str(parameters, "StateMachineArn").map(|name_or_arn| {
+                arn_or(Some(name_or_arn), |name| arn_builder.state_machine(name))
+            });
+            policy.allow(
+                &["states:StartExecution"],
+                &[state_machine_arn.clone().unwrap_or("*".to_string())],
+            );
+
+            if sync {
+                let execution_arn = state_machine_arn
+                    .map(|arn| format!("{}:*", execution_arn_prefix(&arn)))
+                    .unwrap_or("*".to_string());
+                policy.allow(
+                    &["states:DescribeExecution", "states:StopExecution"],
+                    &[execution_arn],
+                );
+                add_sync_rule_policy(STATE_MACHINE_EXECUTION_RULE, arn_builder, policy);
+            }
+        }
+        "sqs:sendMessage" => {
+            let queue_arn = static_str(parameters, "QueueUrl")
+                .and_then(|queue_url| sqs_queue_arn(queue_url, arn_builder));
+            policy.allow(
+                &["sqs:SendMessage"],
+                &[queue_arn.unwrap_or("*".to_string())],
+            );
+        }
+        "sns:publish" => {
+            // Publishing to phone number has no resource
+            let topic_arn =
+                static_str(parameters, "TopicArn").or(static_str(parameters, "TargetArn"));
+            policy.allow(&["sns:Publish"], &[topic_arn.unwrap_or("*").to_string()]);
+        }
+        "dynamodb:getItem" | "dynamodb:putItem" | "dynamodb:updateItem" | "dynamodb:deleteItem" => {
+            let action = format!("dynamodb:{}", pascal_case(&api["dynamodb:".len()..]));
+            let table_arn = arn_or(static_str(parameters, "TableName"), |name| {
+                arn_builder.dynamodb_table(name)
+            });
+            policy.allow(&[action.as_str()], &[table_arn]);
+        }
+        "events:putEvents" => {
+            let event_bus_arns: Vec<String> = match parameters["Entries"].as_array() {
+                Some(entries) => entries
+                    .iter()
+                    .map(|entry| match entry.get("EventBusName") {
+                        None => arn_builder.event_bus(DEFAULT_EVENT_BUS_NAME),
+                        Some(_) => arn_or(static_str(entry, "EventBusName"), |name| {
+                            arn_builder.event_bus(name)
+                        }),
+                    })
+                    .collect(),
+                None => vec!["*".to_string()],
+            };
+            policy.allow(&["events:PutEvents"], &event_bus_arns);
+        }
+        "batch:submitJob" => {
+            let job_queue_arn = arn_or(static_str(parameters, "JobQueue"), |name| {
+                arn_builder.batch_job_queue(name)
+            });
+            let job_definition_arn = arn_or(static_str(parameters, "JobDefinition"), |name| {
+                if name.contains(':') {
+                    arn_builder.batch_job_definition(name)
+                } else {
+                    arn_builder.batch_job_definition(&format!("{}:*", name))
+                }
+            });
+            policy.allow(&["batch:SubmitJob"], &[job_queue_arn, job_definition_arn]);
+
+            if sync {
+                policy.allow(
+                    &["batch:DescribeJobs", "batch:TerminateJob"],
+                    &["*".to_string()],
+                );
+                add_sync_rule_policy(BATCH_JOB_RULE, arn_builder, policy);
+            }
+        }
+        "glue:startJobRun" => {
+            let job_arn = arn_or(static_str(parameters, "JobName"), |name| {
+                arn_builder.glue_job(name)
+            });
+            policy.allow(
+                &[
+                    "glue:StartJobRun",
+                    "glue:GetJobRun",
+                    "glue:GetJobRuns",
+                    "glue:BatchStopJobRun",
+                ],
+                &[job_arn],
+            );
+        }
+        // Item reader and result writer of distributed map
+        "s3:getObject" | "s3:listObjectsV2" | "s3:putObject" => {
+            let bucket = static_str(parameters, "Bucket");
+            let object = match (
+                static_str(parameters, "Key"),
+                static_str(parameters, "Prefix"),
+            ) {
+                (Some(key), _) => key.to_string(),
+                (None, Some(prefix)) => format!("{}*", prefix),
+                (None, None) => "*".to_string(),
+            };
+            let bucket_arn =
+                bucket.map(|bucket| format!("arn:{}:s3:::{}", arn_builder.partition, bucket));
+
+            match api {
+                "s3:listObjectsV2" => {
+                    policy.allow(&["s3:ListBucket"], &[bucket_arn.unwrap_or("*".to_string())])
+                }
+                "s3:getObject" => policy.allow(
+                    &["s3:GetObject"],
+                    &[bucket_arn
+                        .map(|arn| format!("{}/{}", arn, object))
+                        .unwrap_or("*".to_string())],
+                ),
+                _ => policy.allow(
+                    &[
+                        "s3:PutObject",
+                        "s3:GetObject",
+                        "s3:ListMultipartUploadParts",
+                        "s3:AbortMultipartUpload",
+                    ],
+                    &[bucket_arn
+                        .map(|arn| format!("{}/{}", arn, object))
+                        .unwrap_or("*".to_string())],
+                ),
+            }
+        }
+        _ => warn_unsupported(state_name, resource),
+    }
+}
+
+// "aws-sdk:s3:getObject" can call any resource given by parameters, so resource is left wildcard
+fn add_sdk_policy(state_name: &str, resource: &str, sdk_api: &str, policy: &mut PolicyDocument) {
+    let Some((service, action)) = sdk_api.split_once(':') else {
+        warn_unsupported(state_name, resource);
+        return;
+    };
+    let prefix = SDK_SERVICE_PREFIXES
+        .iter()
+        .find(|(sdk_service, _)| *sdk_service == service)
+        .map(|(_, prefix)| *prefix)
+        .unwrap_or(service);
+    let action = format!("{}:{}", prefix, pascal_case(action));
+
+    policy.allow(&[action.as_str()], &["*".to_string()]);
+}
+
+fn add_sync_rule_policy(rule_name: &str, arn_builder: &ArnBuilder, policy: &mut PolicyDocument) {
+    policy.allow(
+        &["events:PutTargets", "events:PutRule", "events:DescribeRule"],
+        &[arn_builder.rule(DEFAULT_EVENT_BUS_NAME, rule_name)],
+    );
+}
+
+fn warn_unsupported(state_name: &str, resource: &str) {
+    warn!(
+        "state({}) has resource {}, which fubura can't build policy for. Add it to the policy by hand.",
+        state_name, resource
+    );
+}
+
+// Value given in definition, or None if it's resolved on execution by JSONPath or JSONata
+fn static_str<'a>(parameters: &'a Value, key: &str) -> Option<&'a str> {
+    parameters
+        .get(key)?
+        .as_str()
+        .filter(|value| !value.starts_with("{%"))
+}
+
+// Wildcard if value is resolved on execution
+fn arn_or(name_or_arn: Option<&str>, build: impl FnOnce(&str) -> String) -> String {
+    match name_or_arn {
+        Some(arn) if arn.starts_with("arn:") => arn.to_string(),
+        Some(name) => build(name),
+        None => "*".to_string(),
+    }
+}
+
+// Unqualified function arn needs its versions and aliases too
+fn lambda_function_arns(function_arn: &str) -> Vec<String> {
+    if function_arn.split(':').count() > 7 {
+        vec![function_arn.to_string()]
+    } else {
+        vec![function_arn.to_string(), format!("{}:*", function_arn)]
+    }
+}
+
+// "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld:live" to
+// "arn:aws:states:us-west-2:123456789012:execution:HelloWorld"
+fn execution_arn_prefix(state_machine_arn: &str) -> String {
+    let components: Vec<&str> = state_machine_arn.split(':').take(7).collect();
+    if components.len() != 7 {
+        return "*".to_string();
+    }
+
+    format!("{}:execution:{}", components[..5].join(":"), components[6])
+}
+
+// "https://sqs.us-west-2.amazonaws.com/123456789012/HelloWorld" to
+// "arn:aws:sqs:us-west-2:123456789012:HelloWorld"
+fn sqs_queue_arn(queue_url: &str, arn_builder: &ArnBuilder) -> Option<String> {
+    let path = queue_url.split_once("://")?.1;
+    let mut components = path.split('/');
+    let region = components.next()?.split('.').nth(1)?;
+    let account = components.next()?;
+    let queue_name = components.next()?;
+
+    Some(format!(
+        "arn:{}:sqs:{}:{}:{}",
+        arn_builder.partition, region, account, queue_name
+    ))
+}
+
+fn pascal_case(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    use crate::types::{Partition, Rule, RuleConfig, Schedule};
+
+    fn arn_builder() -> ArnBuilder {
+        ArnBuilder::new(Partition::Aws, "us-west-2", "123456789012")
+    }
+
+    fn ss_config(definition: Value) -> SsConfig {
+        SsConfig {
+            state: StateMachine {
+                definition,
+                ..StateMachine::test_default()
+            },
+            ..SsConfig::test_default()
+        }
+    }
+
+    #[test]
+    fn test_build_execution_policy() {
+        let state = StateMachine {
+            definition: json!({
+                "StartAt": "Run",
+                "States": {
+                    "Run": {
+                        "Type": "Task",
+                        "Resource": "arn:aws:states:::ecs:runTask.sync",
+                        "Parameters": {
+                            "TaskDefinition": "batch:3",
+                            "Overrides": {
+                                "TaskRoleArn": "arn:aws:iam::123456789012:role/TaskRole",
+                                "ExecutionRoleArn": "arn:aws:iam::123456789012:role/ExecutionRole",
+                            },
+                        },
+                        "Next": "Each",
+                    },
+                    "Each": {
+                        "Type": "Map",
+                        "ItemProcessor": {
+                            "StartAt": "Invoke",
+                            "States": {
+                                "Invoke": {
+                                    "Type": "Task",
+                                    "Resource": "arn:aws:states:::lambda:invoke",
+                                    "Parameters": { "FunctionName": "hello", "Payload.$": "$" },
+                                    "End": true,
+                                },
+                                "InvokeDynamic": {
+                                    "Type": "Task",
+                                    "Resource": "arn:aws:states:::lambda:invoke.waitForTaskToken",
+                                    "Parameters": { "FunctionName.$": "$.function" },
+                                    "End": true,
+                                },
+                            },
+                        },
+                        "Next": "Child",
+                    },
+                    "Child": {
+                        "Type": "Task",
+                        "Resource": "arn:aws:states:::states:startExecution.sync:2",
+                        "Parameters": { "StateMachineArn": "arn:aws:states:us-west-2:123456789012:stateMachine:Child:live" },
+                        "Next": "Enqueue",
+                    },
+                    "Enqueue": {
+                        "Type": "Task",
+                        "Resource": "arn:aws:states:::sqs:sendMessage",
+                        "Arguments": { "QueueUrl": "https://sqs.us-west-2.amazonaws.com/123456789012/jobs" },
+                        "Next": "Save",
+                    },
+                    "Save": {
+                        "Type": "Task",
+                        "Resource": "arn:aws:states:::dynamodb:putItem",
+                        "Parameters": { "TableName": "jobs" },
+                        "Next": "Unknown",
+                    },
+                    "Unknown": {
+                        "Type": "Task",
+                        "Resource": "arn:aws:states:::unknown:doSomething",
+                        "End": true,
+                    },
+                },
+            }),
+            ..StateMachine::test_default()
+        };
+
+        let actual = build_execution_policy(&state, &arn_builder());
+
+        similar_asserts::assert_eq!(
+            json!({
+                "Version": "2012-10-17",
+                "Statement": [
+                    {
+                        "Effect": "Allow",
+                        "Action": ["states:StartExecution"],
+                        "Resource": ["arn:aws:states:us-west-2:123456789012:stateMachine:Child:live"],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["states:DescribeExecution", "states:StopExecution"],
+                        "Resource": ["arn:aws:states:us-west-2:123456789012:execution:Child:*"],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["events:PutTargets", "events:PutRule", "events:DescribeRule"],
+                        "Resource": [
+                            "arn:aws:events:us-west-2:123456789012:rule/StepFunctionsGetEventsForStepFunctionsExecutionRule",
+                            "arn:aws:events:us-west-2:123456789012:rule/StepFunctionsGetEventsForECSTaskRule",
+                        ],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["lambda:InvokeFunction"],
+                        "Resource": ["*"],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["sqs:SendMessage"],
+                        "Resource": ["arn:aws:sqs:us-west-2:123456789012:jobs"],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["ecs:RunTask"],
+                        "Resource": ["arn:aws:ecs:us-west-2:123456789012:task-definition/batch:3"],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["iam:PassRole"],
+                        "Resource": [
+                            "arn:aws:iam::123456789012:role/TaskRole",
+                            "arn:aws:iam::123456789012:role/ExecutionRole",
+                        ],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["ecs:StopTask", "ecs:DescribeTasks"],
+                        "Resource": ["*"],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["dynamodb:PutItem"],
+                        "Resource": ["arn:aws:dynamodb:us-west-2:123456789012:table/jobs"],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": [
+                            "logs:CreateLogDelivery",
+                            "logs:GetLogDelivery",
+                            "logs:UpdateLogDelivery",
+                            "logs:DeleteLogDelivery",
+                            "logs:ListLogDeliveries",
+                            "logs:PutResourcePolicy",
+                            "logs:DescribeResourcePolicies",
+                            "logs:DescribeLogGroups",
+                        ],
+                        "Resource": ["*"],
+                    },
+                ],
+            }),
+            serde_json::to_value(&actual).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_role_policies() {
+        let mut config = ss_config(json!({
+            "StartAt": "Invoke",
+            "States": {
+                "Invoke": {
+                    "Type": "Task",
+                    "Resource": "arn:aws:lambda:us-west-2:123456789012:function:hello:live",
+                    "End": true,
+                },
+            },
+        }));
+        config.state.logging_configuration = None;
+        // Scheduler shares the execution role
+        config.schedule = Some(Schedule::test_default());
+        let mut rule = Rule::test_default();
+        rule.target.as_mut().unwrap().arn = None;
+        rule.target.as_mut().unwrap().role_arn =
+            "arn:aws:iam::123456789012:role/RuleRole".to_string();
+        config.rules = vec![RuleConfig {
+            rule,
+            delete: false,
+        }];
+
+        let actual = build_role_policies(&config, &arn_builder());
+
+        assert_eq!(2, actual.len());
+        assert_eq!(config.state.role_arn, actual[0].role_arn);
+        similar_asserts::assert_eq!(
+            vec![
+                vec!["lambda:InvokeFunction".to_string()],
+                vec!["states:StartExecution".to_string()],
+            ],
+            actual[0]
+                .policy
+                .statement
+                .iter()
+                .map(|statement| statement.action.clone())
+                .collect::<Vec<_>>()
+        );
+        similar_asserts::assert_eq!(
+            vec!["arn:aws:lambda:us-west-2:123456789012:function:hello:live".to_string()],
+            actual[0].policy.statement[0].resource
+        );
+        assert_eq!(
+            "arn:aws:iam::123456789012:role/RuleRole",
+            actual[1].role_arn
+        );
+        similar_asserts::assert_eq!(
+            vec!["arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld".to_string()],
+            actual[1].policy.statement[0].resource
+        );
+
+        // Deleted schedules and rules need nothing
+        config.delete_schedule = true;
+        config.rules[0].delete = true;
+        let actual = build_role_policies(&config, &arn_builder());
+        assert_eq!(1, actual.len());
+        assert_eq!(1, actual[0].policy.statement.len());
+    }
+
+    #[test]
+    fn test_build_execution_policy_for_distributed_map() {
+        let state = StateMachine {
+            definition: json!({
+                "StartAt": "Each",
+                "States": {
+                    "Each": {
+                        "Type": "Map",
+                        "ItemReader": {
+                            "Resource": "arn:aws:states:::s3:getObject",
+                            "Parameters": { "Bucket": "inputs", "Key": "items.json" },
+                        },
+                        "ItemProcessor": {
+                            "ProcessorConfig": { "Mode": "DISTRIBUTED", "ExecutionType": "STANDARD" },
+                            "StartAt": "Pass",
+                            "States": { "Pass": { "Type": "Pass", "End": true } },
+                        },
+                        "End": true,
+                    },
+                },
+            }),
+            logging_configuration: None,
+            ..StateMachine::test_default()
+        };
+
+        let actual = build_execution_policy(&state, &arn_builder());
+
+        similar_asserts::assert_eq!(
+            json!([
+                {
+                    "Effect": "Allow",
+                    "Action": ["states:StartExecution"],
+                    "Resource": ["arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld"],
+                },
+                {
+                    "Effect": "Allow",
+                    "Action": ["states:DescribeExecution", "states:StopExecution"],
+                    "Resource": ["arn:aws:states:us-west-2:123456789012:execution:HelloWorld/*"],
+                },
+                {
+                    "Effect": "Allow",
+                    "Action": ["s3:GetObject"],
+                    "Resource": ["arn:aws:s3:::inputs/items.json"],
+                },
+            ]),
+            serde_json::to_value(&actual.statement).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_add_sdk_policy() {
+        let mut policy = PolicyDocument::default();
+        add_sdk_policy(
+            "Describe",
+            "arn:aws:states:::aws-sdk:sfn:describeExecution",
+            "sfn:describeExecution",
+            &mut policy,
+        );
+
+        assert_eq!(
+            vec!["states:DescribeExecution".to_string()],
+            policy.statement[0].action
+        );
+    }
+}
@@ -1,10 +1,18 @@
+pub mod canary;
+pub mod cassette;
 pub mod cli;
 pub mod commands;
 pub mod context;
 pub mod differ;
+pub mod error;
+pub mod events;
+pub mod iam;
 pub mod jsonnet_evaluator;
+pub mod logs;
 pub mod macros;
+pub mod memory;
 pub mod scheduler;
 pub mod sfn;
 pub mod sts;
+pub mod telemetry;
 pub mod types;
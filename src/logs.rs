@@ -0,0 +1,617 @@
+// Mocks of LogsImpl return SdkError as is
+#![cfg_attr(test, allow(clippy::result_large_err))]
+
+use anyhow::{Result, bail};
+use aws_sdk_cloudwatchlogs as cloudwatchlogs;
+use aws_sdk_cloudwatchlogs::operation::associate_kms_key::{
+    AssociateKmsKeyError, AssociateKmsKeyOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::create_log_group::builders::CreateLogGroupInputBuilder;
+use aws_sdk_cloudwatchlogs::operation::create_log_group::{
+    CreateLogGroupError, CreateLogGroupInput, CreateLogGroupOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::delete_retention_policy::{
+    DeleteRetentionPolicyError, DeleteRetentionPolicyOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::describe_log_groups::{
+    DescribeLogGroupsError, DescribeLogGroupsOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::disassociate_kms_key::{
+    DisassociateKmsKeyError, DisassociateKmsKeyOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::list_tags_for_resource::{
+    ListTagsForResourceError, ListTagsForResourceOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::put_retention_policy::{
+    PutRetentionPolicyError, PutRetentionPolicyOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::tag_resource::builders::TagResourceInputBuilder;
+use aws_sdk_cloudwatchlogs::operation::tag_resource::{
+    TagResourceError, TagResourceInput, TagResourceOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::untag_resource::{UntagResourceError, UntagResourceOutput};
+
+#[allow(unused_imports)]
+use mockall::automock;
+
+use crate::context::BoxFuture;
+use crate::error::FuburaError;
+use crate::telemetry::traced;
+use crate::types::{LogGroup, ResourceTag};
+
+/// CloudWatch Logs operations used by fubura.
+///
+/// Implement this to inject your own client into `FuburaContext::new`.
+pub trait Logs: Send + Sync {
+    fn describe_log_groups<'a>(
+        &'a self,
+        log_group_name_prefix: &'a str,
+        next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<DescribeLogGroupsOutput, cloudwatchlogs::error::SdkError<DescribeLogGroupsError>>,
+    >;
+
+    fn list_tags_for_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            ListTagsForResourceOutput,
+            cloudwatchlogs::error::SdkError<ListTagsForResourceError>,
+        >,
+    >;
+
+    fn create_log_group<'a>(
+        &'a self,
+        log_group: &'a LogGroup,
+    ) -> BoxFuture<
+        'a,
+        Result<CreateLogGroupOutput, cloudwatchlogs::error::SdkError<CreateLogGroupError>>,
+    >;
+
+    fn put_retention_policy<'a>(
+        &'a self,
+        log_group_name: &'a str,
+        retention_in_days: i32,
+    ) -> BoxFuture<
+        'a,
+        Result<PutRetentionPolicyOutput, cloudwatchlogs::error::SdkError<PutRetentionPolicyError>>,
+    >;
+
+    fn delete_retention_policy<'a>(
+        &'a self,
+        log_group_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            DeleteRetentionPolicyOutput,
+            cloudwatchlogs::error::SdkError<DeleteRetentionPolicyError>,
+        >,
+    >;
+
+    fn associate_kms_key<'a>(
+        &'a self,
+        log_group_name: &'a str,
+        kms_key_id: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<AssociateKmsKeyOutput, cloudwatchlogs::error::SdkError<AssociateKmsKeyError>>,
+    >;
+
+    fn disassociate_kms_key<'a>(
+        &'a self,
+        log_group_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DisassociateKmsKeyOutput, cloudwatchlogs::error::SdkError<DisassociateKmsKeyError>>,
+    >;
+
+    fn tag_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+        tags: &'a [ResourceTag],
+    ) -> BoxFuture<'a, Result<TagResourceOutput, cloudwatchlogs::error::SdkError<TagResourceError>>>;
+
+    fn untag_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+        tag_keys: &'a [String],
+    ) -> BoxFuture<
+        'a,
+        Result<UntagResourceOutput, cloudwatchlogs::error::SdkError<UntagResourceError>>,
+    >;
+}
+
+// Implements `Logs` by delegating to inherent methods of the given type,
+// which is shared by the aws-sdk based client and its mock.
+macro_rules! impl_logs {
+    ($impl:ty) => {
+        impl Logs for $impl {
+            fn describe_log_groups<'a>(
+                &'a self,
+                log_group_name_prefix: &'a str,
+                next_token: Option<String>,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    DescribeLogGroupsOutput,
+                    cloudwatchlogs::error::SdkError<DescribeLogGroupsError>,
+                >,
+            > {
+                Box::pin(<$impl>::describe_log_groups(
+                    self,
+                    log_group_name_prefix,
+                    next_token,
+                ))
+            }
+
+            fn list_tags_for_resource<'a>(
+                &'a self,
+                resource_arn: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    ListTagsForResourceOutput,
+                    cloudwatchlogs::error::SdkError<ListTagsForResourceError>,
+                >,
+            > {
+                Box::pin(<$impl>::list_tags_for_resource(self, resource_arn))
+            }
+
+            fn create_log_group<'a>(
+                &'a self,
+                log_group: &'a LogGroup,
+            ) -> BoxFuture<
+                'a,
+                Result<CreateLogGroupOutput, cloudwatchlogs::error::SdkError<CreateLogGroupError>>,
+            > {
+                Box::pin(<$impl>::create_log_group(self, log_group))
+            }
+
+            fn put_retention_policy<'a>(
+                &'a self,
+                log_group_name: &'a str,
+                retention_in_days: i32,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    PutRetentionPolicyOutput,
+                    cloudwatchlogs::error::SdkError<PutRetentionPolicyError>,
+                >,
+            > {
+                Box::pin(<$impl>::put_retention_policy(
+                    self,
+                    log_group_name,
+                    retention_in_days,
+                ))
+            }
+
+            fn delete_retention_policy<'a>(
+                &'a self,
+                log_group_name: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    DeleteRetentionPolicyOutput,
+                    cloudwatchlogs::error::SdkError<DeleteRetentionPolicyError>,
+                >,
+            > {
+                Box::pin(<$impl>::delete_retention_policy(self, log_group_name))
+            }
+
+            fn associate_kms_key<'a>(
+                &'a self,
+                log_group_name: &'a str,
+                kms_key_id: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    AssociateKmsKeyOutput,
+                    cloudwatchlogs::error::SdkError<AssociateKmsKeyError>,
+                >,
+            > {
+                Box::pin(<$impl>::associate_kms_key(self, log_group_name, kms_key_id))
+            }
+
+            fn disassociate_kms_key<'a>(
+                &'a self,
+                log_group_name: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    DisassociateKmsKeyOutput,
+                    cloudwatchlogs::error::SdkError<DisassociateKmsKeyError>,
+                >,
+            > {
+                Box::pin(<$impl>::disassociate_kms_key(self, log_group_name))
+            }
+
+            fn tag_resource<'a>(
+                &'a self,
+                resource_arn: &'a str,
+                tags: &'a [ResourceTag],
+            ) -> BoxFuture<
+                'a,
+                Result<TagResourceOutput, cloudwatchlogs::error::SdkError<TagResourceError>>,
+            > {
+                Box::pin(<$impl>::tag_resource(self, resource_arn, tags))
+            }
+
+            fn untag_resource<'a>(
+                &'a self,
+                resource_arn: &'a str,
+                tag_keys: &'a [String],
+            ) -> BoxFuture<
+                'a,
+                Result<UntagResourceOutput, cloudwatchlogs::error::SdkError<UntagResourceError>>,
+            > {
+                Box::pin(<$impl>::untag_resource(self, resource_arn, tag_keys))
+            }
+        }
+    };
+}
+
+impl_logs!(LogsImpl);
+#[cfg(test)]
+impl_logs!(MockLogsImpl);
+
+pub struct LogsImpl {
+    inner: cloudwatchlogs::Client,
+}
+
+#[cfg_attr(test, automock)]
+impl LogsImpl {
+    pub fn new(inner: cloudwatchlogs::Client) -> Self {
+        Self { inner }
+    }
+
+    pub async fn describe_log_groups(
+        &self,
+        log_group_name_prefix: &str,
+        next_token: Option<String>,
+    ) -> Result<DescribeLogGroupsOutput, cloudwatchlogs::error::SdkError<DescribeLogGroupsError>>
+    {
+        self.inner
+            .describe_log_groups()
+            .log_group_name_prefix(log_group_name_prefix)
+            .set_next_token(next_token)
+            .send()
+            .await
+    }
+
+    pub async fn list_tags_for_resource(
+        &self,
+        resource_arn: &str,
+    ) -> Result<ListTagsForResourceOutput, cloudwatchlogs::error::SdkError<ListTagsForResourceError>>
+    {
+        self.inner
+            .list_tags_for_resource()
+            .resource_arn(resource_arn)
+            .send()
+            .await
+    }
+
+    pub async fn create_log_group(
+        &self,
+        log_group: &LogGroup,
+    ) -> Result<CreateLogGroupOutput, cloudwatchlogs::error::SdkError<CreateLogGroupError>> {
+        create_log_group_request(log_group)
+            .send_with(&self.inner)
+            .await
+    }
+
+    pub async fn put_retention_policy(
+        &self,
+        log_group_name: &str,
+        retention_in_days: i32,
+    ) -> Result<PutRetentionPolicyOutput, cloudwatchlogs::error::SdkError<PutRetentionPolicyError>>
+    {
+        self.inner
+            .put_retention_policy()
+            .log_group_name(log_group_name)
+            .retention_in_days(retention_in_days)
+            .send()
+            .await
+    }
+
+    pub async fn delete_retention_policy(
+        &self,
+        log_group_name: &str,
+    ) -> Result<
+        DeleteRetentionPolicyOutput,
+        cloudwatchlogs::error::SdkError<DeleteRetentionPolicyError>,
+    > {
+        self.inner
+            .delete_retention_policy()
+            .log_group_name(log_group_name)
+            .send()
+            .await
+    }
+
+    pub async fn associate_kms_key(
+        &self,
+        log_group_name: &str,
+        kms_key_id: &str,
+    ) -> Result<AssociateKmsKeyOutput, cloudwatchlogs::error::SdkError<AssociateKmsKeyError>> {
+        self.inner
+            .associate_kms_key()
+            .log_group_name(log_group_name)
+            .kms_key_id(kms_key_id)
+            .send()
+            .await
+    }
+
+    pub async fn disassociate_kms_key(
+        &self,
+        log_group_name: &str,
+    ) -> Result<DisassociateKmsKeyOutput, cloudwatchlogs::error::SdkError<DisassociateKmsKeyError>>
+    {
+        self.inner
+            .disassociate_kms_key()
+            .log_group_name(log_group_name)
+            .send()
+            .await
+    }
+
+    pub async fn tag_resource(
+        &self,
+        resource_arn: &str,
+        tags: &[ResourceTag],
+    ) -> Result<TagResourceOutput, cloudwatchlogs::error::SdkError<TagResourceError>> {
+        tag_resource_request(resource_arn, tags)
+            .send_with(&self.inner)
+            .await
+    }
+
+    pub async fn untag_resource(
+        &self,
+        resource_arn: &str,
+        tag_keys: &[String],
+    ) -> Result<UntagResourceOutput, cloudwatchlogs::error::SdkError<UntagResourceError>> {
+        self.inner
+            .untag_resource()
+            .resource_arn(resource_arn)
+            .set_tag_keys(Some(tag_keys.to_vec()))
+            .send()
+            .await
+    }
+}
+
+// Requests which take fubura models are built apart from clients, so that cassette
+// records them as sent.
+
+pub fn create_log_group_request(log_group: &LogGroup) -> CreateLogGroupInputBuilder {
+    let tags = (!log_group.tags.is_empty()).then(|| log_group.tags_map());
+
+    CreateLogGroupInput::builder()
+        .log_group_name(&log_group.name)
+        .set_kms_key_id(log_group.kms_key_id.clone())
+        .set_tags(tags)
+}
+
+pub fn tag_resource_request(resource_arn: &str, tags: &[ResourceTag]) -> TagResourceInputBuilder {
+    let tags = tags
+        .iter()
+        .map(|tag| (tag.key.clone(), tag.value.clone()))
+        .collect();
+
+    TagResourceInput::builder()
+        .resource_arn(resource_arn)
+        .set_tags(Some(tags))
+}
+
+// Log group of exact name, as DescribeLogGroups only filters by prefix.
+pub async fn describe_log_group(
+    client: &dyn Logs,
+    log_group_name: &str,
+) -> Result<Option<LogGroup>> {
+    let mut next_token = None;
+
+    let remote = loop {
+        let res = traced(
+            "logs:DescribeLogGroups",
+            client.describe_log_groups(log_group_name, next_token),
+        )
+        .await;
+
+        let output = match res {
+            Ok(output) => output,
+            Err(err) => {
+                let service_error = err.into_service_error();
+                return Err(FuburaError::from_aws_error(
+                    &service_error,
+                    format!(
+                        "failed to describe log group({}): {}",
+                        log_group_name, service_error
+                    ),
+                ));
+            }
+        };
+
+        if let Some(log_group) = output
+            .log_groups()
+            .iter()
+            .find(|log_group| log_group.log_group_name() == Some(log_group_name))
+        {
+            break log_group.clone();
+        }
+
+        next_token = output.next_token().map(|token| token.to_string());
+        if next_token.is_none() {
+            return Ok(None);
+        }
+    };
+
+    let Some(log_group_arn) = remote.log_group_arn() else {
+        bail!("log group({}) has no arn", log_group_name);
+    };
+    let res = traced(
+        "logs:ListTagsForResource",
+        client.list_tags_for_resource(log_group_arn),
+    )
+    .await;
+
+    match res {
+        Ok(output) => Ok(Some(LogGroup::from_remote(&remote, output.tags()))),
+        Err(err) => {
+            let service_error = err.into_service_error();
+            Err(FuburaError::from_aws_error(
+                &service_error,
+                format!(
+                    "failed to list tags of log group({}): {}",
+                    log_group_name, service_error
+                ),
+            ))
+        }
+    }
+}
+
+// Retention can't be given on CreateLogGroup, so it's put after that.
+pub async fn create_log_group(client: &dyn Logs, log_group: &LogGroup) -> Result<()> {
+    let res = traced("logs:CreateLogGroup", client.create_log_group(log_group)).await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to create log group with error: {}", e),
+        ));
+    }
+
+    if let Some(retention_in_days) = log_group.retention_in_days {
+        put_retention_policy(client, &log_group.name, retention_in_days).await?;
+    }
+
+    Ok(())
+}
+
+async fn put_retention_policy(
+    client: &dyn Logs,
+    log_group_name: &str,
+    retention_in_days: i32,
+) -> Result<()> {
+    let res = traced(
+        "logs:PutRetentionPolicy",
+        client.put_retention_policy(log_group_name, retention_in_days),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to put retention policy with error: {}", e),
+        ));
+    }
+
+    Ok(())
+}
+
+// Changes only settings which differ from the remote log group.
+pub async fn update_log_group(
+    client: &dyn Logs,
+    log_group_arn: &str,
+    log_group: &LogGroup,
+) -> Result<()> {
+    let Some(remote) = describe_log_group(client, &log_group.name).await? else {
+        bail!("log group({}) to update does not exist", log_group.name);
+    };
+
+    if remote.retention_in_days != log_group.retention_in_days {
+        if let Some(retention_in_days) = log_group.retention_in_days {
+            put_retention_policy(client, &log_group.name, retention_in_days).await?;
+        } else {
+            let res = traced(
+                "logs:DeleteRetentionPolicy",
+                client.delete_retention_policy(&log_group.name),
+            )
+            .await;
+
+            if let Err(e) = res {
+                let e = e.into_service_error();
+                return Err(FuburaError::from_aws_error(
+                    &e,
+                    format!("failed to delete retention policy with error: {}", e),
+                ));
+            }
+        }
+    }
+
+    if remote.kms_key_id != log_group.kms_key_id {
+        if let Some(kms_key_id) = &log_group.kms_key_id {
+            let res = traced(
+                "logs:AssociateKmsKey",
+                client.associate_kms_key(&log_group.name, kms_key_id),
+            )
+            .await;
+
+            if let Err(e) = res {
+                let e = e.into_service_error();
+                return Err(FuburaError::from_aws_error(
+                    &e,
+                    format!("failed to associate kms key with error: {}", e),
+                ));
+            }
+        } else {
+            let res = traced(
+                "logs:DisassociateKmsKey",
+                client.disassociate_kms_key(&log_group.name),
+            )
+            .await;
+
+            if let Err(e) = res {
+                let e = e.into_service_error();
+                return Err(FuburaError::from_aws_error(
+                    &e,
+                    format!("failed to disassociate kms key with error: {}", e),
+                ));
+            }
+        }
+    }
+
+    let removed_keys: Vec<String> = remote
+        .tags
+        .iter()
+        .filter(|remote_tag| !log_group.tags.iter().any(|tag| tag.key == remote_tag.key))
+        .map(|remote_tag| remote_tag.key.clone())
+        .collect();
+    if !removed_keys.is_empty() {
+        let res = traced(
+            "logs:UntagResource",
+            client.untag_resource(log_group_arn, &removed_keys),
+        )
+        .await;
+
+        if let Err(e) = res {
+            let e = e.into_service_error();
+            return Err(FuburaError::from_aws_error(
+                &e,
+                format!("failed to untag log group with error: {}", e),
+            ));
+        }
+    }
+
+    let mut remote_tags = remote.tags.clone();
+    let mut local_tags = log_group.tags.clone();
+    remote_tags.sort();
+    local_tags.sort();
+    if !log_group.tags.is_empty() && remote_tags != local_tags {
+        let res = traced(
+            "logs:TagResource",
+            client.tag_resource(log_group_arn, &log_group.tags),
+        )
+        .await;
+
+        if let Err(e) = res {
+            let e = e.into_service_error();
+            return Err(FuburaError::from_aws_error(
+                &e,
+                format!("failed to tag log group with error: {}", e),
+            ));
+        }
+    }
+
+    Ok(())
+}
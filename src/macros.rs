@@ -32,3 +32,32 @@ macro_rules! fast_exit {
         }
     };
 }
+
+/// Prints human readable output of command, like `println!`.
+/// It goes to stderr on `--output json` to keep stdout for the JSON document.
+#[macro_export]
+macro_rules! human_println {
+    ($context:expr, $($arg:tt)*) => {
+        {
+            if $context.output_format == $crate::cli::OutputFormat::Json {
+                eprintln!($($arg)*);
+            } else {
+                println!($($arg)*);
+            }
+        }
+    };
+}
+
+/// Same as `human_println!`, but without newline, like `print!`.
+#[macro_export]
+macro_rules! human_print {
+    ($context:expr, $($arg:tt)*) => {
+        {
+            if $context.output_format == $crate::cli::OutputFormat::Json {
+                eprint!($($arg)*);
+            } else {
+                print!($($arg)*);
+            }
+        }
+    };
+}
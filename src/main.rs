@@ -1,19 +1,27 @@
+use std::fs::File;
+use std::io::Write;
 use std::path::Path;
 
+use anyhow::{Context as _, Result};
 use clap::Parser;
 
-use tracing::info;
+use tracing::{Instrument, info, info_span};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 use tracing_subscriber::prelude::*;
 
-use fubura::cli::{Cli, Commands};
+use fubura::cli::{Cli, Commands, ErrorFormat, LogFormat, OutputFormat};
 use fubura::commands::apply::ApplyCommand;
+use fubura::commands::iam_policy::IamPolicyCommand;
 use fubura::commands::import::ImportCommand;
 use fubura::commands::plan::PlanCommand;
+use fubura::commands::rollback::RollbackCommand;
+use fubura::commands::versions::VersionsCommand;
 use fubura::context::FuburaContext;
-use fubura::fast_exit;
-use fubura::types::Config;
+use fubura::error::ErrorCategory;
+use fubura::telemetry::TelemetryLayer;
+use fubura::types::{CommandOutput, CommandResult, Config};
 
-fn set_log_level(debug_mode: &bool) {
+fn set_log_level(debug_mode: &bool, cli: &Cli) -> Result<()> {
     let fubura_level = if *debug_mode {
         tracing::Level::DEBUG
     } else {
@@ -26,11 +34,6 @@ fn set_log_level(debug_mode: &bool) {
         tracing::Level::ERROR
     };
 
-    let format = tracing_subscriber::fmt::format()
-        .with_target(*debug_mode)
-        .with_timer(tracing_subscriber::fmt::time::SystemTime)
-        .compact();
-
     let filter = tracing_subscriber::filter::Targets::new()
         .with_target("fubura", fubura_level)
         .with_target("hyper", tracing::Level::ERROR)
@@ -38,51 +41,145 @@ fn set_log_level(debug_mode: &bool) {
         .with_target("rustls", tracing::Level::ERROR)
         .with_default(dependency_level);
 
+    // Keep stdout for the JSON document
+    let log_to_stderr = cli.output_format == OutputFormat::Json;
+    let log_output = || -> Box<dyn Write + Send> {
+        if log_to_stderr {
+            Box::new(std::io::stderr())
+        } else {
+            Box::new(std::io::stdout())
+        }
+    };
+
+    let text_layer = (cli.log_options.log_format == LogFormat::Text).then(|| {
+        let format = tracing_subscriber::fmt::format()
+            .with_target(*debug_mode)
+            .with_timer(tracing_subscriber::fmt::time::SystemTime)
+            .compact();
+        let writer = if log_to_stderr {
+            BoxMakeWriter::new(std::io::stderr)
+        } else {
+            BoxMakeWriter::new(std::io::stdout)
+        };
+
+        tracing_subscriber::fmt::layer()
+            .event_format(format)
+            .with_writer(writer)
+    });
+
+    let json_log_output = (cli.log_options.log_format == LogFormat::Json).then(log_output);
+    let trace_output: Option<Box<dyn Write + Send>> = match cli.log_options.trace_output.as_deref()
+    {
+        Some("-") => Some(log_output()),
+        Some(path) => Some(Box::new(
+            File::create(path).with_context(|| format!("failed to open {}", path))?,
+        )),
+        None => None,
+    };
+    let telemetry_layer = (json_log_output.is_some() || trace_output.is_some())
+        .then(|| TelemetryLayer::new(json_log_output, trace_output));
+
     tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer().event_format(format))
+        .with(text_layer)
+        .with(telemetry_layer)
         .with(filter)
         .init();
 
     info!("Set log level: {:?}", fubura_level);
+
+    Ok(())
 }
 
-#[tokio::main]
-async fn main() {
-    let cli = Cli::parse();
+fn report_error(e: &anyhow::Error, error_format: ErrorFormat) -> ! {
+    let category = ErrorCategory::of(e);
+
+    match error_format {
+        ErrorFormat::Text => eprintln!("{:#}", e),
+        ErrorFormat::Json => eprintln!(
+            "{}",
+            serde_json::json!({
+                "error": {
+                    "category": category.as_str(),
+                    "exitCode": category.exit_code(),
+                    "message": format!("{:#}", e),
+                }
+            })
+        ),
+    }
+
+    std::process::exit(category.exit_code());
+}
 
-    let result = match &cli.command {
+async fn run(cli: &Cli) -> Result<CommandResult> {
+    match &cli.command {
         Commands::Apply {
             auto_approve,
+            allow_destroy,
+            interactive,
             config_path,
             ext_str,
             target,
             json_diff_path,
+            strict,
+            canary_options,
             debug_mode,
         } => {
-            set_log_level(debug_mode);
+            set_log_level(debug_mode, cli)?;
 
-            let config = Config::load_from_path(config_path, ext_str);
-            let mut context = FuburaContext::async_default().await;
+            let config = Config::load_from_path(config_path, ext_str)?;
+            let mut context =
+                FuburaContext::from_options(&cli.aws_options, &cli.backend_options).await?;
+            context.output_format = cli.output_format;
+            context.strict = *strict;
+            context.prepare_deploy_targets(&config).await?;
             context.targets.clone_from(target);
             context.json_diff_path.clone_from(json_diff_path);
 
-            ApplyCommand::run(&context, auto_approve, &config).await
+            let result = ApplyCommand::run(
+                &context,
+                auto_approve,
+                allow_destroy,
+                interactive,
+                canary_options,
+                &config,
+            )
+            .instrument(info_span!("command", name = "apply"))
+            .await
+            .map(CommandResult::Apply);
+
+            // Keep applied resources for next run
+            if let (Some(memory_backend), Some(path)) = (
+                &context.memory_backend,
+                &cli.backend_options.memory_snapshot_path,
+            ) {
+                memory_backend.save(path)?;
+            }
+
+            result
         }
         Commands::Plan {
             config_path,
             ext_str,
             target,
             json_diff_path,
+            strict,
             debug_mode,
         } => {
-            set_log_level(debug_mode);
+            set_log_level(debug_mode, cli)?;
 
-            let config = Config::load_from_path(config_path, ext_str);
-            let mut context = FuburaContext::async_default().await;
+            let config = Config::load_from_path(config_path, ext_str)?;
+            let mut context =
+                FuburaContext::from_options(&cli.aws_options, &cli.backend_options).await?;
+            context.output_format = cli.output_format;
+            context.strict = *strict;
+            context.prepare_deploy_targets(&config).await?;
             context.targets.clone_from(target);
             context.json_diff_path.clone_from(json_diff_path);
 
-            PlanCommand::run(&context, &config).await
+            PlanCommand::run(&context, &config)
+                .instrument(info_span!("command", name = "plan"))
+                .await
+                .map(CommandResult::Plan)
         }
         Commands::Import {
             config_path,
@@ -91,16 +188,18 @@ async fn main() {
             schedule_name_with_group,
             debug_mode,
         } => {
-            set_log_level(debug_mode);
+            set_log_level(debug_mode, cli)?;
 
             let config_exist = Path::new(config_path).exists();
             let config = if config_exist {
-                Config::load_from_path(config_path, ext_str)
+                Config::load_from_path(config_path, ext_str)?
             } else {
                 Config::default()
             };
 
-            let context = FuburaContext::async_default().await;
+            let mut context =
+                FuburaContext::from_options(&cli.aws_options, &cli.backend_options).await?;
+            context.output_format = cli.output_format;
 
             ImportCommand::run(
                 &context,
@@ -109,11 +208,89 @@ async fn main() {
                 sfn_name,
                 schedule_name_with_group,
             )
+            .instrument(info_span!("command", name = "import"))
             .await
+            .map(CommandResult::Import)
         }
-    };
+        Commands::Rollback {
+            state_name,
+            to_version,
+            alias,
+            auto_approve,
+            debug_mode,
+        } => {
+            set_log_level(debug_mode, cli)?;
+
+            let mut context =
+                FuburaContext::from_options(&cli.aws_options, &cli.backend_options).await?;
+            context.output_format = cli.output_format;
 
-    if let Err(e) = result {
-        fast_exit!("{}", e);
+            let result =
+                RollbackCommand::run(&context, state_name, to_version, alias, auto_approve)
+                    .instrument(info_span!("command", name = "rollback"))
+                    .await
+                    .map(CommandResult::Rollback);
+
+            // Keep rolled back resources for next run
+            if let (Some(memory_backend), Some(path)) = (
+                &context.memory_backend,
+                &cli.backend_options.memory_snapshot_path,
+            ) {
+                memory_backend.save(path)?;
+            }
+
+            result
+        }
+        Commands::IamPolicy {
+            config_path,
+            ext_str,
+            target,
+            merge,
+            debug_mode,
+        } => {
+            set_log_level(debug_mode, cli)?;
+
+            let config = Config::load_from_path(config_path, ext_str)?;
+            let mut context =
+                FuburaContext::from_options(&cli.aws_options, &cli.backend_options).await?;
+            context.output_format = cli.output_format;
+            context.prepare_deploy_targets(&config).await?;
+            context.targets.clone_from(target);
+
+            IamPolicyCommand::run(&context, &config, merge)
+                .instrument(info_span!("command", name = "iam_policy"))
+                .await
+                .map(CommandResult::IamPolicy)
+        }
+        Commands::Versions {
+            state_name,
+            debug_mode,
+        } => {
+            set_log_level(debug_mode, cli)?;
+
+            let mut context =
+                FuburaContext::from_options(&cli.aws_options, &cli.backend_options).await?;
+            context.output_format = cli.output_format;
+
+            VersionsCommand::run(&context, state_name)
+                .instrument(info_span!("command", name = "versions"))
+                .await
+                .map(CommandResult::Versions)
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    match run(&cli).await {
+        Ok(result) => {
+            if cli.output_format == OutputFormat::Json {
+                let output = CommandOutput::new(result);
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            }
+        }
+        Err(e) => report_error(&e, cli.error_format),
     }
 }
@@ -0,0 +1,2707 @@
+use std::collections::{BTreeMap, HashSet};
+use std::future::ready;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::{Context as _, Result};
+use aws_sdk_cloudwatchlogs::operation::associate_kms_key::{
+    AssociateKmsKeyError, AssociateKmsKeyOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::create_log_group::{
+    CreateLogGroupError, CreateLogGroupOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::delete_retention_policy::{
+    DeleteRetentionPolicyError, DeleteRetentionPolicyOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::describe_log_groups::{
+    DescribeLogGroupsError, DescribeLogGroupsOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::disassociate_kms_key::{
+    DisassociateKmsKeyError, DisassociateKmsKeyOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::list_tags_for_resource::{
+    ListTagsForResourceError as ListLogGroupTagsError,
+    ListTagsForResourceOutput as ListLogGroupTagsOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::put_retention_policy::{
+    PutRetentionPolicyError, PutRetentionPolicyOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::tag_resource::{
+    TagResourceError as TagLogGroupError, TagResourceOutput as TagLogGroupOutput,
+};
+use aws_sdk_cloudwatchlogs::operation::untag_resource::{
+    UntagResourceError as UntagLogGroupError, UntagResourceOutput as UntagLogGroupOutput,
+};
+use aws_sdk_eventbridge::operation::delete_rule::{DeleteRuleError, DeleteRuleOutput};
+use aws_sdk_eventbridge::operation::describe_rule::{DescribeRuleError, DescribeRuleOutput};
+use aws_sdk_eventbridge::operation::list_targets_by_rule::{
+    ListTargetsByRuleError, ListTargetsByRuleOutput,
+};
+use aws_sdk_eventbridge::operation::put_rule::{PutRuleError, PutRuleOutput};
+use aws_sdk_eventbridge::operation::put_targets::{PutTargetsError, PutTargetsOutput};
+use aws_sdk_eventbridge::operation::remove_targets::{RemoveTargetsError, RemoveTargetsOutput};
+use aws_sdk_scheduler::operation::create_schedule::{CreateScheduleError, CreateScheduleOutput};
+use aws_sdk_scheduler::operation::create_schedule_group::{
+    CreateScheduleGroupError, CreateScheduleGroupOutput,
+};
+use aws_sdk_scheduler::operation::delete_schedule::{DeleteScheduleError, DeleteScheduleOutput};
+use aws_sdk_scheduler::operation::delete_schedule_group::{
+    DeleteScheduleGroupError, DeleteScheduleGroupOutput,
+};
+use aws_sdk_scheduler::operation::get_schedule::{GetScheduleError, GetScheduleOutput};
+use aws_sdk_scheduler::operation::get_schedule_group::{
+    GetScheduleGroupError, GetScheduleGroupOutput,
+};
+use aws_sdk_scheduler::operation::list_schedules::{ListSchedulesError, ListSchedulesOutput};
+use aws_sdk_scheduler::operation::list_tags_for_resource::{
+    ListTagsForResourceError as ListScheduleTagsError,
+    ListTagsForResourceOutput as ListScheduleTagsOutput,
+};
+use aws_sdk_scheduler::operation::tag_resource::{
+    TagResourceError as TagScheduleResourceError, TagResourceOutput as TagScheduleResourceOutput,
+};
+use aws_sdk_scheduler::operation::untag_resource::{
+    UntagResourceError as UntagScheduleResourceError,
+    UntagResourceOutput as UntagScheduleResourceOutput,
+};
+use aws_sdk_scheduler::operation::update_schedule::{UpdateScheduleError, UpdateScheduleOutput};
+use aws_sdk_sfn::operation::create_state_machine::{
+    CreateStateMachineError, CreateStateMachineOutput,
+};
+use aws_sdk_sfn::operation::create_state_machine_alias::{
+    CreateStateMachineAliasError, CreateStateMachineAliasOutput,
+};
+use aws_sdk_sfn::operation::delete_state_machine::{
+    DeleteStateMachineError, DeleteStateMachineOutput,
+};
+use aws_sdk_sfn::operation::delete_state_machine_alias::{
+    DeleteStateMachineAliasError, DeleteStateMachineAliasOutput,
+};
+use aws_sdk_sfn::operation::describe_execution::{DescribeExecutionError, DescribeExecutionOutput};
+use aws_sdk_sfn::operation::describe_state_machine::{
+    DescribeStateMachineError, DescribeStateMachineOutput,
+};
+use aws_sdk_sfn::operation::describe_state_machine_alias::{
+    DescribeStateMachineAliasError, DescribeStateMachineAliasOutput,
+};
+use aws_sdk_sfn::operation::list_executions::{ListExecutionsError, ListExecutionsOutput};
+use aws_sdk_sfn::operation::list_state_machine_aliases::{
+    ListStateMachineAliasesError, ListStateMachineAliasesOutput,
+};
+use aws_sdk_sfn::operation::list_state_machine_versions::{
+    ListStateMachineVersionsError, ListStateMachineVersionsOutput,
+};
+use aws_sdk_sfn::operation::list_tags_for_resource::{
+    ListTagsForResourceError, ListTagsForResourceOutput,
+};
+use aws_sdk_sfn::operation::tag_resource::{TagResourceError, TagResourceOutput};
+use aws_sdk_sfn::operation::untag_resource::{UntagResourceError, UntagResourceOutput};
+use aws_sdk_sfn::operation::update_state_machine::{
+    UpdateStateMachineError, UpdateStateMachineOutput,
+};
+use aws_sdk_sfn::operation::update_state_machine_alias::{
+    UpdateStateMachineAliasError, UpdateStateMachineAliasOutput,
+};
+use aws_sdk_sfn::primitives::{DateTime, DateTimeFormat};
+use aws_sdk_sts::operation::get_caller_identity::{
+    GetCallerIdentityError, GetCallerIdentityOutput,
+};
+use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_runtime_api::http::{Response, StatusCode};
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::error::ErrorMetadata;
+use serde::{Deserialize, Serialize};
+
+use crate::context::BoxFuture;
+use crate::events::Events;
+use crate::logs::Logs;
+use crate::scheduler::Scheduler;
+use crate::sfn::Sfn;
+use crate::sts::Sts;
+use crate::types::{
+    ArnBuilder, DEFAULT_EVENT_BUS_NAME, DEFAULT_SCHEDULE_GROUP_NAME, EncryptionConfiguration,
+    Execution, LogGroup, Partition, ResourceTag, Rule, RuleTarget, Schedule, ScheduleGroup,
+    StateMachine, StateMachineAlias, state_machine_version_number,
+};
+
+pub const DEFAULT_ACCOUNT: &str = "123456789012";
+
+// Resources held by memory backend, which is also the format of snapshot file.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MemorySnapshot {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(default)]
+    pub state_machines: Vec<StateMachine>,
+    #[serde(default)]
+    pub schedules: Vec<Schedule>,
+    // except default group, which always exists
+    #[serde(default)]
+    pub schedule_groups: Vec<ScheduleGroup>,
+    // EventBridge rules, each with one target at most
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>,
+    // CloudWatch Logs log groups
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log_groups: Vec<LogGroup>,
+    // published versions of state machines above
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_machine_versions: Vec<MemoryStateMachineVersion>,
+    // executions are never started by memory backend, so they are only given by snapshot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executions: Vec<Execution>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStateMachineVersion {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    // RFC 3339, like "2024-01-01T00:00:00Z"
+    pub creation_date: String,
+    // state machine as of the version, without tags
+    pub state: StateMachine,
+}
+
+struct StoredStateMachine {
+    state: StateMachine,
+    creation_date: DateTime,
+    // ordered by version number
+    versions: Vec<StoredVersion>,
+    // keyed by alias name
+    aliases: BTreeMap<String, StoredAlias>,
+}
+
+struct StoredAlias {
+    alias: StateMachineAlias,
+    creation_date: DateTime,
+}
+
+struct StoredVersion {
+    version: u32,
+    description: Option<String>,
+    creation_date: DateTime,
+    state: StateMachine,
+}
+
+impl StoredStateMachine {
+    fn new(mut state: StateMachine, creation_date: DateTime) -> Self {
+        let aliases = state
+            .aliases
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|alias| {
+                (
+                    alias.name.clone(),
+                    StoredAlias {
+                        alias,
+                        creation_date,
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            state,
+            creation_date,
+            versions: vec![],
+            aliases,
+        }
+    }
+
+    // State machine with its aliases, as written in snapshot
+    fn state_with_aliases(&self) -> StateMachine {
+        let mut state = self.state.clone();
+        if !self.aliases.is_empty() {
+            state.aliases = Some(
+                self.aliases
+                    .values()
+                    .map(|stored| stored.alias.clone())
+                    .collect(),
+            );
+        }
+
+        state
+    }
+
+    // Version numbers are never reused, as AWS does.
+    fn publish(&mut self, description: Option<String>) -> u32 {
+        let version = self.versions.last().map_or(1, |v| v.version + 1);
+        let mut state = self.state.clone();
+        state.tags = vec![];
+
+        self.versions.push(StoredVersion {
+            version,
+            description,
+            creation_date: DateTime::from(SystemTime::now()),
+            state,
+        });
+
+        version
+    }
+}
+
+/// Fake of Step Functions, EventBridge Scheduler, EventBridge and STS which keeps resources in memory.
+///
+/// Errors follow AWS: missing resources return not found errors, creating an existing
+/// resource returns conflict errors, and tagging merges tags by key.
+pub struct MemoryBackend {
+    arn_builder: ArnBuilder,
+    // keyed by state machine arn
+    state_machines: Mutex<BTreeMap<String, StoredStateMachine>>,
+    // keyed by (group name, schedule name)
+    schedules: Mutex<BTreeMap<(String, String), Schedule>>,
+    // keyed by group name
+    schedule_groups: Mutex<BTreeMap<String, ScheduleGroup>>,
+    // keyed by (event bus name, rule name). A rule holds one target at most, which is
+    // enough for rules managed by fubura.
+    rules: Mutex<BTreeMap<(String, String), Rule>>,
+    // keyed by log group name
+    log_groups: Mutex<BTreeMap<String, LogGroup>>,
+    executions: Mutex<Vec<Execution>>,
+}
+
+fn service_error<E>(err: E, status: u16) -> SdkError<E, Response> {
+    SdkError::service_error(
+        err,
+        Response::new(StatusCode::try_from(status).unwrap(), SdkBody::empty()),
+    )
+}
+
+fn error_meta(code: &str, message: &str) -> ErrorMetadata {
+    ErrorMetadata::builder().code(code).message(message).build()
+}
+
+// Modeled errors carry their code in metadata as errors deserialized by aws-sdk do,
+// so that they can be recorded and replayed, see `cassette`.
+pub(crate) fn state_machine_does_not_exist(
+    message: &str,
+) -> aws_sdk_sfn::types::error::StateMachineDoesNotExist {
+    aws_sdk_sfn::types::error::StateMachineDoesNotExist::builder()
+        .message(message)
+        .meta(error_meta("StateMachineDoesNotExist", message))
+        .build()
+}
+
+pub(crate) fn state_machine_already_exists(
+    message: &str,
+) -> aws_sdk_sfn::types::error::StateMachineAlreadyExists {
+    aws_sdk_sfn::types::error::StateMachineAlreadyExists::builder()
+        .message(message)
+        .meta(error_meta("StateMachineAlreadyExists", message))
+        .build()
+}
+
+pub(crate) fn invalid_logging_configuration(
+    message: &str,
+) -> aws_sdk_sfn::types::error::InvalidLoggingConfiguration {
+    aws_sdk_sfn::types::error::InvalidLoggingConfiguration::builder()
+        .message(message)
+        .meta(error_meta("InvalidLoggingConfiguration", message))
+        .build()
+}
+
+pub(crate) fn sfn_validation_error(
+    message: &str,
+) -> aws_sdk_sfn::types::error::ValidationException {
+    aws_sdk_sfn::types::error::ValidationException::builder()
+        .message(message)
+        .meta(error_meta("ValidationException", message))
+        .build()
+}
+
+pub(crate) fn sfn_conflict(message: &str) -> aws_sdk_sfn::types::error::ConflictException {
+    aws_sdk_sfn::types::error::ConflictException::builder()
+        .message(message)
+        .meta(error_meta("ConflictException", message))
+        .build()
+}
+
+pub(crate) fn resource_not_found(message: &str) -> aws_sdk_sfn::types::error::ResourceNotFound {
+    aws_sdk_sfn::types::error::ResourceNotFound::builder()
+        .message(message)
+        .meta(error_meta("ResourceNotFound", message))
+        .build()
+}
+
+pub(crate) fn schedule_not_found(
+    message: &str,
+) -> aws_sdk_scheduler::types::error::ResourceNotFoundException {
+    aws_sdk_scheduler::types::error::ResourceNotFoundException::builder()
+        .message(message)
+        .meta(error_meta("ResourceNotFoundException", message))
+        .build()
+        .unwrap()
+}
+
+pub(crate) fn schedule_validation_error(
+    message: &str,
+) -> aws_sdk_scheduler::types::error::ValidationException {
+    aws_sdk_scheduler::types::error::ValidationException::builder()
+        .message(message)
+        .meta(error_meta("ValidationException", message))
+        .build()
+        .unwrap()
+}
+
+pub(crate) fn schedule_conflict(
+    message: &str,
+) -> aws_sdk_scheduler::types::error::ConflictException {
+    aws_sdk_scheduler::types::error::ConflictException::builder()
+        .message(message)
+        .meta(error_meta("ConflictException", message))
+        .build()
+        .unwrap()
+}
+
+pub(crate) fn rule_not_found(
+    message: &str,
+) -> aws_sdk_eventbridge::types::error::ResourceNotFoundException {
+    aws_sdk_eventbridge::types::error::ResourceNotFoundException::builder()
+        .message(message)
+        .meta(error_meta("ResourceNotFoundException", message))
+        .build()
+}
+
+pub(crate) fn log_group_not_found(
+    message: &str,
+) -> aws_sdk_cloudwatchlogs::types::error::ResourceNotFoundException {
+    aws_sdk_cloudwatchlogs::types::error::ResourceNotFoundException::builder()
+        .message(message)
+        .meta(error_meta("ResourceNotFoundException", message))
+        .build()
+}
+
+pub(crate) fn log_group_already_exists(
+    message: &str,
+) -> aws_sdk_cloudwatchlogs::types::error::ResourceAlreadyExistsException {
+    aws_sdk_cloudwatchlogs::types::error::ResourceAlreadyExistsException::builder()
+        .message(message)
+        .meta(error_meta("ResourceAlreadyExistsException", message))
+        .build()
+}
+
+fn state_machine_does_not_exist_message(state_arn: &str) -> String {
+    format!("State Machine Does Not Exist: '{}'", state_arn)
+}
+
+fn resource_not_found_message(state_arn: &str) -> String {
+    format!("Resource not found: '{}'", state_arn)
+}
+
+fn schedule_not_found_message(group_name: &str, schedule_name: &str) -> String {
+    format!(
+        "Schedule {} does not exist in group {}.",
+        schedule_name, group_name
+    )
+}
+
+fn schedule_group_not_found_message(group_name: &str) -> String {
+    format!("Schedule group {} does not exist.", group_name)
+}
+
+fn rule_not_found_message(event_bus_name: &str, rule_name: &str) -> String {
+    format!(
+        "Rule {} does not exist on EventBus {}.",
+        rule_name, event_bus_name
+    )
+}
+
+fn log_group_not_found_message() -> String {
+    "The specified log group does not exist.".to_string()
+}
+
+// AWS uses default event bus when bus name is omitted.
+fn rule_key(event_bus_name: &str, rule_name: &str) -> (String, String) {
+    let event_bus_name = if event_bus_name.is_empty() {
+        DEFAULT_EVENT_BUS_NAME
+    } else {
+        event_bus_name
+    };
+
+    (event_bus_name.to_string(), rule_name.to_string())
+}
+
+// AWS uses default group when group name is omitted.
+fn schedule_key(group_name: &str, schedule_name: &str) -> (String, String) {
+    let group_name = if group_name.is_empty() {
+        DEFAULT_SCHEDULE_GROUP_NAME
+    } else {
+        group_name
+    };
+
+    (group_name.to_string(), schedule_name.to_string())
+}
+
+// Later tags win over former ones with the same key, as TagResource does.
+// Rejects logging configuration which can't be sent to AWS, as AWS does.
+fn validate_logging_configuration(
+    state: &StateMachine,
+) -> Result<(), aws_sdk_sfn::types::error::InvalidLoggingConfiguration> {
+    let Some(logging_configuration) = &state.logging_configuration else {
+        return Ok(());
+    };
+
+    aws_sdk_sfn::types::LoggingConfiguration::try_from(logging_configuration.clone())
+        .map(|_| ())
+        .map_err(|e| invalid_logging_configuration(&e.to_string()))
+}
+
+// (state machine arn, alias name) of alias arn, which is state machine arn followed by ":<alias>"
+fn split_alias_arn(alias_arn: &str) -> Option<(&str, &str)> {
+    if alias_arn.split(':').count() != 8 || state_machine_version_number(alias_arn).is_some() {
+        return None;
+    }
+
+    alias_arn.rsplit_once(':')
+}
+
+enum RoutingError {
+    Invalid(String),
+    VersionNotFound(String),
+}
+
+// Returns arn of state machine which versions in routing configuration belong to.
+fn validate_routing_configuration(
+    state_machines: &BTreeMap<String, StoredStateMachine>,
+    alias: &StateMachineAlias,
+) -> Result<String, RoutingError> {
+    let routing_configuration = &alias.routing_configuration;
+    if routing_configuration.is_empty() || routing_configuration.len() > 2 {
+        return Err(RoutingError::Invalid(format!(
+            "routingConfiguration must have 1 or 2 versions, but got {}",
+            routing_configuration.len()
+        )));
+    }
+    let total_weight: i32 = routing_configuration.iter().map(|item| item.weight).sum();
+    if total_weight != 100 {
+        return Err(RoutingError::Invalid(format!(
+            "sum of weights in routingConfiguration must be 100, but got {}",
+            total_weight
+        )));
+    }
+
+    let mut state_arns = HashSet::new();
+    for item in routing_configuration {
+        let version_arn = &item.state_machine_version_arn;
+        let Some(version) = state_machine_version_number(version_arn) else {
+            return Err(RoutingError::Invalid(format!(
+                "invalid state machine version arn: '{}'",
+                version_arn
+            )));
+        };
+        let state_arn = version_arn.rsplit_once(':').unwrap().0;
+        let exists = state_machines
+            .get(state_arn)
+            .is_some_and(|stored| stored.versions.iter().any(|v| v.version == version));
+        if !exists {
+            return Err(RoutingError::VersionNotFound(resource_not_found_message(
+                version_arn,
+            )));
+        }
+        state_arns.insert(state_arn);
+    }
+    if state_arns.len() > 1 {
+        return Err(RoutingError::Invalid(
+            "versions in routingConfiguration must belong to the same state machine".to_string(),
+        ));
+    }
+
+    Ok(state_arns.into_iter().next().unwrap().to_string())
+}
+
+fn parse_execution_date(date: &str) -> DateTime {
+    DateTime::from_str(date, DateTimeFormat::DateTime)
+        .unwrap_or_else(|_| DateTime::from(SystemTime::now()))
+}
+
+fn merge_tags(tags: &mut Vec<ResourceTag>, new_tags: &[ResourceTag]) {
+    for new_tag in new_tags {
+        if let Some(tag) = tags.iter_mut().find(|tag| tag.key == new_tag.key) {
+            tag.value.clone_from(&new_tag.value);
+        } else {
+            tags.push(new_tag.clone());
+        }
+    }
+}
+
+// Operations return SdkError as aws-sdk clients do.
+#[allow(clippy::result_large_err)]
+impl MemoryBackend {
+    pub fn new(account: &str, region: &str) -> Self {
+        Self {
+            arn_builder: ArnBuilder::new(Partition::from_region(region), region, account),
+            state_machines: Mutex::new(BTreeMap::new()),
+            schedules: Mutex::new(BTreeMap::new()),
+            schedule_groups: Mutex::new(BTreeMap::new()),
+            rules: Mutex::new(BTreeMap::new()),
+            log_groups: Mutex::new(BTreeMap::new()),
+            executions: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn from_snapshot(snapshot: MemorySnapshot, region: &str) -> Self {
+        let account = snapshot.account.as_deref().unwrap_or(DEFAULT_ACCOUNT);
+        let backend = Self::new(account, region);
+
+        {
+            let mut state_machines = backend.state_machines.lock().unwrap();
+            for state in snapshot.state_machines {
+                state_machines.insert(
+                    backend.arn_builder.state_machine(&state.name),
+                    StoredStateMachine::new(state, DateTime::from(SystemTime::now())),
+                );
+            }
+            for version in snapshot.state_machine_versions {
+                let state_arn = backend.arn_builder.state_machine(&version.state.name);
+                // versions of missing state machine are dropped, as AWS deletes them together
+                if let Some(stored) = state_machines.get_mut(&state_arn) {
+                    stored.versions.push(StoredVersion {
+                        version: version.version,
+                        description: version.description,
+                        creation_date: DateTime::from_str(
+                            &version.creation_date,
+                            DateTimeFormat::DateTime,
+                        )
+                        .unwrap_or_else(|_| DateTime::from(SystemTime::now())),
+                        state: version.state,
+                    });
+                }
+            }
+            for stored in state_machines.values_mut() {
+                stored.versions.sort_by_key(|v| v.version);
+            }
+
+            let mut schedules = backend.schedules.lock().unwrap();
+            for mut schedule in snapshot.schedules {
+                let key = schedule_key(&schedule.group_name, &schedule.name);
+                schedule.group_name.clone_from(&key.0);
+                schedules.insert(key, schedule);
+            }
+
+            let mut schedule_groups = backend.schedule_groups.lock().unwrap();
+            for group in snapshot.schedule_groups {
+                schedule_groups.insert(group.name.clone(), group);
+            }
+
+            let mut rules = backend.rules.lock().unwrap();
+            for rule in snapshot.rules {
+                rules.insert(rule_key(&rule.event_bus_name, &rule.name), rule);
+            }
+
+            let mut log_groups = backend.log_groups.lock().unwrap();
+            for log_group in snapshot.log_groups {
+                log_groups.insert(log_group.name.clone(), log_group);
+            }
+
+            *backend.executions.lock().unwrap() = snapshot.executions;
+        }
+
+        backend
+    }
+
+    // Load snapshot file if it exists, or start with no resources.
+    pub fn load(path: &str, region: &str) -> Result<Self> {
+        let snapshot = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse memory snapshot '{}'", path))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => MemorySnapshot::default(),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("failed to read memory snapshot '{}'", path));
+            }
+        };
+
+        Ok(Self::from_snapshot(snapshot, region))
+    }
+
+    pub fn account(&self) -> &str {
+        &self.arn_builder.account
+    }
+
+    pub fn region(&self) -> &str {
+        &self.arn_builder.region
+    }
+
+    pub fn snapshot(&self) -> MemorySnapshot {
+        let state_machines = self.state_machines.lock().unwrap();
+        let schedules = self.schedules.lock().unwrap();
+        let schedule_groups = self.schedule_groups.lock().unwrap();
+        let rules = self.rules.lock().unwrap();
+        let log_groups = self.log_groups.lock().unwrap();
+
+        MemorySnapshot {
+            account: Some(self.account().to_string()),
+            state_machines: state_machines
+                .values()
+                .map(|stored| stored.state_with_aliases())
+                .collect(),
+            schedules: schedules.values().cloned().collect(),
+            schedule_groups: schedule_groups.values().cloned().collect(),
+            rules: rules.values().cloned().collect(),
+            log_groups: log_groups.values().cloned().collect(),
+            state_machine_versions: state_machines
+                .values()
+                .flat_map(|stored| stored.versions.iter())
+                .map(|v| MemoryStateMachineVersion {
+                    version: v.version,
+                    description: v.description.clone(),
+                    creation_date: v.creation_date.fmt(DateTimeFormat::DateTime).unwrap(),
+                    state: v.state.clone(),
+                })
+                .collect(),
+            executions: self.executions.lock().unwrap().clone(),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self.snapshot())?;
+
+        std::fs::write(path, content)
+            .with_context(|| format!("failed to write memory snapshot '{}'", path))
+    }
+
+    fn describe_state_machine_sync(
+        &self,
+        state_arn: &str,
+    ) -> Result<DescribeStateMachineOutput, SdkError<DescribeStateMachineError, Response>> {
+        let state_machines = self.state_machines.lock().unwrap();
+        // version arn is state machine arn followed by ":<version>"
+        let version = state_machine_version_number(state_arn);
+        let stored = match version {
+            Some(_) => state_machines.get(state_arn.rsplit_once(':').unwrap().0),
+            None => state_machines.get(state_arn),
+        };
+        let found = stored.and_then(|stored| match version {
+            Some(version) => stored
+                .versions
+                .iter()
+                .find(|v| v.version == version)
+                .map(|v| (&v.state, v.description.clone(), v.creation_date)),
+            None => Some((&stored.state, None, stored.creation_date)),
+        });
+        let Some((state, description, creation_date)) = found else {
+            return Err(service_error(
+                DescribeStateMachineError::StateMachineDoesNotExist(state_machine_does_not_exist(
+                    &state_machine_does_not_exist_message(state_arn),
+                )),
+                400,
+            ));
+        };
+
+        let mut output = state.to_describe_state_machine_output(state_arn, creation_date);
+        output.description = description;
+
+        Ok(output)
+    }
+
+    fn list_tags_for_resource_sync(
+        &self,
+        state_arn: &str,
+    ) -> Result<ListTagsForResourceOutput, SdkError<ListTagsForResourceError, Response>> {
+        let state_machines = self.state_machines.lock().unwrap();
+        let Some(stored) = state_machines.get(state_arn) else {
+            return Err(service_error(
+                ListTagsForResourceError::ResourceNotFound(resource_not_found(
+                    &resource_not_found_message(state_arn),
+                )),
+                400,
+            ));
+        };
+
+        Ok(ListTagsForResourceOutput::builder()
+            .set_tags(Some(
+                stored
+                    .state
+                    .tags
+                    .iter()
+                    .cloned()
+                    .map(|t| t.into())
+                    .collect(),
+            ))
+            .build())
+    }
+
+    fn create_state_machine_sync(
+        &self,
+        state: &StateMachine,
+    ) -> Result<CreateStateMachineOutput, SdkError<CreateStateMachineError, Response>> {
+        if let Err(e) = validate_logging_configuration(state) {
+            return Err(service_error(
+                CreateStateMachineError::InvalidLoggingConfiguration(e),
+                400,
+            ));
+        }
+
+        let state_arn = self.arn_builder.state_machine(&state.name);
+        let mut state_machines = self.state_machines.lock().unwrap();
+        if state_machines.contains_key(&state_arn) {
+            return Err(service_error(
+                CreateStateMachineError::StateMachineAlreadyExists(state_machine_already_exists(
+                    &format!("State Machine Already Exists: '{}'", state_arn),
+                )),
+                400,
+            ));
+        }
+
+        let mut state = state.clone();
+        // publish settings are a part of request, not of state machine
+        let publish = std::mem::take(&mut state.publish);
+        let version_description = state.version_description.take();
+        // aliases and log group are created by their own api
+        state.aliases = None;
+        state.log_group = None;
+        let tags = std::mem::take(&mut state.tags);
+        merge_tags(&mut state.tags, &tags);
+
+        let creation_date = DateTime::from(SystemTime::now());
+        let mut stored = StoredStateMachine::new(state, creation_date);
+        let version_arn = publish.then(|| {
+            let version = stored.publish(version_description);
+            self.arn_builder
+                .state_machine_version(&stored.state.name, version)
+        });
+        state_machines.insert(state_arn.clone(), stored);
+
+        Ok(CreateStateMachineOutput::builder()
+            .state_machine_arn(state_arn)
+            .set_state_machine_version_arn(version_arn)
+            .creation_date(creation_date)
+            .build()
+            .unwrap())
+    }
+
+    fn update_state_machine_sync(
+        &self,
+        state_arn: &str,
+        state: &StateMachine,
+    ) -> Result<UpdateStateMachineOutput, SdkError<UpdateStateMachineError, Response>> {
+        if let Err(e) = validate_logging_configuration(state) {
+            return Err(service_error(
+                UpdateStateMachineError::InvalidLoggingConfiguration(e),
+                400,
+            ));
+        }
+
+        let mut state_machines = self.state_machines.lock().unwrap();
+        let Some(stored) = state_machines.get_mut(state_arn) else {
+            return Err(service_error(
+                UpdateStateMachineError::StateMachineDoesNotExist(state_machine_does_not_exist(
+                    &state_machine_does_not_exist_message(state_arn),
+                )),
+                400,
+            ));
+        };
+
+        // Omitted configurations are kept as is, and type and tags are not updatable.
+        stored.state.definition = state.definition.clone();
+        stored.state.role_arn.clone_from(&state.role_arn);
+        if let Some(logging_configuration) = &state.logging_configuration {
+            stored.state.logging_configuration = Some(logging_configuration.clone());
+        }
+        if let Some(tracing_configuration) = &state.tracing_configuration {
+            stored.state.tracing_configuration = Some(tracing_configuration.clone());
+        }
+        stored.state.encryption_configuration = state
+            .encryption_configuration
+            .clone()
+            .filter(|ec| *ec != EncryptionConfiguration::aws_owned_key());
+        let version_arn = state.publish.then(|| {
+            let version = stored.publish(state.version_description.clone());
+            format!("{}:{}", state_arn, version)
+        });
+
+        Ok(UpdateStateMachineOutput::builder()
+            .update_date(DateTime::from(SystemTime::now()))
+            .set_state_machine_version_arn(version_arn)
+            .build()
+            .unwrap())
+    }
+
+    fn delete_state_machine_sync(&self, state_arn: &str) -> DeleteStateMachineOutput {
+        // Deleting missing state machine succeeds, as AWS does.
+        self.state_machines.lock().unwrap().remove(state_arn);
+
+        DeleteStateMachineOutput::builder().build()
+    }
+
+    fn tag_resource_sync(
+        &self,
+        state_arn: &str,
+        tags: &[ResourceTag],
+    ) -> Result<TagResourceOutput, SdkError<TagResourceError, Response>> {
+        let mut state_machines = self.state_machines.lock().unwrap();
+        let Some(stored) = state_machines.get_mut(state_arn) else {
+            return Err(service_error(
+                TagResourceError::ResourceNotFound(resource_not_found(
+                    &resource_not_found_message(state_arn),
+                )),
+                400,
+            ));
+        };
+
+        merge_tags(&mut stored.state.tags, tags);
+
+        Ok(TagResourceOutput::builder().build())
+    }
+
+    fn untag_resource_sync(
+        &self,
+        state_arn: &str,
+        tag_keys: &[String],
+    ) -> Result<UntagResourceOutput, SdkError<UntagResourceError, Response>> {
+        let mut state_machines = self.state_machines.lock().unwrap();
+        let Some(stored) = state_machines.get_mut(state_arn) else {
+            return Err(service_error(
+                UntagResourceError::ResourceNotFound(resource_not_found(
+                    &resource_not_found_message(state_arn),
+                )),
+                400,
+            ));
+        };
+
+        stored.state.tags.retain(|tag| !tag_keys.contains(&tag.key));
+
+        Ok(UntagResourceOutput::builder().build())
+    }
+
+    fn list_state_machine_aliases_sync(
+        &self,
+        state_arn: &str,
+    ) -> Result<ListStateMachineAliasesOutput, SdkError<ListStateMachineAliasesError, Response>>
+    {
+        let state_machines = self.state_machines.lock().unwrap();
+        let Some(stored) = state_machines.get(state_arn) else {
+            return Err(service_error(
+                ListStateMachineAliasesError::StateMachineDoesNotExist(
+                    state_machine_does_not_exist(&state_machine_does_not_exist_message(state_arn)),
+                ),
+                400,
+            ));
+        };
+
+        let aliases = stored
+            .aliases
+            .iter()
+            .map(|(name, stored_alias)| {
+                aws_sdk_sfn::types::StateMachineAliasListItem::builder()
+                    .state_machine_alias_arn(format!("{}:{}", state_arn, name))
+                    .creation_date(stored_alias.creation_date)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        Ok(ListStateMachineAliasesOutput::builder()
+            .set_state_machine_aliases(Some(aliases))
+            .build()
+            .unwrap())
+    }
+
+    fn describe_state_machine_alias_sync(
+        &self,
+        alias_arn: &str,
+    ) -> Result<DescribeStateMachineAliasOutput, SdkError<DescribeStateMachineAliasError, Response>>
+    {
+        let state_machines = self.state_machines.lock().unwrap();
+        let stored_alias = split_alias_arn(alias_arn).and_then(|(state_arn, name)| {
+            state_machines
+                .get(state_arn)
+                .and_then(|stored| stored.aliases.get(name))
+        });
+        let Some(stored_alias) = stored_alias else {
+            return Err(service_error(
+                DescribeStateMachineAliasError::ResourceNotFound(resource_not_found(
+                    &resource_not_found_message(alias_arn),
+                )),
+                400,
+            ));
+        };
+        let alias = &stored_alias.alias;
+
+        Ok(DescribeStateMachineAliasOutput::builder()
+            .state_machine_alias_arn(alias_arn)
+            .name(&alias.name)
+            .set_description(alias.description.clone())
+            .set_routing_configuration(Some(
+                alias
+                    .routing_configuration
+                    .iter()
+                    .cloned()
+                    .map(|item| item.into())
+                    .collect(),
+            ))
+            .creation_date(stored_alias.creation_date)
+            .build())
+    }
+
+    fn create_state_machine_alias_sync(
+        &self,
+        alias: &StateMachineAlias,
+    ) -> Result<CreateStateMachineAliasOutput, SdkError<CreateStateMachineAliasError, Response>>
+    {
+        let mut state_machines = self.state_machines.lock().unwrap();
+        let state_arn = match validate_routing_configuration(&state_machines, alias) {
+            Ok(state_arn) => state_arn,
+            Err(RoutingError::Invalid(message)) => {
+                return Err(service_error(
+                    CreateStateMachineAliasError::ValidationException(sfn_validation_error(
+                        &message,
+                    )),
+                    400,
+                ));
+            }
+            Err(RoutingError::VersionNotFound(message)) => {
+                return Err(service_error(
+                    CreateStateMachineAliasError::ResourceNotFound(resource_not_found(&message)),
+                    400,
+                ));
+            }
+        };
+
+        let stored = state_machines.get_mut(&state_arn).unwrap();
+        let alias_arn = format!("{}:{}", state_arn, alias.name);
+        if stored.aliases.contains_key(&alias.name) {
+            return Err(service_error(
+                CreateStateMachineAliasError::ConflictException(sfn_conflict(&format!(
+                    "Alias already exists: '{}'",
+                    alias_arn
+                ))),
+                400,
+            ));
+        }
+
+        let creation_date = DateTime::from(SystemTime::now());
+        stored.aliases.insert(
+            alias.name.clone(),
+            StoredAlias {
+                alias: alias.clone(),
+                creation_date,
+            },
+        );
+
+        Ok(CreateStateMachineAliasOutput::builder()
+            .state_machine_alias_arn(alias_arn)
+            .creation_date(creation_date)
+            .build()
+            .unwrap())
+    }
+
+    fn update_state_machine_alias_sync(
+        &self,
+        alias_arn: &str,
+        alias: &StateMachineAlias,
+    ) -> Result<UpdateStateMachineAliasOutput, SdkError<UpdateStateMachineAliasError, Response>>
+    {
+        let mut state_machines = self.state_machines.lock().unwrap();
+        match validate_routing_configuration(&state_machines, alias) {
+            Ok(state_arn) if split_alias_arn(alias_arn).map(|(arn, _)| arn) == Some(&state_arn) => {
+            }
+            Ok(_) => {
+                return Err(service_error(
+                    UpdateStateMachineAliasError::ValidationException(sfn_validation_error(
+                        "versions in routingConfiguration must belong to the state machine of alias",
+                    )),
+                    400,
+                ));
+            }
+            Err(RoutingError::Invalid(message)) => {
+                return Err(service_error(
+                    UpdateStateMachineAliasError::ValidationException(sfn_validation_error(
+                        &message,
+                    )),
+                    400,
+                ));
+            }
+            Err(RoutingError::VersionNotFound(message)) => {
+                return Err(service_error(
+                    UpdateStateMachineAliasError::ResourceNotFound(resource_not_found(&message)),
+                    400,
+                ));
+            }
+        }
+
+        let (state_arn, name) = split_alias_arn(alias_arn).unwrap();
+        let Some(stored_alias) = state_machines
+            .get_mut(state_arn)
+            .and_then(|stored| stored.aliases.get_mut(name))
+        else {
+            return Err(service_error(
+                UpdateStateMachineAliasError::ResourceNotFound(resource_not_found(
+                    &resource_not_found_message(alias_arn),
+                )),
+                400,
+            ));
+        };
+
+        // Omitted description is kept as is, and empty one removes it
+        match alias.description.as_deref() {
+            Some("") => stored_alias.alias.description = None,
+            Some(description) => stored_alias.alias.description = Some(description.to_string()),
+            None => {}
+        }
+        stored_alias
+            .alias
+            .routing_configuration
+            .clone_from(&alias.routing_configuration);
+
+        Ok(UpdateStateMachineAliasOutput::builder()
+            .update_date(DateTime::from(SystemTime::now()))
+            .build()
+            .unwrap())
+    }
+
+    fn delete_state_machine_alias_sync(
+        &self,
+        alias_arn: &str,
+    ) -> Result<DeleteStateMachineAliasOutput, SdkError<DeleteStateMachineAliasError, Response>>
+    {
+        let mut state_machines = self.state_machines.lock().unwrap();
+        let removed = split_alias_arn(alias_arn).and_then(|(state_arn, name)| {
+            state_machines
+                .get_mut(state_arn)
+                .and_then(|stored| stored.aliases.remove(name))
+        });
+        if removed.is_none() {
+            return Err(service_error(
+                DeleteStateMachineAliasError::ResourceNotFound(resource_not_found(
+                    &resource_not_found_message(alias_arn),
+                )),
+                400,
+            ));
+        }
+
+        Ok(DeleteStateMachineAliasOutput::builder().build())
+    }
+
+    // All versions are returned at once from the latest, and none for missing state machine.
+    fn list_state_machine_versions_sync(&self, state_arn: &str) -> ListStateMachineVersionsOutput {
+        let state_machines = self.state_machines.lock().unwrap();
+        let versions = state_machines
+            .get(state_arn)
+            .map(|stored| {
+                stored
+                    .versions
+                    .iter()
+                    .rev()
+                    .map(|v| {
+                        aws_sdk_sfn::types::StateMachineVersionListItem::builder()
+                            .state_machine_version_arn(format!("{}:{}", state_arn, v.version))
+                            .creation_date(v.creation_date)
+                            .build()
+                            .unwrap()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        ListStateMachineVersionsOutput::builder()
+            .set_state_machine_versions(Some(versions))
+            .build()
+            .unwrap()
+    }
+
+    // Executions of version arn are filtered by it, and all executions for state machine arn.
+    fn list_executions_sync(
+        &self,
+        state_arn: &str,
+    ) -> Result<ListExecutionsOutput, SdkError<ListExecutionsError, Response>> {
+        let base_state_arn = match state_machine_version_number(state_arn) {
+            Some(_) => state_arn.rsplit_once(':').unwrap().0,
+            None => state_arn,
+        };
+        if !self
+            .state_machines
+            .lock()
+            .unwrap()
+            .contains_key(base_state_arn)
+        {
+            return Err(service_error(
+                ListExecutionsError::StateMachineDoesNotExist(state_machine_does_not_exist(
+                    &state_machine_does_not_exist_message(state_arn),
+                )),
+                400,
+            ));
+        }
+
+        let mut executions: Vec<&Execution> = vec![];
+        let stored_executions = self.executions.lock().unwrap();
+        for execution in stored_executions.iter() {
+            let matched = if base_state_arn == state_arn {
+                execution.state_machine_arn == state_arn
+            } else {
+                execution.state_machine_version_arn.as_deref() == Some(state_arn)
+            };
+            if matched {
+                executions.push(execution);
+            }
+        }
+        // RFC 3339 dates in UTC are ordered as strings
+        executions.sort_by(|a, b| b.start_date.cmp(&a.start_date));
+
+        let executions = executions
+            .into_iter()
+            .map(|execution| {
+                aws_sdk_sfn::types::ExecutionListItem::builder()
+                    .execution_arn(&execution.execution_arn)
+                    .state_machine_arn(&execution.state_machine_arn)
+                    .set_state_machine_version_arn(execution.state_machine_version_arn.clone())
+                    .name(execution.execution_arn.rsplit(':').next().unwrap())
+                    .status(execution.status.as_str().into())
+                    .start_date(parse_execution_date(&execution.start_date))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        Ok(ListExecutionsOutput::builder()
+            .set_executions(Some(executions))
+            .build()
+            .unwrap())
+    }
+
+    fn describe_execution_sync(
+        &self,
+        execution_arn: &str,
+    ) -> Result<DescribeExecutionOutput, SdkError<DescribeExecutionError, Response>> {
+        let executions = self.executions.lock().unwrap();
+        let Some(execution) = executions
+            .iter()
+            .find(|execution| execution.execution_arn == execution_arn)
+        else {
+            let message = format!("Execution Does Not Exist: '{}'", execution_arn);
+            return Err(service_error(
+                DescribeExecutionError::ExecutionDoesNotExist(
+                    aws_sdk_sfn::types::error::ExecutionDoesNotExist::builder()
+                        .message(&message)
+                        .meta(error_meta("ExecutionDoesNotExist", &message))
+                        .build(),
+                ),
+                400,
+            ));
+        };
+
+        Ok(DescribeExecutionOutput::builder()
+            .execution_arn(&execution.execution_arn)
+            .state_machine_arn(&execution.state_machine_arn)
+            .set_state_machine_version_arn(execution.state_machine_version_arn.clone())
+            .status(execution.status.as_str().into())
+            .start_date(parse_execution_date(&execution.start_date))
+            .set_error(execution.error.clone())
+            .set_cause(execution.cause.clone())
+            .build()
+            .unwrap())
+    }
+
+    fn get_schedule_sync(
+        &self,
+        group_name: &str,
+        schedule_name: &str,
+    ) -> Result<GetScheduleOutput, SdkError<GetScheduleError, Response>> {
+        let key = schedule_key(group_name, schedule_name);
+        let schedules = self.schedules.lock().unwrap();
+        let Some(schedule) = schedules.get(&key) else {
+            return Err(service_error(
+                GetScheduleError::ResourceNotFoundException(schedule_not_found(
+                    &schedule_not_found_message(&key.0, schedule_name),
+                )),
+                404,
+            ));
+        };
+
+        Ok(schedule.to_get_schedule_output(&self.arn_builder.schedule(&key.0, &key.1)))
+    }
+
+    fn create_schedule_sync(
+        &self,
+        schedule: &Schedule,
+    ) -> Result<CreateScheduleOutput, SdkError<CreateScheduleError, Response>> {
+        let key = schedule_key(&schedule.group_name, &schedule.name);
+        let mut schedules = self.schedules.lock().unwrap();
+        if schedules.contains_key(&key) {
+            return Err(service_error(
+                CreateScheduleError::ConflictException(schedule_conflict(&format!(
+                    "Schedule {} already exists in group {}.",
+                    key.1, key.0
+                ))),
+                409,
+            ));
+        }
+
+        let schedule_arn = self.arn_builder.schedule(&key.0, &key.1);
+        let mut schedule = schedule.clone();
+        schedule.group_name.clone_from(&key.0);
+        schedules.insert(key, schedule);
+
+        Ok(CreateScheduleOutput::builder()
+            .schedule_arn(schedule_arn)
+            .build()
+            .unwrap())
+    }
+
+    fn update_schedule_sync(
+        &self,
+        schedule: &Schedule,
+    ) -> Result<UpdateScheduleOutput, SdkError<UpdateScheduleError, Response>> {
+        let key = schedule_key(&schedule.group_name, &schedule.name);
+        let mut schedules = self.schedules.lock().unwrap();
+        let Some(stored) = schedules.get_mut(&key) else {
+            return Err(service_error(
+                UpdateScheduleError::ResourceNotFoundException(schedule_not_found(
+                    &schedule_not_found_message(&key.0, &key.1),
+                )),
+                404,
+            ));
+        };
+
+        // UpdateSchedule replaces whole schedule, omitted fields are reset.
+        *stored = schedule.clone();
+        stored.group_name.clone_from(&key.0);
+
+        Ok(UpdateScheduleOutput::builder()
+            .schedule_arn(self.arn_builder.schedule(&key.0, &key.1))
+            .build()
+            .unwrap())
+    }
+
+    fn delete_schedule_sync(
+        &self,
+        schedule: &Schedule,
+    ) -> Result<DeleteScheduleOutput, SdkError<DeleteScheduleError, Response>> {
+        let key = schedule_key(&schedule.group_name, &schedule.name);
+        if self.schedules.lock().unwrap().remove(&key).is_none() {
+            return Err(service_error(
+                DeleteScheduleError::ResourceNotFoundException(schedule_not_found(
+                    &schedule_not_found_message(&key.0, &key.1),
+                )),
+                404,
+            ));
+        }
+
+        Ok(DeleteScheduleOutput::builder().build())
+    }
+
+    fn list_schedules_sync(
+        &self,
+        group_name: &str,
+    ) -> Result<ListSchedulesOutput, SdkError<ListSchedulesError, Response>> {
+        if group_name != DEFAULT_SCHEDULE_GROUP_NAME
+            && !self
+                .schedule_groups
+                .lock()
+                .unwrap()
+                .contains_key(group_name)
+        {
+            return Err(service_error(
+                ListSchedulesError::ResourceNotFoundException(schedule_not_found(
+                    &schedule_group_not_found_message(group_name),
+                )),
+                404,
+            ));
+        }
+
+        // all schedules are returned in a page
+        let schedules = self.schedules.lock().unwrap();
+        let summaries = schedules
+            .keys()
+            .filter(|(schedule_group_name, _)| schedule_group_name == group_name)
+            .map(|(group_name, schedule_name)| {
+                aws_sdk_scheduler::types::ScheduleSummary::builder()
+                    .arn(self.arn_builder.schedule(group_name, schedule_name))
+                    .name(schedule_name)
+                    .group_name(group_name)
+                    .build()
+            })
+            .collect();
+
+        Ok(ListSchedulesOutput::builder()
+            .set_schedules(Some(summaries))
+            .build()
+            .unwrap())
+    }
+
+    fn get_schedule_group_sync(
+        &self,
+        group_name: &str,
+    ) -> Result<GetScheduleGroupOutput, SdkError<GetScheduleGroupError, Response>> {
+        if group_name != DEFAULT_SCHEDULE_GROUP_NAME
+            && !self
+                .schedule_groups
+                .lock()
+                .unwrap()
+                .contains_key(group_name)
+        {
+            return Err(service_error(
+                GetScheduleGroupError::ResourceNotFoundException(schedule_not_found(
+                    &schedule_group_not_found_message(group_name),
+                )),
+                404,
+            ));
+        }
+
+        Ok(GetScheduleGroupOutput::builder()
+            .arn(self.arn_builder.schedule_group(group_name))
+            .name(group_name)
+            .state(aws_sdk_scheduler::types::ScheduleGroupState::Active)
+            .build())
+    }
+
+    fn create_schedule_group_sync(
+        &self,
+        group: &ScheduleGroup,
+    ) -> Result<CreateScheduleGroupOutput, SdkError<CreateScheduleGroupError, Response>> {
+        let mut schedule_groups = self.schedule_groups.lock().unwrap();
+        if group.name == DEFAULT_SCHEDULE_GROUP_NAME || schedule_groups.contains_key(&group.name) {
+            return Err(service_error(
+                CreateScheduleGroupError::ConflictException(schedule_conflict(&format!(
+                    "Schedule group {} already exists.",
+                    group.name
+                ))),
+                409,
+            ));
+        }
+
+        let group_arn = self.arn_builder.schedule_group(&group.name);
+        let mut group = group.clone();
+        let tags = std::mem::take(&mut group.tags);
+        merge_tags(&mut group.tags, &tags);
+        schedule_groups.insert(group.name.clone(), group);
+
+        Ok(CreateScheduleGroupOutput::builder()
+            .schedule_group_arn(group_arn)
+            .build()
+            .unwrap())
+    }
+
+    // Deleting a group deletes all schedules in it, as AWS does.
+    fn delete_schedule_group_sync(
+        &self,
+        group_name: &str,
+    ) -> Result<DeleteScheduleGroupOutput, SdkError<DeleteScheduleGroupError, Response>> {
+        if group_name == DEFAULT_SCHEDULE_GROUP_NAME {
+            return Err(service_error(
+                DeleteScheduleGroupError::ValidationException(schedule_validation_error(
+                    "Default group cannot be deleted.",
+                )),
+                400,
+            ));
+        }
+
+        if self
+            .schedule_groups
+            .lock()
+            .unwrap()
+            .remove(group_name)
+            .is_none()
+        {
+            return Err(service_error(
+                DeleteScheduleGroupError::ResourceNotFoundException(schedule_not_found(
+                    &schedule_group_not_found_message(group_name),
+                )),
+                404,
+            ));
+        }
+
+        self.schedules
+            .lock()
+            .unwrap()
+            .retain(|(schedule_group_name, _), _| schedule_group_name != group_name);
+
+        Ok(DeleteScheduleGroupOutput::builder().build())
+    }
+
+    // Only schedule groups are taggable in memory backend.
+    fn with_schedule_group<T>(
+        &self,
+        resource_arn: &str,
+        f: impl FnOnce(&mut ScheduleGroup) -> T,
+    ) -> Option<T> {
+        let mut schedule_groups = self.schedule_groups.lock().unwrap();
+
+        schedule_groups
+            .values_mut()
+            .find(|group| self.arn_builder.schedule_group(&group.name) == resource_arn)
+            .map(f)
+    }
+
+    fn list_schedule_tags_sync(
+        &self,
+        resource_arn: &str,
+    ) -> Result<ListScheduleTagsOutput, SdkError<ListScheduleTagsError, Response>> {
+        let Some(tags) = self.with_schedule_group(resource_arn, |group| group.tags.clone()) else {
+            return Err(service_error(
+                ListScheduleTagsError::ResourceNotFoundException(schedule_not_found(
+                    &resource_not_found_message(resource_arn),
+                )),
+                404,
+            ));
+        };
+
+        Ok(ListScheduleTagsOutput::builder()
+            .set_tags(Some(tags.into_iter().map(|t| t.into()).collect()))
+            .build())
+    }
+
+    fn tag_schedule_resource_sync(
+        &self,
+        resource_arn: &str,
+        tags: &[ResourceTag],
+    ) -> Result<TagScheduleResourceOutput, SdkError<TagScheduleResourceError, Response>> {
+        if self
+            .with_schedule_group(resource_arn, |group| merge_tags(&mut group.tags, tags))
+            .is_none()
+        {
+            return Err(service_error(
+                TagScheduleResourceError::ResourceNotFoundException(schedule_not_found(
+                    &resource_not_found_message(resource_arn),
+                )),
+                404,
+            ));
+        }
+
+        Ok(TagScheduleResourceOutput::builder().build())
+    }
+
+    fn untag_schedule_resource_sync(
+        &self,
+        resource_arn: &str,
+        tag_keys: &[String],
+    ) -> Result<UntagScheduleResourceOutput, SdkError<UntagScheduleResourceError, Response>> {
+        if self
+            .with_schedule_group(resource_arn, |group| {
+                group.tags.retain(|tag| !tag_keys.contains(&tag.key))
+            })
+            .is_none()
+        {
+            return Err(service_error(
+                UntagScheduleResourceError::ResourceNotFoundException(schedule_not_found(
+                    &resource_not_found_message(resource_arn),
+                )),
+                404,
+            ));
+        }
+
+        Ok(UntagScheduleResourceOutput::builder().build())
+    }
+
+    fn describe_rule_sync(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+    ) -> Result<DescribeRuleOutput, SdkError<DescribeRuleError, Response>> {
+        let key = rule_key(event_bus_name, rule_name);
+        let rules = self.rules.lock().unwrap();
+        let Some(rule) = rules.get(&key) else {
+            return Err(service_error(
+                DescribeRuleError::ResourceNotFoundException(rule_not_found(
+                    &rule_not_found_message(&key.0, &key.1),
+                )),
+                400,
+            ));
+        };
+
+        Ok(rule.to_describe_rule_output(&self.arn_builder.rule(&key.0, &key.1)))
+    }
+
+    fn list_targets_by_rule_sync(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+    ) -> Result<ListTargetsByRuleOutput, SdkError<ListTargetsByRuleError, Response>> {
+        let key = rule_key(event_bus_name, rule_name);
+        let rules = self.rules.lock().unwrap();
+        let Some(rule) = rules.get(&key) else {
+            return Err(service_error(
+                ListTargetsByRuleError::ResourceNotFoundException(rule_not_found(
+                    &rule_not_found_message(&key.0, &key.1),
+                )),
+                400,
+            ));
+        };
+
+        // all targets are returned in a page
+        let targets = rule.target.iter().cloned().map(|t| t.into()).collect();
+
+        Ok(ListTargetsByRuleOutput::builder()
+            .set_targets(Some(targets))
+            .build())
+    }
+
+    // PutRule creates the rule, or updates it keeping its targets.
+    fn put_rule_sync(&self, rule: &Rule) -> PutRuleOutput {
+        let key = rule_key(&rule.event_bus_name, &rule.name);
+        let rule_arn = self.arn_builder.rule(&key.0, &key.1);
+        let mut rules = self.rules.lock().unwrap();
+
+        let target = rules.get(&key).and_then(|stored| stored.target.clone());
+        let mut rule = rule.clone();
+        rule.event_bus_name.clone_from(&key.0);
+        rule.target = target;
+        rules.insert(key, rule);
+
+        PutRuleOutput::builder().rule_arn(rule_arn).build()
+    }
+
+    fn put_targets_sync(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+        target: &RuleTarget,
+    ) -> Result<PutTargetsOutput, SdkError<PutTargetsError, Response>> {
+        let key = rule_key(event_bus_name, rule_name);
+        let mut rules = self.rules.lock().unwrap();
+        let Some(stored) = rules.get_mut(&key) else {
+            return Err(service_error(
+                PutTargetsError::ResourceNotFoundException(rule_not_found(
+                    &rule_not_found_message(&key.0, &key.1),
+                )),
+                400,
+            ));
+        };
+
+        stored.target = Some(target.clone());
+
+        Ok(PutTargetsOutput::builder().failed_entry_count(0).build())
+    }
+
+    fn remove_targets_sync(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+        target_ids: &[String],
+    ) -> Result<RemoveTargetsOutput, SdkError<RemoveTargetsError, Response>> {
+        let key = rule_key(event_bus_name, rule_name);
+        let mut rules = self.rules.lock().unwrap();
+        let Some(stored) = rules.get_mut(&key) else {
+            return Err(service_error(
+                RemoveTargetsError::ResourceNotFoundException(rule_not_found(
+                    &rule_not_found_message(&key.0, &key.1),
+                )),
+                400,
+            ));
+        };
+
+        if stored
+            .target
+            .as_ref()
+            .is_some_and(|target| target_ids.contains(&target.id))
+        {
+            stored.target = None;
+        }
+
+        Ok(RemoveTargetsOutput::builder().failed_entry_count(0).build())
+    }
+
+    // Deleting missing rule succeeds, and rule with targets can't be deleted, as AWS does.
+    fn delete_rule_sync(
+        &self,
+        event_bus_name: &str,
+        rule_name: &str,
+    ) -> Result<DeleteRuleOutput, SdkError<DeleteRuleError, Response>> {
+        let key = rule_key(event_bus_name, rule_name);
+        let mut rules = self.rules.lock().unwrap();
+        if rules.get(&key).is_some_and(|rule| rule.target.is_some()) {
+            return Err(service_error(
+                DeleteRuleError::generic(error_meta(
+                    "ValidationException",
+                    "Rule can't be deleted since it has targets.",
+                )),
+                400,
+            ));
+        }
+        rules.remove(&key);
+
+        Ok(DeleteRuleOutput::builder().build())
+    }
+
+    // Only the first page is returned, as there is no need to paginate in memory.
+    fn describe_log_groups_sync(&self, log_group_name_prefix: &str) -> DescribeLogGroupsOutput {
+        let log_groups = self.log_groups.lock().unwrap();
+
+        DescribeLogGroupsOutput::builder()
+            .set_log_groups(Some(
+                log_groups
+                    .values()
+                    .filter(|log_group| log_group.name.starts_with(log_group_name_prefix))
+                    .map(|log_group| {
+                        log_group.to_remote(&self.arn_builder.log_group(&log_group.name))
+                    })
+                    .collect(),
+            ))
+            .build()
+    }
+
+    fn with_log_group<T, E>(
+        &self,
+        find: impl Fn(&LogGroup) -> bool,
+        not_found: impl FnOnce(aws_sdk_cloudwatchlogs::types::error::ResourceNotFoundException) -> E,
+        f: impl FnOnce(&mut LogGroup) -> T,
+    ) -> Result<T, SdkError<E, Response>> {
+        let mut log_groups = self.log_groups.lock().unwrap();
+        match log_groups.values_mut().find(|log_group| find(log_group)) {
+            Some(log_group) => Ok(f(log_group)),
+            None => Err(service_error(
+                not_found(log_group_not_found(&log_group_not_found_message())),
+                400,
+            )),
+        }
+    }
+
+    fn by_log_group_arn<'a>(&'a self, resource_arn: &'a str) -> impl Fn(&LogGroup) -> bool + 'a {
+        move |log_group| self.arn_builder.log_group(&log_group.name) == resource_arn
+    }
+
+    fn list_log_group_tags_sync(
+        &self,
+        resource_arn: &str,
+    ) -> Result<ListLogGroupTagsOutput, SdkError<ListLogGroupTagsError, Response>> {
+        self.with_log_group(
+            self.by_log_group_arn(resource_arn),
+            ListLogGroupTagsError::ResourceNotFoundException,
+            |log_group| {
+                ListLogGroupTagsOutput::builder()
+                    .set_tags(Some(log_group.tags_map()))
+                    .build()
+            },
+        )
+    }
+
+    fn create_log_group_sync(
+        &self,
+        log_group: &LogGroup,
+    ) -> Result<CreateLogGroupOutput, SdkError<CreateLogGroupError, Response>> {
+        let mut log_groups = self.log_groups.lock().unwrap();
+        if log_groups.contains_key(&log_group.name) {
+            return Err(service_error(
+                CreateLogGroupError::ResourceAlreadyExistsException(log_group_already_exists(
+                    "The specified log group already exists",
+                )),
+                400,
+            ));
+        }
+
+        // retention is put by its own api
+        let mut log_group = log_group.clone();
+        log_group.retention_in_days = None;
+        let tags = std::mem::take(&mut log_group.tags);
+        merge_tags(&mut log_group.tags, &tags);
+        log_groups.insert(log_group.name.clone(), log_group);
+
+        Ok(CreateLogGroupOutput::builder().build())
+    }
+
+    fn put_retention_policy_sync(
+        &self,
+        log_group_name: &str,
+        retention_in_days: i32,
+    ) -> Result<PutRetentionPolicyOutput, SdkError<PutRetentionPolicyError, Response>> {
+        self.with_log_group(
+            |log_group| log_group.name == log_group_name,
+            PutRetentionPolicyError::ResourceNotFoundException,
+            |log_group| {
+                log_group.retention_in_days = Some(retention_in_days);
+                PutRetentionPolicyOutput::builder().build()
+            },
+        )
+    }
+
+    fn delete_retention_policy_sync(
+        &self,
+        log_group_name: &str,
+    ) -> Result<DeleteRetentionPolicyOutput, SdkError<DeleteRetentionPolicyError, Response>> {
+        self.with_log_group(
+            |log_group| log_group.name == log_group_name,
+            DeleteRetentionPolicyError::ResourceNotFoundException,
+            |log_group| {
+                log_group.retention_in_days = None;
+                DeleteRetentionPolicyOutput::builder().build()
+            },
+        )
+    }
+
+    fn associate_kms_key_sync(
+        &self,
+        log_group_name: &str,
+        kms_key_id: &str,
+    ) -> Result<AssociateKmsKeyOutput, SdkError<AssociateKmsKeyError, Response>> {
+        self.with_log_group(
+            |log_group| log_group.name == log_group_name,
+            AssociateKmsKeyError::ResourceNotFoundException,
+            |log_group| {
+                log_group.kms_key_id = Some(kms_key_id.to_string());
+                AssociateKmsKeyOutput::builder().build()
+            },
+        )
+    }
+
+    fn disassociate_kms_key_sync(
+        &self,
+        log_group_name: &str,
+    ) -> Result<DisassociateKmsKeyOutput, SdkError<DisassociateKmsKeyError, Response>> {
+        self.with_log_group(
+            |log_group| log_group.name == log_group_name,
+            DisassociateKmsKeyError::ResourceNotFoundException,
+            |log_group| {
+                log_group.kms_key_id = None;
+                DisassociateKmsKeyOutput::builder().build()
+            },
+        )
+    }
+
+    fn tag_log_group_sync(
+        &self,
+        resource_arn: &str,
+        tags: &[ResourceTag],
+    ) -> Result<TagLogGroupOutput, SdkError<TagLogGroupError, Response>> {
+        self.with_log_group(
+            self.by_log_group_arn(resource_arn),
+            TagLogGroupError::ResourceNotFoundException,
+            |log_group| {
+                merge_tags(&mut log_group.tags, tags);
+                TagLogGroupOutput::builder().build()
+            },
+        )
+    }
+
+    fn untag_log_group_sync(
+        &self,
+        resource_arn: &str,
+        tag_keys: &[String],
+    ) -> Result<UntagLogGroupOutput, SdkError<UntagLogGroupError, Response>> {
+        self.with_log_group(
+            self.by_log_group_arn(resource_arn),
+            UntagLogGroupError::ResourceNotFoundException,
+            |log_group| {
+                log_group.tags.retain(|tag| !tag_keys.contains(&tag.key));
+                UntagLogGroupOutput::builder().build()
+            },
+        )
+    }
+
+    fn get_caller_identity_sync(&self) -> GetCallerIdentityOutput {
+        GetCallerIdentityOutput::builder()
+            .account(self.account())
+            .arn(format!(
+                "arn:{}:iam::{}:user/fubura",
+                self.arn_builder.partition,
+                self.account()
+            ))
+            .build()
+    }
+}
+
+impl Sfn for MemoryBackend {
+    fn describe_state_machine<'a>(
+        &'a self,
+        state_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DescribeStateMachineOutput, aws_sdk_sfn::error::SdkError<DescribeStateMachineError>>,
+    > {
+        Box::pin(ready(self.describe_state_machine_sync(state_arn)))
+    }
+
+    fn list_tags_for_resource<'a>(
+        &'a self,
+        state_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<ListTagsForResourceOutput, aws_sdk_sfn::error::SdkError<ListTagsForResourceError>>,
+    > {
+        Box::pin(ready(self.list_tags_for_resource_sync(state_arn)))
+    }
+
+    fn create_state_machine<'a>(
+        &'a self,
+        state: &'a StateMachine,
+    ) -> BoxFuture<
+        'a,
+        Result<CreateStateMachineOutput, aws_sdk_sfn::error::SdkError<CreateStateMachineError>>,
+    > {
+        Box::pin(ready(self.create_state_machine_sync(state)))
+    }
+
+    fn update_state_machine<'a>(
+        &'a self,
+        state_arn: &'a str,
+        state: &'a StateMachine,
+    ) -> BoxFuture<
+        'a,
+        Result<UpdateStateMachineOutput, aws_sdk_sfn::error::SdkError<UpdateStateMachineError>>,
+    > {
+        Box::pin(ready(self.update_state_machine_sync(state_arn, state)))
+    }
+
+    fn delete_state_machine<'a>(
+        &'a self,
+        state_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DeleteStateMachineOutput, aws_sdk_sfn::error::SdkError<DeleteStateMachineError>>,
+    > {
+        Box::pin(ready(Ok(self.delete_state_machine_sync(state_arn))))
+    }
+
+    fn tag_resource<'a>(
+        &'a self,
+        state_arn: &'a str,
+        tags: &'a [ResourceTag],
+    ) -> BoxFuture<'a, Result<TagResourceOutput, aws_sdk_sfn::error::SdkError<TagResourceError>>>
+    {
+        Box::pin(ready(self.tag_resource_sync(state_arn, tags)))
+    }
+
+    fn untag_resource<'a>(
+        &'a self,
+        state_arn: &'a str,
+        tags: &'a [String],
+    ) -> BoxFuture<'a, Result<UntagResourceOutput, aws_sdk_sfn::error::SdkError<UntagResourceError>>>
+    {
+        Box::pin(ready(self.untag_resource_sync(state_arn, tags)))
+    }
+
+    fn list_state_machine_versions<'a>(
+        &'a self,
+        state_arn: &'a str,
+        _next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            ListStateMachineVersionsOutput,
+            aws_sdk_sfn::error::SdkError<ListStateMachineVersionsError>,
+        >,
+    > {
+        Box::pin(ready(Ok(self.list_state_machine_versions_sync(state_arn))))
+    }
+
+    fn list_state_machine_aliases<'a>(
+        &'a self,
+        state_arn: &'a str,
+        _next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            ListStateMachineAliasesOutput,
+            aws_sdk_sfn::error::SdkError<ListStateMachineAliasesError>,
+        >,
+    > {
+        Box::pin(ready(self.list_state_machine_aliases_sync(state_arn)))
+    }
+
+    fn describe_state_machine_alias<'a>(
+        &'a self,
+        alias_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            DescribeStateMachineAliasOutput,
+            aws_sdk_sfn::error::SdkError<DescribeStateMachineAliasError>,
+        >,
+    > {
+        Box::pin(ready(self.describe_state_machine_alias_sync(alias_arn)))
+    }
+
+    fn create_state_machine_alias<'a>(
+        &'a self,
+        alias: &'a StateMachineAlias,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            CreateStateMachineAliasOutput,
+            aws_sdk_sfn::error::SdkError<CreateStateMachineAliasError>,
+        >,
+    > {
+        Box::pin(ready(self.create_state_machine_alias_sync(alias)))
+    }
+
+    fn update_state_machine_alias<'a>(
+        &'a self,
+        alias_arn: &'a str,
+        alias: &'a StateMachineAlias,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            UpdateStateMachineAliasOutput,
+            aws_sdk_sfn::error::SdkError<UpdateStateMachineAliasError>,
+        >,
+    > {
+        Box::pin(ready(
+            self.update_state_machine_alias_sync(alias_arn, alias),
+        ))
+    }
+
+    fn delete_state_machine_alias<'a>(
+        &'a self,
+        alias_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            DeleteStateMachineAliasOutput,
+            aws_sdk_sfn::error::SdkError<DeleteStateMachineAliasError>,
+        >,
+    > {
+        Box::pin(ready(self.delete_state_machine_alias_sync(alias_arn)))
+    }
+
+    fn list_executions<'a>(
+        &'a self,
+        state_arn: &'a str,
+        _next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<ListExecutionsOutput, aws_sdk_sfn::error::SdkError<ListExecutionsError>>,
+    > {
+        Box::pin(ready(self.list_executions_sync(state_arn)))
+    }
+
+    fn describe_execution<'a>(
+        &'a self,
+        execution_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DescribeExecutionOutput, aws_sdk_sfn::error::SdkError<DescribeExecutionError>>,
+    > {
+        Box::pin(ready(self.describe_execution_sync(execution_arn)))
+    }
+}
+
+impl Scheduler for MemoryBackend {
+    fn get_schedule<'a>(
+        &'a self,
+        group_name: &'a str,
+        schedule_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<GetScheduleOutput, aws_sdk_scheduler::error::SdkError<GetScheduleError>>,
+    > {
+        Box::pin(ready(self.get_schedule_sync(group_name, schedule_name)))
+    }
+
+    fn create_schedule<'a>(
+        &'a self,
+        schedule: &'a Schedule,
+    ) -> BoxFuture<
+        'a,
+        Result<CreateScheduleOutput, aws_sdk_scheduler::error::SdkError<CreateScheduleError>>,
+    > {
+        Box::pin(ready(self.create_schedule_sync(schedule)))
+    }
+
+    fn update_schedule<'a>(
+        &'a self,
+        schedule: &'a Schedule,
+    ) -> BoxFuture<
+        'a,
+        Result<UpdateScheduleOutput, aws_sdk_scheduler::error::SdkError<UpdateScheduleError>>,
+    > {
+        Box::pin(ready(self.update_schedule_sync(schedule)))
+    }
+
+    fn delete_schedule<'a>(
+        &'a self,
+        schedule: &'a Schedule,
+    ) -> BoxFuture<
+        'a,
+        Result<DeleteScheduleOutput, aws_sdk_scheduler::error::SdkError<DeleteScheduleError>>,
+    > {
+        Box::pin(ready(self.delete_schedule_sync(schedule)))
+    }
+
+    fn list_schedules<'a>(
+        &'a self,
+        group_name: &'a str,
+        _next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<ListSchedulesOutput, aws_sdk_scheduler::error::SdkError<ListSchedulesError>>,
+    > {
+        Box::pin(ready(self.list_schedules_sync(group_name)))
+    }
+
+    fn get_schedule_group<'a>(
+        &'a self,
+        group_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<GetScheduleGroupOutput, aws_sdk_scheduler::error::SdkError<GetScheduleGroupError>>,
+    > {
+        Box::pin(ready(self.get_schedule_group_sync(group_name)))
+    }
+
+    fn create_schedule_group<'a>(
+        &'a self,
+        group: &'a ScheduleGroup,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            CreateScheduleGroupOutput,
+            aws_sdk_scheduler::error::SdkError<CreateScheduleGroupError>,
+        >,
+    > {
+        Box::pin(ready(self.create_schedule_group_sync(group)))
+    }
+
+    fn delete_schedule_group<'a>(
+        &'a self,
+        group_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            DeleteScheduleGroupOutput,
+            aws_sdk_scheduler::error::SdkError<DeleteScheduleGroupError>,
+        >,
+    > {
+        Box::pin(ready(self.delete_schedule_group_sync(group_name)))
+    }
+
+    fn list_tags_for_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<ListScheduleTagsOutput, aws_sdk_scheduler::error::SdkError<ListScheduleTagsError>>,
+    > {
+        Box::pin(ready(self.list_schedule_tags_sync(resource_arn)))
+    }
+
+    fn tag_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+        tags: &'a [ResourceTag],
+    ) -> BoxFuture<
+        'a,
+        Result<
+            TagScheduleResourceOutput,
+            aws_sdk_scheduler::error::SdkError<TagScheduleResourceError>,
+        >,
+    > {
+        Box::pin(ready(self.tag_schedule_resource_sync(resource_arn, tags)))
+    }
+
+    fn untag_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+        tag_keys: &'a [String],
+    ) -> BoxFuture<
+        'a,
+        Result<
+            UntagScheduleResourceOutput,
+            aws_sdk_scheduler::error::SdkError<UntagScheduleResourceError>,
+        >,
+    > {
+        Box::pin(ready(
+            self.untag_schedule_resource_sync(resource_arn, tag_keys),
+        ))
+    }
+}
+
+impl Events for MemoryBackend {
+    fn describe_rule<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DescribeRuleOutput, aws_sdk_eventbridge::error::SdkError<DescribeRuleError>>,
+    > {
+        Box::pin(ready(self.describe_rule_sync(event_bus_name, rule_name)))
+    }
+
+    fn list_targets_by_rule<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+        _next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            ListTargetsByRuleOutput,
+            aws_sdk_eventbridge::error::SdkError<ListTargetsByRuleError>,
+        >,
+    > {
+        Box::pin(ready(
+            self.list_targets_by_rule_sync(event_bus_name, rule_name),
+        ))
+    }
+
+    fn put_rule<'a>(
+        &'a self,
+        rule: &'a Rule,
+    ) -> BoxFuture<'a, Result<PutRuleOutput, aws_sdk_eventbridge::error::SdkError<PutRuleError>>>
+    {
+        Box::pin(ready(Ok(self.put_rule_sync(rule))))
+    }
+
+    fn put_targets<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+        target: &'a RuleTarget,
+    ) -> BoxFuture<
+        'a,
+        Result<PutTargetsOutput, aws_sdk_eventbridge::error::SdkError<PutTargetsError>>,
+    > {
+        Box::pin(ready(self.put_targets_sync(
+            event_bus_name,
+            rule_name,
+            target,
+        )))
+    }
+
+    fn remove_targets<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+        target_ids: &'a [String],
+    ) -> BoxFuture<
+        'a,
+        Result<RemoveTargetsOutput, aws_sdk_eventbridge::error::SdkError<RemoveTargetsError>>,
+    > {
+        Box::pin(ready(self.remove_targets_sync(
+            event_bus_name,
+            rule_name,
+            target_ids,
+        )))
+    }
+
+    fn delete_rule<'a>(
+        &'a self,
+        event_bus_name: &'a str,
+        rule_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DeleteRuleOutput, aws_sdk_eventbridge::error::SdkError<DeleteRuleError>>,
+    > {
+        Box::pin(ready(self.delete_rule_sync(event_bus_name, rule_name)))
+    }
+}
+
+impl Logs for MemoryBackend {
+    fn describe_log_groups<'a>(
+        &'a self,
+        log_group_name_prefix: &'a str,
+        _next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            DescribeLogGroupsOutput,
+            aws_sdk_cloudwatchlogs::error::SdkError<DescribeLogGroupsError>,
+        >,
+    > {
+        Box::pin(ready(Ok(
+            self.describe_log_groups_sync(log_group_name_prefix)
+        )))
+    }
+
+    fn list_tags_for_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            ListLogGroupTagsOutput,
+            aws_sdk_cloudwatchlogs::error::SdkError<ListLogGroupTagsError>,
+        >,
+    > {
+        Box::pin(ready(self.list_log_group_tags_sync(resource_arn)))
+    }
+
+    fn create_log_group<'a>(
+        &'a self,
+        log_group: &'a LogGroup,
+    ) -> BoxFuture<
+        'a,
+        Result<CreateLogGroupOutput, aws_sdk_cloudwatchlogs::error::SdkError<CreateLogGroupError>>,
+    > {
+        Box::pin(ready(self.create_log_group_sync(log_group)))
+    }
+
+    fn put_retention_policy<'a>(
+        &'a self,
+        log_group_name: &'a str,
+        retention_in_days: i32,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            PutRetentionPolicyOutput,
+            aws_sdk_cloudwatchlogs::error::SdkError<PutRetentionPolicyError>,
+        >,
+    > {
+        Box::pin(ready(
+            self.put_retention_policy_sync(log_group_name, retention_in_days),
+        ))
+    }
+
+    fn delete_retention_policy<'a>(
+        &'a self,
+        log_group_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            DeleteRetentionPolicyOutput,
+            aws_sdk_cloudwatchlogs::error::SdkError<DeleteRetentionPolicyError>,
+        >,
+    > {
+        Box::pin(ready(self.delete_retention_policy_sync(log_group_name)))
+    }
+
+    fn associate_kms_key<'a>(
+        &'a self,
+        log_group_name: &'a str,
+        kms_key_id: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            AssociateKmsKeyOutput,
+            aws_sdk_cloudwatchlogs::error::SdkError<AssociateKmsKeyError>,
+        >,
+    > {
+        Box::pin(ready(
+            self.associate_kms_key_sync(log_group_name, kms_key_id),
+        ))
+    }
+
+    fn disassociate_kms_key<'a>(
+        &'a self,
+        log_group_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            DisassociateKmsKeyOutput,
+            aws_sdk_cloudwatchlogs::error::SdkError<DisassociateKmsKeyError>,
+        >,
+    > {
+        Box::pin(ready(self.disassociate_kms_key_sync(log_group_name)))
+    }
+
+    fn tag_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+        tags: &'a [ResourceTag],
+    ) -> BoxFuture<
+        'a,
+        Result<TagLogGroupOutput, aws_sdk_cloudwatchlogs::error::SdkError<TagLogGroupError>>,
+    > {
+        Box::pin(ready(self.tag_log_group_sync(resource_arn, tags)))
+    }
+
+    fn untag_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+        tag_keys: &'a [String],
+    ) -> BoxFuture<
+        'a,
+        Result<UntagLogGroupOutput, aws_sdk_cloudwatchlogs::error::SdkError<UntagLogGroupError>>,
+    > {
+        Box::pin(ready(self.untag_log_group_sync(resource_arn, tag_keys)))
+    }
+}
+
+impl Sts for MemoryBackend {
+    fn get_caller_identity(
+        &self,
+    ) -> BoxFuture<
+        '_,
+        Result<GetCallerIdentityOutput, aws_sdk_sts::error::SdkError<GetCallerIdentityError>>,
+    > {
+        Box::pin(ready(Ok(self.get_caller_identity_sync())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::events;
+    use crate::logs;
+    use crate::scheduler;
+    use crate::sfn;
+
+    const STATE_ARN: &str = "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld";
+
+    #[tokio::test]
+    async fn test_describe_missing_state_machine() {
+        let backend = MemoryBackend::new(DEFAULT_ACCOUNT, "us-west-2");
+
+        let actual = sfn::describe_state_machine_with_tags(&backend, STATE_ARN)
+            .await
+            .unwrap();
+        assert_eq!(actual, None);
+
+        let err = Sfn::list_tags_for_resource(&backend, STATE_ARN)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_resource_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_create_and_describe_state_machine() {
+        let backend = MemoryBackend::new(DEFAULT_ACCOUNT, "us-west-2");
+        let state = StateMachine::test_default();
+
+        sfn::create_state_machine(&backend, &state).await.unwrap();
+
+        let actual = sfn::describe_state_machine_with_tags(&backend, STATE_ARN)
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(Some(state.clone()), actual);
+
+        let err = Sfn::create_state_machine(&backend, &state)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_state_machine_already_exists());
+    }
+
+    #[tokio::test]
+    async fn test_publish_versions() {
+        let backend = MemoryBackend::new(DEFAULT_ACCOUNT, "us-west-2");
+        let mut state = StateMachine::test_default();
+        state.publish = true;
+
+        let actual = sfn::create_state_machine(&backend, &state).await.unwrap();
+        assert_eq!(Some(format!("{}:1", STATE_ARN)), actual);
+
+        state.definition = serde_json::json!({ "StartAt": "SecondState" });
+        state.version_description = Some("deploy abc123".to_string());
+        let actual = sfn::update_state_machine(&backend, STATE_ARN, &state)
+            .await
+            .unwrap();
+        assert_eq!(Some(format!("{}:2", STATE_ARN)), actual);
+
+        let actual: Vec<(u32, Option<String>)> =
+            sfn::list_state_machine_versions(&backend, STATE_ARN)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|v| (v.version, v.description))
+                .collect();
+        similar_asserts::assert_eq!(
+            vec![(2, Some("deploy abc123".to_string())), (1, None)],
+            actual
+        );
+
+        // version keeps definition as of its publish
+        let actual = Sfn::describe_state_machine(&backend, &format!("{}:1", STATE_ARN))
+            .await
+            .unwrap();
+        assert_eq!(r#"{"StartAt":"FirstState"}"#, actual.definition());
+
+        // publish settings are not stored as a part of state machine
+        let actual = sfn::describe_state_machine_with_tags(&backend, STATE_ARN)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!actual.publish);
+        assert_eq!(None, actual.version_description);
+    }
+
+    #[tokio::test]
+    async fn test_log_group_lifecycle() {
+        let backend = MemoryBackend::new(DEFAULT_ACCOUNT, "us-west-2");
+        let log_group_arn = "arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup";
+        let mut log_group = LogGroup::test_default();
+
+        let actual = logs::describe_log_group(&backend, &log_group.name)
+            .await
+            .unwrap();
+        assert_eq!(None, actual);
+
+        logs::create_log_group(&backend, &log_group).await.unwrap();
+        let actual = logs::describe_log_group(&backend, &log_group.name)
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(Some(log_group.clone()), actual);
+
+        let err = Logs::create_log_group(&backend, &log_group)
+            .await
+            .unwrap_err();
+        assert!(
+            err.into_service_error()
+                .is_resource_already_exists_exception()
+        );
+
+        log_group.retention_in_days = None;
+        log_group.kms_key_id = Some("arn:aws:kms:us-west-2:123456789012:key/fubura".to_string());
+        log_group.tags = vec![ResourceTag {
+            key: "Team".to_string(),
+            value: "Platform".to_string(),
+        }];
+        logs::update_log_group(&backend, log_group_arn, &log_group)
+            .await
+            .unwrap();
+        let actual = logs::describe_log_group(&backend, &log_group.name)
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(Some(log_group.clone()), actual);
+
+        let err = Logs::put_retention_policy(&backend, "Missing", 7)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_resource_not_found_exception());
+    }
+
+    #[tokio::test]
+    async fn test_alias_lifecycle() {
+        let backend = MemoryBackend::new(DEFAULT_ACCOUNT, "us-west-2");
+        let mut state = StateMachine::test_default();
+        state.publish = true;
+        sfn::create_state_machine(&backend, &state).await.unwrap();
+
+        let mut alias = StateMachineAlias {
+            name: "live".to_string(),
+            description: None,
+            routing_configuration: vec![crate::types::RoutingConfigurationListItem {
+                state_machine_version_arn: format!("{}:1", STATE_ARN),
+                weight: 100,
+            }],
+        };
+        sfn::create_state_machine_alias(&backend, &alias)
+            .await
+            .unwrap();
+
+        let err = Sfn::create_state_machine_alias(&backend, &alias)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_conflict_exception());
+
+        let alias_arn = format!("{}:live", STATE_ARN);
+        alias.description = Some("production".to_string());
+        sfn::update_state_machine_alias(&backend, &alias_arn, &alias)
+            .await
+            .unwrap();
+        let actual = sfn::list_state_machine_aliases(&backend, STATE_ARN)
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(vec![alias.clone()], actual);
+
+        // weights must sum up to 100, and versions must exist
+        alias.routing_configuration[0].weight = 50;
+        let err = Sfn::update_state_machine_alias(&backend, &alias_arn, &alias)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_validation_exception());
+        alias.routing_configuration[0].weight = 100;
+        alias.routing_configuration[0].state_machine_version_arn = format!("{}:2", STATE_ARN);
+        let err = Sfn::update_state_machine_alias(&backend, &alias_arn, &alias)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_resource_not_found());
+
+        sfn::delete_state_machine_alias(&backend, &alias_arn)
+            .await
+            .unwrap();
+        let actual = sfn::list_state_machine_aliases(&backend, STATE_ARN)
+            .await
+            .unwrap();
+        assert!(actual.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tag_resource_merges_tags() {
+        let backend = MemoryBackend::from_snapshot(
+            MemorySnapshot {
+                account: None,
+                state_machines: vec![StateMachine::test_default()],
+                schedules: vec![],
+                schedule_groups: vec![],
+                state_machine_versions: vec![],
+                executions: vec![],
+                rules: vec![],
+                log_groups: vec![],
+            },
+            "us-west-2",
+        );
+
+        let tags = vec![
+            ResourceTag {
+                key: "Env".to_string(),
+                value: "Prod".to_string(),
+            },
+            ResourceTag {
+                key: "Team".to_string(),
+                value: "Batch".to_string(),
+            },
+        ];
+        sfn::tag_resource(&backend, STATE_ARN, &tags).await.unwrap();
+        sfn::untag_resource(&backend, STATE_ARN, &["Name".to_string()])
+            .await
+            .unwrap();
+
+        let actual = backend.snapshot().state_machines[0].tags.clone();
+        similar_asserts::assert_eq!(tags, actual);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_lifecycle() {
+        let backend = MemoryBackend::new(DEFAULT_ACCOUNT, "us-west-2");
+        let mut schedule = Schedule::test_default();
+        // omitted group falls back to default group
+        schedule.group_name = "".to_string();
+
+        scheduler::create_schedule(&backend, &schedule)
+            .await
+            .unwrap();
+        let err = Scheduler::create_schedule(&backend, &schedule)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_conflict_exception());
+
+        let actual = scheduler::get_schedule(&backend, "default/HelloWorld")
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(Some(Schedule::test_default()), actual);
+
+        scheduler::delete_schedule(&backend, &schedule)
+            .await
+            .unwrap();
+        let actual = scheduler::get_schedule(&backend, "default/HelloWorld")
+            .await
+            .unwrap();
+        assert_eq!(actual, None);
+
+        let err = Scheduler::update_schedule(&backend, &schedule)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_resource_not_found_exception());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_group_lifecycle() {
+        let backend = MemoryBackend::new(DEFAULT_ACCOUNT, "us-west-2");
+        let group = ScheduleGroup::test_default();
+
+        let actual = scheduler::get_schedule_group_with_tags(&backend, "batch")
+            .await
+            .unwrap();
+        assert_eq!(actual, None);
+
+        scheduler::create_schedule_group(&backend, &group)
+            .await
+            .unwrap();
+        let err = Scheduler::create_schedule_group(&backend, &group)
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_conflict_exception());
+
+        let group_arn = "arn:aws:scheduler:us-west-2:123456789012:schedule-group/batch";
+        let tags = vec![ResourceTag {
+            key: "Env".to_string(),
+            value: "Prod".to_string(),
+        }];
+        scheduler::tag_resource(&backend, group_arn, &tags)
+            .await
+            .unwrap();
+        scheduler::untag_resource(&backend, group_arn, &["Team".to_string()])
+            .await
+            .unwrap();
+
+        let actual = scheduler::get_schedule_group_with_tags(&backend, "batch")
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(
+            Some(ScheduleGroup {
+                name: "batch".to_string(),
+                tags,
+            }),
+            actual
+        );
+
+        let mut schedule = Schedule::test_default();
+        schedule.group_name = "batch".to_string();
+        scheduler::create_schedule(&backend, &schedule)
+            .await
+            .unwrap();
+        let actual = scheduler::list_schedule_names(&backend, "batch")
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(vec!["HelloWorld".to_string()], actual);
+
+        // schedules in the group are deleted with it
+        scheduler::delete_schedule_group(&backend, "batch")
+            .await
+            .unwrap();
+        let actual = scheduler::get_schedule(&backend, "batch/HelloWorld")
+            .await
+            .unwrap();
+        assert_eq!(actual, None);
+
+        let err = Scheduler::delete_schedule_group(&backend, "default")
+            .await
+            .unwrap_err();
+        assert!(err.into_service_error().is_validation_exception());
+    }
+
+    #[tokio::test]
+    async fn test_rule_lifecycle() {
+        let backend = MemoryBackend::new(DEFAULT_ACCOUNT, "us-west-2");
+        let rule = Rule::test_default();
+        let name = rule.rule_name_with_bus();
+
+        let actual = events::describe_rule_with_target(&backend, &name, rule.target_id())
+            .await
+            .unwrap();
+        assert_eq!(None, actual);
+
+        events::put_rule_with_target(&backend, &rule).await.unwrap();
+        let actual = events::describe_rule_with_target(&backend, &name, rule.target_id())
+            .await
+            .unwrap();
+        similar_asserts::assert_eq!(Some(rule.clone()), actual);
+
+        // rule with target can't be deleted by DeleteRule only
+        let err = Events::delete_rule(&backend, "default", &rule.name)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            Some("ValidationException"),
+            aws_smithy_types::error::metadata::ProvideErrorMetadata::code(
+                &err.into_service_error()
+            )
+        );
+
+        events::delete_rule(&backend, &rule).await.unwrap();
+        let actual = events::describe_rule_with_target(&backend, &name, rule.target_id())
+            .await
+            .unwrap();
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let snapshot = MemorySnapshot {
+            account: Some("210987654321".to_string()),
+            state_machines: vec![StateMachine::test_default()],
+            schedules: vec![Schedule::test_default()],
+            schedule_groups: vec![ScheduleGroup::test_default()],
+            rules: vec![Rule::test_default()],
+            log_groups: vec![LogGroup::test_default()],
+            state_machine_versions: vec![MemoryStateMachineVersion {
+                version: 1,
+                description: Some("first".to_string()),
+                creation_date: "2024-01-01T00:00:00Z".to_string(),
+                state: StateMachine {
+                    tags: vec![],
+                    ..StateMachine::test_default()
+                },
+            }],
+            executions: vec![Execution {
+                execution_arn: "arn:aws:states:us-west-2:210987654321:execution:HelloWorld:first"
+                    .to_string(),
+                state_machine_arn: "arn:aws:states:us-west-2:210987654321:stateMachine:HelloWorld"
+                    .to_string(),
+                state_machine_version_arn: None,
+                status: "SUCCEEDED".to_string(),
+                start_date: "2024-01-01T00:00:00Z".to_string(),
+                error: None,
+                cause: None,
+            }],
+        };
+
+        let backend = MemoryBackend::from_snapshot(snapshot.clone(), "us-west-2");
+
+        similar_asserts::assert_eq!(snapshot, backend.snapshot());
+    }
+}
@@ -1,27 +1,263 @@
-use anyhow::{Result, bail};
+// Mocks of SchedulerImpl return SdkError as is
+#![cfg_attr(test, allow(clippy::result_large_err))]
+
+use anyhow::{Context, Result, bail};
 use aws_sdk_scheduler as scheduler;
-use aws_sdk_scheduler::operation::create_schedule::{CreateScheduleError, CreateScheduleOutput};
+use aws_sdk_scheduler::operation::create_schedule::builders::CreateScheduleInputBuilder;
+use aws_sdk_scheduler::operation::create_schedule::{
+    CreateScheduleError, CreateScheduleInput, CreateScheduleOutput,
+};
+use aws_sdk_scheduler::operation::create_schedule_group::builders::CreateScheduleGroupInputBuilder;
+use aws_sdk_scheduler::operation::create_schedule_group::{
+    CreateScheduleGroupError, CreateScheduleGroupInput, CreateScheduleGroupOutput,
+};
 use aws_sdk_scheduler::operation::delete_schedule::{DeleteScheduleError, DeleteScheduleOutput};
+use aws_sdk_scheduler::operation::delete_schedule_group::{
+    DeleteScheduleGroupError, DeleteScheduleGroupOutput,
+};
 use aws_sdk_scheduler::operation::get_schedule::{GetScheduleError, GetScheduleOutput};
-use aws_sdk_scheduler::operation::update_schedule::{UpdateScheduleError, UpdateScheduleOutput};
+use aws_sdk_scheduler::operation::get_schedule_group::{
+    GetScheduleGroupError, GetScheduleGroupOutput,
+};
+use aws_sdk_scheduler::operation::list_schedules::{ListSchedulesError, ListSchedulesOutput};
+use aws_sdk_scheduler::operation::list_tags_for_resource::{
+    ListTagsForResourceError, ListTagsForResourceOutput,
+};
+use aws_sdk_scheduler::operation::tag_resource::builders::TagResourceInputBuilder;
+use aws_sdk_scheduler::operation::tag_resource::{
+    TagResourceError, TagResourceInput, TagResourceOutput,
+};
+use aws_sdk_scheduler::operation::untag_resource::{UntagResourceError, UntagResourceOutput};
+use aws_sdk_scheduler::operation::update_schedule::builders::UpdateScheduleInputBuilder;
+use aws_sdk_scheduler::operation::update_schedule::{
+    UpdateScheduleError, UpdateScheduleInput, UpdateScheduleOutput,
+};
 
 #[allow(unused_imports)]
 use mockall::automock;
 
-use crate::types::Schedule;
+use crate::context::BoxFuture;
+use crate::error::FuburaError;
+use crate::telemetry::traced;
+use crate::types::{ResourceTag, Schedule, ScheduleGroup};
 
-pub struct SchedulerImpl {
-    inner: scheduler::Client,
+/// EventBridge Scheduler operations used by fubura.
+///
+/// Implement this to inject your own client into `FuburaContext::new`.
+pub trait Scheduler: Send + Sync {
+    fn get_schedule<'a>(
+        &'a self,
+        group_name: &'a str,
+        schedule_name: &'a str,
+    ) -> BoxFuture<'a, Result<GetScheduleOutput, scheduler::error::SdkError<GetScheduleError>>>;
+
+    fn create_schedule<'a>(
+        &'a self,
+        schedule: &'a Schedule,
+    ) -> BoxFuture<'a, Result<CreateScheduleOutput, scheduler::error::SdkError<CreateScheduleError>>>;
+
+    fn update_schedule<'a>(
+        &'a self,
+        schedule: &'a Schedule,
+    ) -> BoxFuture<'a, Result<UpdateScheduleOutput, scheduler::error::SdkError<UpdateScheduleError>>>;
+
+    fn delete_schedule<'a>(
+        &'a self,
+        schedule: &'a Schedule,
+    ) -> BoxFuture<'a, Result<DeleteScheduleOutput, scheduler::error::SdkError<DeleteScheduleError>>>;
+
+    fn list_schedules<'a>(
+        &'a self,
+        group_name: &'a str,
+        next_token: Option<String>,
+    ) -> BoxFuture<'a, Result<ListSchedulesOutput, scheduler::error::SdkError<ListSchedulesError>>>;
+
+    fn get_schedule_group<'a>(
+        &'a self,
+        group_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<GetScheduleGroupOutput, scheduler::error::SdkError<GetScheduleGroupError>>,
+    >;
+
+    fn create_schedule_group<'a>(
+        &'a self,
+        group: &'a ScheduleGroup,
+    ) -> BoxFuture<
+        'a,
+        Result<CreateScheduleGroupOutput, scheduler::error::SdkError<CreateScheduleGroupError>>,
+    >;
+
+    fn delete_schedule_group<'a>(
+        &'a self,
+        group_name: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DeleteScheduleGroupOutput, scheduler::error::SdkError<DeleteScheduleGroupError>>,
+    >;
+
+    fn list_tags_for_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<ListTagsForResourceOutput, scheduler::error::SdkError<ListTagsForResourceError>>,
+    >;
+
+    fn tag_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+        tags: &'a [ResourceTag],
+    ) -> BoxFuture<'a, Result<TagResourceOutput, scheduler::error::SdkError<TagResourceError>>>;
+
+    fn untag_resource<'a>(
+        &'a self,
+        resource_arn: &'a str,
+        tag_keys: &'a [String],
+    ) -> BoxFuture<'a, Result<UntagResourceOutput, scheduler::error::SdkError<UntagResourceError>>>;
 }
 
+// Implements `Scheduler` by delegating to inherent methods of the given type,
+// which is shared by the aws-sdk based client and its mock.
+macro_rules! impl_scheduler {
+    ($impl:ty) => {
+        impl Scheduler for $impl {
+            fn get_schedule<'a>(
+                &'a self,
+                group_name: &'a str,
+                schedule_name: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<GetScheduleOutput, scheduler::error::SdkError<GetScheduleError>>,
+            > {
+                Box::pin(<$impl>::get_schedule(self, group_name, schedule_name))
+            }
+
+            fn create_schedule<'a>(
+                &'a self,
+                schedule: &'a Schedule,
+            ) -> BoxFuture<
+                'a,
+                Result<CreateScheduleOutput, scheduler::error::SdkError<CreateScheduleError>>,
+            > {
+                Box::pin(<$impl>::create_schedule(self, schedule))
+            }
+
+            fn update_schedule<'a>(
+                &'a self,
+                schedule: &'a Schedule,
+            ) -> BoxFuture<
+                'a,
+                Result<UpdateScheduleOutput, scheduler::error::SdkError<UpdateScheduleError>>,
+            > {
+                Box::pin(<$impl>::update_schedule(self, schedule))
+            }
+
+            fn delete_schedule<'a>(
+                &'a self,
+                schedule: &'a Schedule,
+            ) -> BoxFuture<
+                'a,
+                Result<DeleteScheduleOutput, scheduler::error::SdkError<DeleteScheduleError>>,
+            > {
+                Box::pin(<$impl>::delete_schedule(self, schedule))
+            }
+
+            fn list_schedules<'a>(
+                &'a self,
+                group_name: &'a str,
+                next_token: Option<String>,
+            ) -> BoxFuture<
+                'a,
+                Result<ListSchedulesOutput, scheduler::error::SdkError<ListSchedulesError>>,
+            > {
+                Box::pin(<$impl>::list_schedules(self, group_name, next_token))
+            }
+
+            fn get_schedule_group<'a>(
+                &'a self,
+                group_name: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<GetScheduleGroupOutput, scheduler::error::SdkError<GetScheduleGroupError>>,
+            > {
+                Box::pin(<$impl>::get_schedule_group(self, group_name))
+            }
+
+            fn create_schedule_group<'a>(
+                &'a self,
+                group: &'a ScheduleGroup,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    CreateScheduleGroupOutput,
+                    scheduler::error::SdkError<CreateScheduleGroupError>,
+                >,
+            > {
+                Box::pin(<$impl>::create_schedule_group(self, group))
+            }
+
+            fn delete_schedule_group<'a>(
+                &'a self,
+                group_name: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    DeleteScheduleGroupOutput,
+                    scheduler::error::SdkError<DeleteScheduleGroupError>,
+                >,
+            > {
+                Box::pin(<$impl>::delete_schedule_group(self, group_name))
+            }
+
+            fn list_tags_for_resource<'a>(
+                &'a self,
+                resource_arn: &'a str,
+            ) -> BoxFuture<
+                'a,
+                Result<
+                    ListTagsForResourceOutput,
+                    scheduler::error::SdkError<ListTagsForResourceError>,
+                >,
+            > {
+                Box::pin(<$impl>::list_tags_for_resource(self, resource_arn))
+            }
+
+            fn tag_resource<'a>(
+                &'a self,
+                resource_arn: &'a str,
+                tags: &'a [ResourceTag],
+            ) -> BoxFuture<
+                'a,
+                Result<TagResourceOutput, scheduler::error::SdkError<TagResourceError>>,
+            > {
+                Box::pin(<$impl>::tag_resource(self, resource_arn, tags))
+            }
+
+            fn untag_resource<'a>(
+                &'a self,
+                resource_arn: &'a str,
+                tag_keys: &'a [String],
+            ) -> BoxFuture<
+                'a,
+                Result<UntagResourceOutput, scheduler::error::SdkError<UntagResourceError>>,
+            > {
+                Box::pin(<$impl>::untag_resource(self, resource_arn, tag_keys))
+            }
+        }
+    };
+}
+
+impl_scheduler!(SchedulerImpl);
 #[cfg(test)]
-pub use MockSchedulerImpl as Scheduler;
-#[cfg(not(test))]
-pub use SchedulerImpl as Scheduler;
+impl_scheduler!(MockSchedulerImpl);
+
+pub struct SchedulerImpl {
+    inner: scheduler::Client,
+}
 
 #[cfg_attr(test, automock)]
 impl SchedulerImpl {
-    #[allow(dead_code)]
     pub fn new(inner: scheduler::Client) -> Self {
         Self { inner }
     }
@@ -43,20 +279,8 @@ impl SchedulerImpl {
         &self,
         schedule: &Schedule,
     ) -> Result<CreateScheduleOutput, scheduler::error::SdkError<CreateScheduleError>> {
-        self.inner
-            .create_schedule()
-            .group_name(&schedule.group_name)
-            .name(&schedule.name)
-            .set_description(schedule.description.clone())
-            .state(schedule.state.clone().into())
-            .schedule_expression(schedule.schedule_expression.clone())
-            .set_schedule_expression_timezone(schedule.schedule_expression_timezone.clone())
-            .set_start_date(schedule.start_date)
-            .set_end_date(schedule.end_date)
-            .set_flexible_time_window(schedule.flexible_time_window.clone().map(|v| v.into()))
-            .set_kms_key_arn(schedule.kms_key_arn.clone())
-            .target(schedule.target.clone().into())
-            .send()
+        create_schedule_request(schedule)
+            .send_with(&self.inner)
             .await
     }
 
@@ -64,20 +288,8 @@ impl SchedulerImpl {
         &self,
         schedule: &Schedule,
     ) -> Result<UpdateScheduleOutput, scheduler::error::SdkError<UpdateScheduleError>> {
-        self.inner
-            .update_schedule()
-            .group_name(&schedule.group_name)
-            .name(&schedule.name)
-            .set_description(schedule.description.clone())
-            .state(schedule.state.clone().into())
-            .schedule_expression(schedule.schedule_expression.clone())
-            .set_schedule_expression_timezone(schedule.schedule_expression_timezone.clone())
-            .set_start_date(schedule.start_date)
-            .set_end_date(schedule.end_date)
-            .set_flexible_time_window(schedule.flexible_time_window.clone().map(|v| v.into()))
-            .set_kms_key_arn(schedule.kms_key_arn.clone())
-            .target(schedule.target.clone().into())
-            .send()
+        update_schedule_request(schedule)
+            .send_with(&self.inner)
             .await
     }
 
@@ -92,52 +304,195 @@ impl SchedulerImpl {
             .send()
             .await
     }
+
+    pub async fn list_schedules(
+        &self,
+        group_name: &str,
+        next_token: Option<String>,
+    ) -> Result<ListSchedulesOutput, scheduler::error::SdkError<ListSchedulesError>> {
+        self.inner
+            .list_schedules()
+            .group_name(group_name)
+            .set_next_token(next_token)
+            .send()
+            .await
+    }
+
+    pub async fn get_schedule_group(
+        &self,
+        group_name: &str,
+    ) -> Result<GetScheduleGroupOutput, scheduler::error::SdkError<GetScheduleGroupError>> {
+        self.inner
+            .get_schedule_group()
+            .name(group_name)
+            .send()
+            .await
+    }
+
+    pub async fn create_schedule_group(
+        &self,
+        group: &ScheduleGroup,
+    ) -> Result<CreateScheduleGroupOutput, scheduler::error::SdkError<CreateScheduleGroupError>>
+    {
+        create_schedule_group_request(group)
+            .send_with(&self.inner)
+            .await
+    }
+
+    pub async fn delete_schedule_group(
+        &self,
+        group_name: &str,
+    ) -> Result<DeleteScheduleGroupOutput, scheduler::error::SdkError<DeleteScheduleGroupError>>
+    {
+        self.inner
+            .delete_schedule_group()
+            .name(group_name)
+            .send()
+            .await
+    }
+
+    pub async fn list_tags_for_resource(
+        &self,
+        resource_arn: &str,
+    ) -> Result<ListTagsForResourceOutput, scheduler::error::SdkError<ListTagsForResourceError>>
+    {
+        self.inner
+            .list_tags_for_resource()
+            .resource_arn(resource_arn)
+            .send()
+            .await
+    }
+
+    pub async fn tag_resource(
+        &self,
+        resource_arn: &str,
+        tags: &[ResourceTag],
+    ) -> Result<TagResourceOutput, scheduler::error::SdkError<TagResourceError>> {
+        tag_resource_request(resource_arn, tags)
+            .send_with(&self.inner)
+            .await
+    }
+
+    pub async fn untag_resource(
+        &self,
+        resource_arn: &str,
+        tag_keys: &[String],
+    ) -> Result<UntagResourceOutput, scheduler::error::SdkError<UntagResourceError>> {
+        self.inner
+            .untag_resource()
+            .resource_arn(resource_arn)
+            .set_tag_keys(Some(tag_keys.to_vec()))
+            .send()
+            .await
+    }
 }
 
-pub async fn create_schedule(client: &Scheduler, schedule: &Schedule) -> Result<()> {
-    let res = client.create_schedule(schedule).await;
+// Requests which take fubura models are built apart from clients, so that cassette
+// records them as sent.
+
+pub fn create_schedule_request(schedule: &Schedule) -> CreateScheduleInputBuilder {
+    CreateScheduleInput::builder()
+        .group_name(&schedule.group_name)
+        .name(&schedule.name)
+        .set_description(schedule.description.clone())
+        .state(schedule.state.clone().into())
+        .schedule_expression(schedule.schedule_expression.clone())
+        .set_schedule_expression_timezone(schedule.schedule_expression_timezone.clone())
+        .set_start_date(schedule.start_date)
+        .set_end_date(schedule.end_date)
+        .set_flexible_time_window(schedule.flexible_time_window.clone().map(|v| v.into()))
+        .set_kms_key_arn(schedule.kms_key_arn.clone())
+        .action_after_completion(schedule.action_after_completion.clone().into())
+        .target(schedule.target.clone().into())
+}
+
+pub fn update_schedule_request(schedule: &Schedule) -> UpdateScheduleInputBuilder {
+    UpdateScheduleInput::builder()
+        .group_name(&schedule.group_name)
+        .name(&schedule.name)
+        .set_description(schedule.description.clone())
+        .state(schedule.state.clone().into())
+        .schedule_expression(schedule.schedule_expression.clone())
+        .set_schedule_expression_timezone(schedule.schedule_expression_timezone.clone())
+        .set_start_date(schedule.start_date)
+        .set_end_date(schedule.end_date)
+        .set_flexible_time_window(schedule.flexible_time_window.clone().map(|v| v.into()))
+        .set_kms_key_arn(schedule.kms_key_arn.clone())
+        .action_after_completion(schedule.action_after_completion.clone().into())
+        .target(schedule.target.clone().into())
+}
+
+pub fn create_schedule_group_request(group: &ScheduleGroup) -> CreateScheduleGroupInputBuilder {
+    CreateScheduleGroupInput::builder()
+        .name(&group.name)
+        .set_tags(Some(group.tags.iter().cloned().map(|t| t.into()).collect()))
+}
+
+pub fn tag_resource_request(resource_arn: &str, tags: &[ResourceTag]) -> TagResourceInputBuilder {
+    TagResourceInput::builder()
+        .resource_arn(resource_arn)
+        .set_tags(Some(tags.iter().cloned().map(|t| t.into()).collect()))
+}
+
+pub async fn create_schedule(client: &dyn Scheduler, schedule: &Schedule) -> Result<()> {
+    let res = traced("scheduler:CreateSchedule", client.create_schedule(schedule)).await;
 
     if let Err(e) = res {
-        bail!(
-            "failed to create schedule({}) with error: {}",
-            schedule.name,
-            e.into_service_error()
-        );
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!(
+                "failed to create schedule({}) with error: {}",
+                schedule.name, e
+            ),
+        ));
     }
 
     Ok(())
 }
 
-pub async fn update_schedule(client: &Scheduler, schedule: &Schedule) -> Result<()> {
-    let res = client.update_schedule(schedule).await;
+pub async fn update_schedule(client: &dyn Scheduler, schedule: &Schedule) -> Result<()> {
+    let res = traced("scheduler:UpdateSchedule", client.update_schedule(schedule)).await;
 
     if let Err(e) = res {
-        bail!(
-            "failed to update schedule with error: {}",
-            e.into_service_error()
-        );
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to update schedule with error: {}", e),
+        ));
     }
 
     Ok(())
 }
 
-pub async fn delete_schedule(client: &Scheduler, schedule: &Schedule) -> Result<()> {
-    let res = client.delete_schedule(schedule).await;
+pub async fn delete_schedule(client: &dyn Scheduler, schedule: &Schedule) -> Result<()> {
+    let res = traced("scheduler:DeleteSchedule", client.delete_schedule(schedule)).await;
 
     if let Err(e) = res {
-        bail!(
-            "failed to delete schedule with error: {}",
-            e.into_service_error()
-        );
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to delete schedule with error: {}", e),
+        ));
     }
 
     Ok(())
 }
 
 pub async fn get_schedule(
-    client: &Scheduler,
+    client: &dyn Scheduler,
     schedule_name_with_group: &str,
 ) -> Result<Option<Schedule>> {
+    let schedule = get_schedule_with_unmodeled_fields(client, schedule_name_with_group).await?;
+
+    Ok(schedule.map(|(schedule, _)| schedule))
+}
+
+// Schedule with names of its remote fields which fubura doesn't model, see `Schedule::unmodeled_fields`
+pub async fn get_schedule_with_unmodeled_fields(
+    client: &dyn Scheduler,
+    schedule_name_with_group: &str,
+) -> Result<Option<(Schedule, Vec<&'static str>)>> {
     let split_result = schedule_name_with_group.split_once('/');
     let (group_name, schedule_name) = if let Some((group_name, schedule_name)) = split_result {
         (group_name, schedule_name)
@@ -148,19 +503,203 @@ pub async fn get_schedule(
         );
     };
 
-    let res = client.get_schedule(group_name, schedule_name).await;
+    let res = traced(
+        "scheduler:GetSchedule",
+        client.get_schedule(group_name, schedule_name),
+    )
+    .await;
 
     let schedule = match res {
-        Ok(output) => Some(Schedule::from(output)),
+        Ok(output) => {
+            let unmodeled_fields = Schedule::unmodeled_fields(&output);
+            let schedule = Schedule::try_from(output).with_context(|| {
+                format!("failed to read schedule({})", schedule_name_with_group)
+            })?;
+            Some((schedule, unmodeled_fields))
+        }
         Err(err) => {
             let service_error = err.into_service_error();
             if service_error.is_resource_not_found_exception() {
                 None
             } else {
-                bail!("failed to get schedule: {}", service_error);
+                return Err(FuburaError::from_aws_error(
+                    &service_error,
+                    format!("failed to get schedule: {}", service_error),
+                ));
             }
         }
     };
 
     Ok(schedule)
 }
+
+// Names of schedules in the group, following pagination.
+pub async fn list_schedule_names(client: &dyn Scheduler, group_name: &str) -> Result<Vec<String>> {
+    let mut names = vec![];
+    let mut next_token = None;
+
+    loop {
+        let res = traced(
+            "scheduler:ListSchedules",
+            client.list_schedules(group_name, next_token),
+        )
+        .await;
+
+        let output = match res {
+            Ok(output) => output,
+            Err(err) => {
+                let service_error = err.into_service_error();
+                if service_error.is_resource_not_found_exception() {
+                    return Ok(names);
+                }
+                return Err(FuburaError::from_aws_error(
+                    &service_error,
+                    format!(
+                        "failed to list schedules in group({}): {}",
+                        group_name, service_error
+                    ),
+                ));
+            }
+        };
+
+        names.extend(
+            output
+                .schedules()
+                .iter()
+                .filter_map(|schedule| schedule.name().map(|name| name.to_string())),
+        );
+
+        next_token = output.next_token().map(|token| token.to_string());
+        if next_token.is_none() {
+            return Ok(names);
+        }
+    }
+}
+
+pub async fn get_schedule_group_with_tags(
+    client: &dyn Scheduler,
+    group_name: &str,
+) -> Result<Option<ScheduleGroup>> {
+    let res = traced(
+        "scheduler:GetScheduleGroup",
+        client.get_schedule_group(group_name),
+    )
+    .await;
+
+    let group_arn = match res {
+        Ok(output) => output.arn().unwrap_or_default().to_string(),
+        Err(err) => {
+            let service_error = err.into_service_error();
+            if service_error.is_resource_not_found_exception() {
+                return Ok(None);
+            }
+            return Err(FuburaError::from_aws_error(
+                &service_error,
+                format!("failed to get schedule group: {}", service_error),
+            ));
+        }
+    };
+
+    let res = traced(
+        "scheduler:ListTagsForResource",
+        client.list_tags_for_resource(&group_arn),
+    )
+    .await;
+
+    match res {
+        Ok(output) => Ok(Some(ScheduleGroup {
+            name: group_name.to_string(),
+            tags: output.tags().iter().cloned().map(|t| t.into()).collect(),
+        })),
+        Err(e) => {
+            let e = e.into_service_error();
+            Err(FuburaError::from_aws_error(
+                &e,
+                format!("failed to list tags of schedule group with error: {}", e),
+            ))
+        }
+    }
+}
+
+pub async fn create_schedule_group(client: &dyn Scheduler, group: &ScheduleGroup) -> Result<()> {
+    let res = traced(
+        "scheduler:CreateScheduleGroup",
+        client.create_schedule_group(group),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!(
+                "failed to create schedule group({}) with error: {}",
+                group.name, e
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn delete_schedule_group(client: &dyn Scheduler, group_name: &str) -> Result<()> {
+    let res = traced(
+        "scheduler:DeleteScheduleGroup",
+        client.delete_schedule_group(group_name),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to delete schedule group with error: {}", e),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn tag_resource(
+    client: &dyn Scheduler,
+    resource_arn: &str,
+    tags: &[ResourceTag],
+) -> Result<()> {
+    let res = traced(
+        "scheduler:TagResource",
+        client.tag_resource(resource_arn, tags),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to tag resource with error: {}", e),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn untag_resource(
+    client: &dyn Scheduler,
+    resource_arn: &str,
+    tag_keys: &[String],
+) -> Result<()> {
+    let res = traced(
+        "scheduler:UntagResource",
+        client.untag_resource(resource_arn, tag_keys),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to untag resource with error: {}", e),
+        ));
+    }
+
+    Ok(())
+}
@@ -1,45 +1,321 @@
-use anyhow::{Result, bail};
+// Mocks of SfnImpl return SdkError, which is large by design, as is
+#![cfg_attr(test, allow(clippy::result_large_err))]
+
+use anyhow::{Context, Result, bail};
 use aws_sdk_sfn as sfn;
+use aws_sdk_sfn::error::{DisplayErrorContext, ProvideErrorMetadata};
+use aws_sdk_sfn::operation::create_state_machine::builders::CreateStateMachineInputBuilder;
 use aws_sdk_sfn::operation::create_state_machine::{
-    CreateStateMachineError, CreateStateMachineOutput,
+    CreateStateMachineError, CreateStateMachineInput, CreateStateMachineOutput,
+};
+use aws_sdk_sfn::operation::create_state_machine_alias::builders::CreateStateMachineAliasInputBuilder;
+use aws_sdk_sfn::operation::create_state_machine_alias::{
+    CreateStateMachineAliasError, CreateStateMachineAliasInput, CreateStateMachineAliasOutput,
 };
 use aws_sdk_sfn::operation::delete_state_machine::{
     DeleteStateMachineError, DeleteStateMachineOutput,
 };
+use aws_sdk_sfn::operation::delete_state_machine_alias::{
+    DeleteStateMachineAliasError, DeleteStateMachineAliasOutput,
+};
+use aws_sdk_sfn::operation::describe_execution::{DescribeExecutionError, DescribeExecutionOutput};
 use aws_sdk_sfn::operation::describe_state_machine::{
     DescribeStateMachineError, DescribeStateMachineOutput,
 };
+use aws_sdk_sfn::operation::describe_state_machine_alias::{
+    DescribeStateMachineAliasError, DescribeStateMachineAliasOutput,
+};
+use aws_sdk_sfn::operation::list_executions::{ListExecutionsError, ListExecutionsOutput};
+use aws_sdk_sfn::operation::list_state_machine_aliases::{
+    ListStateMachineAliasesError, ListStateMachineAliasesOutput,
+};
+use aws_sdk_sfn::operation::list_state_machine_versions::{
+    ListStateMachineVersionsError, ListStateMachineVersionsOutput,
+};
 use aws_sdk_sfn::operation::list_tags_for_resource::{
     ListTagsForResourceError, ListTagsForResourceOutput,
 };
-use aws_sdk_sfn::operation::tag_resource::{TagResourceError, TagResourceOutput};
+use aws_sdk_sfn::operation::tag_resource::builders::TagResourceInputBuilder;
+use aws_sdk_sfn::operation::tag_resource::{TagResourceError, TagResourceInput, TagResourceOutput};
 use aws_sdk_sfn::operation::untag_resource::{UntagResourceError, UntagResourceOutput};
+use aws_sdk_sfn::operation::update_state_machine::builders::UpdateStateMachineInputBuilder;
 use aws_sdk_sfn::operation::update_state_machine::{
-    UpdateStateMachineError, UpdateStateMachineOutput,
+    UpdateStateMachineError, UpdateStateMachineInput, UpdateStateMachineOutput,
+};
+use aws_sdk_sfn::operation::update_state_machine_alias::builders::UpdateStateMachineAliasInputBuilder;
+use aws_sdk_sfn::operation::update_state_machine_alias::{
+    UpdateStateMachineAliasError, UpdateStateMachineAliasInput, UpdateStateMachineAliasOutput,
 };
+use aws_sdk_sfn::primitives::DateTimeFormat;
 
 #[allow(unused_imports)]
 use mockall::automock;
 
-use crate::types::{ResourceTag, StateMachine};
+use crate::context::BoxFuture;
+use crate::error::FuburaError;
+use crate::telemetry::traced;
+use crate::types::{
+    EncryptionConfiguration, Execution, ResourceTag, StateMachine, StateMachineAlias,
+    StateMachineVersion, state_machine_version_number,
+};
 
-pub struct SfnImpl {
-    inner: sfn::Client,
+/// Step Functions operations used by fubura.
+///
+/// Implement this to inject your own client into `FuburaContext::new`.
+pub trait Sfn: Send + Sync {
+    fn describe_state_machine<'a>(
+        &'a self,
+        state_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DescribeStateMachineOutput, sfn::error::SdkError<DescribeStateMachineError>>,
+    >;
+
+    fn list_tags_for_resource<'a>(
+        &'a self,
+        state_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<ListTagsForResourceOutput, sfn::error::SdkError<ListTagsForResourceError>>,
+    >;
+
+    fn create_state_machine<'a>(
+        &'a self,
+        state: &'a StateMachine,
+    ) -> BoxFuture<
+        'a,
+        Result<CreateStateMachineOutput, sfn::error::SdkError<CreateStateMachineError>>,
+    >;
+
+    fn update_state_machine<'a>(
+        &'a self,
+        state_arn: &'a str,
+        state: &'a StateMachine,
+    ) -> BoxFuture<
+        'a,
+        Result<UpdateStateMachineOutput, sfn::error::SdkError<UpdateStateMachineError>>,
+    >;
+
+    fn delete_state_machine<'a>(
+        &'a self,
+        state_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DeleteStateMachineOutput, sfn::error::SdkError<DeleteStateMachineError>>,
+    >;
+
+    fn tag_resource<'a>(
+        &'a self,
+        state_arn: &'a str,
+        tags: &'a [ResourceTag],
+    ) -> BoxFuture<'a, Result<TagResourceOutput, sfn::error::SdkError<TagResourceError>>>;
+
+    fn untag_resource<'a>(
+        &'a self,
+        state_arn: &'a str,
+        tags: &'a [String],
+    ) -> BoxFuture<'a, Result<UntagResourceOutput, sfn::error::SdkError<UntagResourceError>>>;
+
+    fn list_state_machine_versions<'a>(
+        &'a self,
+        state_arn: &'a str,
+        next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<ListStateMachineVersionsOutput, sfn::error::SdkError<ListStateMachineVersionsError>>,
+    >;
+
+    fn list_state_machine_aliases<'a>(
+        &'a self,
+        state_arn: &'a str,
+        next_token: Option<String>,
+    ) -> BoxFuture<
+        'a,
+        Result<ListStateMachineAliasesOutput, sfn::error::SdkError<ListStateMachineAliasesError>>,
+    >;
+
+    fn describe_state_machine_alias<'a>(
+        &'a self,
+        alias_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<
+            DescribeStateMachineAliasOutput,
+            sfn::error::SdkError<DescribeStateMachineAliasError>,
+        >,
+    >;
+
+    fn create_state_machine_alias<'a>(
+        &'a self,
+        alias: &'a StateMachineAlias,
+    ) -> BoxFuture<
+        'a,
+        Result<CreateStateMachineAliasOutput, sfn::error::SdkError<CreateStateMachineAliasError>>,
+    >;
+
+    fn update_state_machine_alias<'a>(
+        &'a self,
+        alias_arn: &'a str,
+        alias: &'a StateMachineAlias,
+    ) -> BoxFuture<
+        'a,
+        Result<UpdateStateMachineAliasOutput, sfn::error::SdkError<UpdateStateMachineAliasError>>,
+    >;
+
+    fn delete_state_machine_alias<'a>(
+        &'a self,
+        alias_arn: &'a str,
+    ) -> BoxFuture<
+        'a,
+        Result<DeleteStateMachineAliasOutput, sfn::error::SdkError<DeleteStateMachineAliasError>>,
+    >;
+
+    fn list_executions<'a>(
+        &'a self,
+        state_arn: &'a str,
+        next_token: Option<String>,
+    ) -> BoxFuture<'a, Result<ListExecutionsOutput, sfn::error::SdkError<ListExecutionsError>>>;
+
+    fn describe_execution<'a>(
+        &'a self,
+        execution_arn: &'a str,
+    ) -> BoxFuture<'a, Result<DescribeExecutionOutput, sfn::error::SdkError<DescribeExecutionError>>>;
 }
 
+// Implements `Sfn` by delegating to inherent methods of the given type,
+// which is shared by the aws-sdk based client and its mock.
+macro_rules! impl_sfn {
+    ($impl:ty) => {
+        impl Sfn for $impl {
+            fn describe_state_machine<'a>(
+                &'a self,
+                state_arn: &'a str,
+            ) -> BoxFuture<'a, Result<DescribeStateMachineOutput, sfn::error::SdkError<DescribeStateMachineError>>> {
+                Box::pin(<$impl>::describe_state_machine(self, state_arn))
+            }
+
+            fn list_tags_for_resource<'a>(
+                &'a self,
+                state_arn: &'a str,
+            ) -> BoxFuture<'a, Result<ListTagsForResourceOutput, sfn::error::SdkError<ListTagsForResourceError>>> {
+                Box::pin(<$impl>::list_tags_for_resource(self, state_arn))
+            }
+
+            fn create_state_machine<'a>(
+                &'a self,
+                state: &'a StateMachine,
+            ) -> BoxFuture<'a, Result<CreateStateMachineOutput, sfn::error::SdkError<CreateStateMachineError>>> {
+                Box::pin(<$impl>::create_state_machine(self, state))
+            }
+
+            fn update_state_machine<'a>(
+                &'a self,
+                state_arn: &'a str,
+                state: &'a StateMachine,
+            ) -> BoxFuture<'a, Result<UpdateStateMachineOutput, sfn::error::SdkError<UpdateStateMachineError>>> {
+                Box::pin(<$impl>::update_state_machine(self, state_arn, state))
+            }
+
+            fn delete_state_machine<'a>(
+                &'a self,
+                state_arn: &'a str,
+            ) -> BoxFuture<'a, Result<DeleteStateMachineOutput, sfn::error::SdkError<DeleteStateMachineError>>> {
+                Box::pin(<$impl>::delete_state_machine(self, state_arn))
+            }
+
+            fn tag_resource<'a>(
+                &'a self,
+                state_arn: &'a str,
+                tags: &'a [ResourceTag],
+            ) -> BoxFuture<'a, Result<TagResourceOutput, sfn::error::SdkError<TagResourceError>>> {
+                Box::pin(<$impl>::tag_resource(self, state_arn, tags))
+            }
+
+            fn untag_resource<'a>(
+                &'a self,
+                state_arn: &'a str,
+                tags: &'a [String],
+            ) -> BoxFuture<'a, Result<UntagResourceOutput, sfn::error::SdkError<UntagResourceError>>> {
+                Box::pin(<$impl>::untag_resource(self, state_arn, tags))
+            }
+
+            fn list_state_machine_versions<'a>(
+                &'a self,
+                state_arn: &'a str,
+                next_token: Option<String>,
+            ) -> BoxFuture<'a, Result<ListStateMachineVersionsOutput, sfn::error::SdkError<ListStateMachineVersionsError>>> {
+                Box::pin(<$impl>::list_state_machine_versions(self, state_arn, next_token))
+            }
+
+            fn list_state_machine_aliases<'a>(
+                &'a self,
+                state_arn: &'a str,
+                next_token: Option<String>,
+            ) -> BoxFuture<'a, Result<ListStateMachineAliasesOutput, sfn::error::SdkError<ListStateMachineAliasesError>>> {
+                Box::pin(<$impl>::list_state_machine_aliases(self, state_arn, next_token))
+            }
+
+            fn describe_state_machine_alias<'a>(
+                &'a self,
+                alias_arn: &'a str,
+            ) -> BoxFuture<'a, Result<DescribeStateMachineAliasOutput, sfn::error::SdkError<DescribeStateMachineAliasError>>> {
+                Box::pin(<$impl>::describe_state_machine_alias(self, alias_arn))
+            }
+
+            fn create_state_machine_alias<'a>(
+                &'a self,
+                alias: &'a StateMachineAlias,
+            ) -> BoxFuture<'a, Result<CreateStateMachineAliasOutput, sfn::error::SdkError<CreateStateMachineAliasError>>> {
+                Box::pin(<$impl>::create_state_machine_alias(self, alias))
+            }
+
+            fn update_state_machine_alias<'a>(
+                &'a self,
+                alias_arn: &'a str,
+                alias: &'a StateMachineAlias,
+            ) -> BoxFuture<'a, Result<UpdateStateMachineAliasOutput, sfn::error::SdkError<UpdateStateMachineAliasError>>> {
+                Box::pin(<$impl>::update_state_machine_alias(self, alias_arn, alias))
+            }
+
+            fn delete_state_machine_alias<'a>(
+                &'a self,
+                alias_arn: &'a str,
+            ) -> BoxFuture<'a, Result<DeleteStateMachineAliasOutput, sfn::error::SdkError<DeleteStateMachineAliasError>>> {
+                Box::pin(<$impl>::delete_state_machine_alias(self, alias_arn))
+            }
+
+            fn list_executions<'a>(
+                &'a self,
+                state_arn: &'a str,
+                next_token: Option<String>,
+            ) -> BoxFuture<'a, Result<ListExecutionsOutput, sfn::error::SdkError<ListExecutionsError>>> {
+                Box::pin(<$impl>::list_executions(self, state_arn, next_token))
+            }
+
+            fn describe_execution<'a>(
+                &'a self,
+                execution_arn: &'a str,
+            ) -> BoxFuture<'a, Result<DescribeExecutionOutput, sfn::error::SdkError<DescribeExecutionError>>> {
+                Box::pin(<$impl>::describe_execution(self, execution_arn))
+            }
+        }
+    };
+}
+
+impl_sfn!(SfnImpl);
 #[cfg(test)]
-pub use MockSfnImpl as Sfn;
-#[cfg(not(test))]
-pub use SfnImpl as Sfn;
+impl_sfn!(MockSfnImpl);
+
+pub struct SfnImpl {
+    inner: sfn::Client,
+}
 
 #[cfg_attr(test, automock)]
 impl SfnImpl {
-    #[allow(dead_code)]
     pub fn new(inner: sfn::Client) -> Self {
         Self { inner }
     }
 
-    #[allow(dead_code)]
     pub async fn describe_state_machine(
         &self,
         state_arn: &str,
@@ -51,7 +327,6 @@ impl SfnImpl {
             .await
     }
 
-    #[allow(dead_code)]
     pub async fn list_tags_for_resource(
         &self,
         state_arn: &str,
@@ -67,23 +342,10 @@ impl SfnImpl {
         &self,
         state: &StateMachine,
     ) -> Result<CreateStateMachineOutput, sfn::error::SdkError<CreateStateMachineError>> {
-        // XXX: Handle publish and version_description some day?
-
-        let mut builder = self
-            .inner
-            .create_state_machine()
-            .name(&state.name)
-            .definition(serde_json::to_string(&state.definition).unwrap())
-            .role_arn(&state.role_arn)
-            .r#type(state.r#type.into())
-            .set_logging_configuration(state.logging_configuration.clone().map(|lc| lc.into()))
-            .set_tracing_configuration(state.tracing_configuration.clone().map(|tc| tc.into()));
-
-        for tag in &state.tags {
-            builder = builder.tags(tag.clone().into());
-        }
-
-        builder.send().await
+        create_state_machine_request(state)
+            .map_err(sfn::error::SdkError::construction_failure)?
+            .send_with(&self.inner)
+            .await
     }
 
     pub async fn update_state_machine(
@@ -91,16 +353,9 @@ impl SfnImpl {
         state_arn: &str,
         state: &StateMachine,
     ) -> Result<UpdateStateMachineOutput, sfn::error::SdkError<UpdateStateMachineError>> {
-        // XXX: Handle publish and version_description some day?
-
-        self.inner
-            .update_state_machine()
-            .state_machine_arn(state_arn)
-            .definition(serde_json::to_string(&state.definition).unwrap())
-            .role_arn(&state.role_arn)
-            .set_logging_configuration(state.logging_configuration.clone().map(|lc| lc.into()))
-            .set_tracing_configuration(state.tracing_configuration.clone().map(|tc| tc.into()))
-            .send()
+        update_state_machine_request(state_arn, state)
+            .map_err(sfn::error::SdkError::construction_failure)?
+            .send_with(&self.inner)
             .await
     }
 
@@ -120,13 +375,9 @@ impl SfnImpl {
         state_arn: &str,
         tags: &[ResourceTag],
     ) -> Result<TagResourceOutput, sfn::error::SdkError<TagResourceError>> {
-        let mut builder = self.inner.tag_resource().resource_arn(state_arn);
-
-        for tag in tags {
-            builder = builder.tags(tag.clone().into());
-        }
-
-        builder.send().await
+        tag_resource_request(state_arn, tags)
+            .send_with(&self.inner)
+            .await
     }
 
     pub async fn untag_resource(
@@ -142,56 +393,290 @@ impl SfnImpl {
 
         builder.send().await
     }
+
+    pub async fn list_state_machine_versions(
+        &self,
+        state_arn: &str,
+        next_token: Option<String>,
+    ) -> Result<ListStateMachineVersionsOutput, sfn::error::SdkError<ListStateMachineVersionsError>>
+    {
+        self.inner
+            .list_state_machine_versions()
+            .state_machine_arn(state_arn)
+            .set_next_token(next_token)
+            .send()
+            .await
+    }
+
+    pub async fn list_state_machine_aliases(
+        &self,
+        state_arn: &str,
+        next_token: Option<String>,
+    ) -> Result<ListStateMachineAliasesOutput, sfn::error::SdkError<ListStateMachineAliasesError>>
+    {
+        self.inner
+            .list_state_machine_aliases()
+            .state_machine_arn(state_arn)
+            .set_next_token(next_token)
+            .send()
+            .await
+    }
+
+    pub async fn describe_state_machine_alias(
+        &self,
+        alias_arn: &str,
+    ) -> Result<DescribeStateMachineAliasOutput, sfn::error::SdkError<DescribeStateMachineAliasError>>
+    {
+        self.inner
+            .describe_state_machine_alias()
+            .state_machine_alias_arn(alias_arn)
+            .send()
+            .await
+    }
+
+    pub async fn create_state_machine_alias(
+        &self,
+        alias: &StateMachineAlias,
+    ) -> Result<CreateStateMachineAliasOutput, sfn::error::SdkError<CreateStateMachineAliasError>>
+    {
+        create_state_machine_alias_request(alias)
+            .send_with(&self.inner)
+            .await
+    }
+
+    pub async fn update_state_machine_alias(
+        &self,
+        alias_arn: &str,
+        alias: &StateMachineAlias,
+    ) -> Result<UpdateStateMachineAliasOutput, sfn::error::SdkError<UpdateStateMachineAliasError>>
+    {
+        update_state_machine_alias_request(alias_arn, alias)
+            .send_with(&self.inner)
+            .await
+    }
+
+    pub async fn delete_state_machine_alias(
+        &self,
+        alias_arn: &str,
+    ) -> Result<DeleteStateMachineAliasOutput, sfn::error::SdkError<DeleteStateMachineAliasError>>
+    {
+        self.inner
+            .delete_state_machine_alias()
+            .state_machine_alias_arn(alias_arn)
+            .send()
+            .await
+    }
+
+    pub async fn list_executions(
+        &self,
+        state_arn: &str,
+        next_token: Option<String>,
+    ) -> Result<ListExecutionsOutput, sfn::error::SdkError<ListExecutionsError>> {
+        self.inner
+            .list_executions()
+            .state_machine_arn(state_arn)
+            .set_next_token(next_token)
+            .send()
+            .await
+    }
+
+    pub async fn describe_execution(
+        &self,
+        execution_arn: &str,
+    ) -> Result<DescribeExecutionOutput, sfn::error::SdkError<DescribeExecutionError>> {
+        self.inner
+            .describe_execution()
+            .execution_arn(execution_arn)
+            .send()
+            .await
+    }
 }
 
-pub async fn create_state_machine(client: &Sfn, state: &StateMachine) -> Result<()> {
-    let res = client.create_state_machine(state).await;
+// Requests which take fubura models are built apart from clients, so that cassette
+// records them as sent.
 
-    if let Err(e) = res {
-        bail!(
-            "failed to create state machine({}) with error: {}",
-            state.name,
-            e.into_service_error()
-        );
+pub fn create_state_machine_request(
+    state: &StateMachine,
+) -> Result<CreateStateMachineInputBuilder, FuburaError> {
+    let logging_configuration = state
+        .logging_configuration
+        .clone()
+        .map(|lc| lc.try_into())
+        .transpose()?;
+
+    let mut builder = CreateStateMachineInput::builder()
+        .name(&state.name)
+        .definition(serde_json::to_string(&state.definition).unwrap())
+        .role_arn(&state.role_arn)
+        .r#type(state.r#type.clone().into())
+        .set_logging_configuration(logging_configuration)
+        .set_tracing_configuration(state.tracing_configuration.clone().map(|tc| tc.into()))
+        .set_encryption_configuration(state.encryption_configuration.clone().map(|ec| ec.into()))
+        .publish(state.publish)
+        .set_version_description(state.version_description.clone());
+
+    for tag in &state.tags {
+        builder = builder.tags(tag.clone().into());
     }
 
-    Ok(())
+    Ok(builder)
 }
 
-pub async fn update_state_machine(
-    client: &Sfn,
+pub fn update_state_machine_request(
     state_arn: &str,
     state: &StateMachine,
-) -> Result<()> {
-    let res = client.update_state_machine(state_arn, state).await;
+) -> Result<UpdateStateMachineInputBuilder, FuburaError> {
+    let logging_configuration = state
+        .logging_configuration
+        .clone()
+        .map(|lc| lc.try_into())
+        .transpose()?;
 
-    if let Err(e) = res {
-        bail!(
-            "failed to update state machine({}) with error: {}",
-            state.name,
-            e.into_service_error()
+    Ok(UpdateStateMachineInput::builder()
+        .state_machine_arn(state_arn)
+        .definition(serde_json::to_string(&state.definition).unwrap())
+        .role_arn(&state.role_arn)
+        .set_logging_configuration(logging_configuration)
+        .set_tracing_configuration(state.tracing_configuration.clone().map(|tc| tc.into()))
+        // Omitted encryption configuration means AWS owned key, so reset it explicitly
+        .encryption_configuration(
+            state
+                .encryption_configuration
+                .clone()
+                .unwrap_or_else(EncryptionConfiguration::aws_owned_key)
+                .into(),
+        )
+        .publish(state.publish)
+        .set_version_description(state.version_description.clone()))
+}
+
+pub fn tag_resource_request(state_arn: &str, tags: &[ResourceTag]) -> TagResourceInputBuilder {
+    let mut builder = TagResourceInput::builder().resource_arn(state_arn);
+
+    for tag in tags {
+        builder = builder.tags(tag.clone().into());
+    }
+
+    builder
+}
+
+pub fn create_state_machine_alias_request(
+    alias: &StateMachineAlias,
+) -> CreateStateMachineAliasInputBuilder {
+    let mut builder = CreateStateMachineAliasInput::builder()
+        .name(&alias.name)
+        .set_description(alias.description.clone());
+
+    for item in &alias.routing_configuration {
+        builder = builder.routing_configuration(item.clone().into());
+    }
+
+    builder
+}
+
+pub fn update_state_machine_alias_request(
+    alias_arn: &str,
+    alias: &StateMachineAlias,
+) -> UpdateStateMachineAliasInputBuilder {
+    // Omitted description is kept as is, so send empty one to remove it
+    let mut builder = UpdateStateMachineAliasInput::builder()
+        .state_machine_alias_arn(alias_arn)
+        .description(alias.description.clone().unwrap_or_default());
+
+    for item in &alias.routing_configuration {
+        builder = builder.routing_configuration(item.clone().into());
+    }
+
+    builder
+}
+
+// Request may fail before being sent, e.g. on invalid logging configuration.
+fn sdk_error<E>(err: sfn::error::SdkError<E>, message: String) -> anyhow::Error
+where
+    E: std::error::Error + ProvideErrorMetadata + Send + Sync + 'static,
+{
+    if let Some(service_error) = err.as_service_error() {
+        return FuburaError::from_aws_error(
+            service_error,
+            format!("{} with error: {}", message, service_error),
         );
     }
 
-    Ok(())
+    if let Some(e) = std::error::Error::source(&err).and_then(|e| e.downcast_ref::<FuburaError>()) {
+        return anyhow::Error::new(e.clone()).context(message);
+    }
+
+    anyhow::anyhow!("{} with error: {}", message, DisplayErrorContext(&err))
+}
+
+// Returns arn of published version, if state has `publish`.
+pub async fn create_state_machine(
+    client: &dyn Sfn,
+    state: &StateMachine,
+) -> Result<Option<String>> {
+    let res = traced("sfn:CreateStateMachine", client.create_state_machine(state)).await;
+
+    match res {
+        Ok(output) => Ok(output
+            .state_machine_version_arn()
+            .map(|arn| arn.to_string())),
+        Err(e) => Err(sdk_error(
+            e,
+            format!("failed to create state machine({})", state.name),
+        )),
+    }
 }
 
-pub async fn delete_state_machine(client: &Sfn, state_arn: &str) -> Result<()> {
-    let res = client.delete_state_machine(state_arn).await;
+// Returns arn of published version, if state has `publish`.
+pub async fn update_state_machine(
+    client: &dyn Sfn,
+    state_arn: &str,
+    state: &StateMachine,
+) -> Result<Option<String>> {
+    let res = traced(
+        "sfn:UpdateStateMachine",
+        client.update_state_machine(state_arn, state),
+    )
+    .await;
+
+    match res {
+        Ok(output) => Ok(output
+            .state_machine_version_arn()
+            .map(|arn| arn.to_string())),
+        Err(e) => Err(sdk_error(
+            e,
+            format!("failed to update state machine({})", state.name),
+        )),
+    }
+}
+
+pub async fn delete_state_machine(client: &dyn Sfn, state_arn: &str) -> Result<()> {
+    let res = traced(
+        "sfn:DeleteStateMachine",
+        client.delete_state_machine(state_arn),
+    )
+    .await;
 
     if let Err(e) = res {
-        bail!(
-            "failed to delete state machine({}) with error: {}",
-            state_arn,
-            e.into_service_error()
-        );
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!(
+                "failed to delete state machine({}) with error: {}",
+                state_arn, e
+            ),
+        ));
     }
 
     Ok(())
 }
 
-async fn list_tags_for_resource(client: &Sfn, state_arn: &str) -> Result<Vec<ResourceTag>> {
-    let res = client.list_tags_for_resource(state_arn).await;
+async fn list_tags_for_resource(client: &dyn Sfn, state_arn: &str) -> Result<Vec<ResourceTag>> {
+    let res = traced(
+        "sfn:ListTagsForResource",
+        client.list_tags_for_resource(state_arn),
+    )
+    .await;
 
     match res {
         Ok(output) => {
@@ -203,61 +688,426 @@ async fn list_tags_for_resource(client: &Sfn, state_arn: &str) -> Result<Vec<Res
             Ok(tags)
         }
         Err(err) => {
-            bail!(
-                "failed to list tags for resource({}) with error: {}",
-                state_arn,
-                err.into_service_error()
-            );
+            let err = err.into_service_error();
+            Err(FuburaError::from_aws_error(
+                &err,
+                format!(
+                    "failed to list tags for resource({}) with error: {}",
+                    state_arn, err
+                ),
+            ))
         }
     }
 }
 
-pub async fn tag_resource(client: &Sfn, state_arn: &str, tags: &[ResourceTag]) -> Result<()> {
-    let res = client.tag_resource(state_arn, tags).await;
+pub async fn tag_resource(client: &dyn Sfn, state_arn: &str, tags: &[ResourceTag]) -> Result<()> {
+    let res = traced("sfn:TagResource", client.tag_resource(state_arn, tags)).await;
 
     if let Err(e) = res {
-        bail!(
-            "failed to tag resource with error: {}",
-            e.into_service_error()
-        );
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to tag resource with error: {}", e),
+        ));
     }
 
     Ok(())
 }
 
-pub async fn untag_resource(client: &Sfn, state_arn: &str, tags: &[String]) -> Result<()> {
-    let res = client.untag_resource(state_arn, tags).await;
+pub async fn untag_resource(client: &dyn Sfn, state_arn: &str, tags: &[String]) -> Result<()> {
+    let res = traced("sfn:UntagResource", client.untag_resource(state_arn, tags)).await;
 
     if let Err(e) = res {
-        bail!(
-            "failed to untag resource with error: {}",
-            e.into_service_error()
-        );
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!("failed to untag resource with error: {}", e),
+        ));
     }
 
     Ok(())
 }
 
+// Versions of state machine from the latest, with their descriptions.
+pub async fn list_state_machine_versions(
+    client: &dyn Sfn,
+    state_arn: &str,
+) -> Result<Vec<StateMachineVersion>> {
+    let mut versions = vec![];
+    let mut next_token = None;
+
+    loop {
+        let res = traced(
+            "sfn:ListStateMachineVersions",
+            client.list_state_machine_versions(state_arn, next_token),
+        )
+        .await;
+
+        let output = match res {
+            Ok(output) => output,
+            Err(err) => {
+                let err = err.into_service_error();
+                return Err(FuburaError::from_aws_error(
+                    &err,
+                    format!(
+                        "failed to list versions of state machine({}) with error: {}",
+                        state_arn, err
+                    ),
+                ));
+            }
+        };
+
+        for item in output.state_machine_versions() {
+            let version_arn = item.state_machine_version_arn();
+            let Some(version) = state_machine_version_number(version_arn) else {
+                bail!("unexpected state machine version arn: {}", version_arn);
+            };
+
+            // Description is only returned by DescribeStateMachine
+            let res = traced(
+                "sfn:DescribeStateMachine",
+                client.describe_state_machine(version_arn),
+            )
+            .await;
+            let description = match res {
+                Ok(output) => output.description().map(|d| d.to_string()),
+                Err(err) => {
+                    let err = err.into_service_error();
+                    return Err(FuburaError::from_aws_error(
+                        &err,
+                        format!(
+                            "failed to describe state machine version({}) with error: {}",
+                            version_arn, err
+                        ),
+                    ));
+                }
+            };
+
+            versions.push(StateMachineVersion {
+                version,
+                version_arn: version_arn.to_string(),
+                description,
+                creation_date: item.creation_date().fmt(DateTimeFormat::DateTime)?,
+            });
+        }
+
+        next_token = output.next_token().map(|token| token.to_string());
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(versions)
+}
+
+// Aliases of state machine, in the order of their names.
+pub async fn list_state_machine_aliases(
+    client: &dyn Sfn,
+    state_arn: &str,
+) -> Result<Vec<StateMachineAlias>> {
+    let mut aliases = vec![];
+    let mut next_token = None;
+
+    loop {
+        let res = traced(
+            "sfn:ListStateMachineAliases",
+            client.list_state_machine_aliases(state_arn, next_token),
+        )
+        .await;
+
+        let output = match res {
+            Ok(output) => output,
+            Err(err) => {
+                let err = err.into_service_error();
+                return Err(FuburaError::from_aws_error(
+                    &err,
+                    format!(
+                        "failed to list aliases of state machine({}) with error: {}",
+                        state_arn, err
+                    ),
+                ));
+            }
+        };
+
+        for item in output.state_machine_aliases() {
+            let alias_arn = item.state_machine_alias_arn();
+            let res = traced(
+                "sfn:DescribeStateMachineAlias",
+                client.describe_state_machine_alias(alias_arn),
+            )
+            .await;
+
+            match res {
+                Ok(output) => aliases.push(StateMachineAlias::from(output)),
+                Err(err) => {
+                    let err = err.into_service_error();
+                    return Err(FuburaError::from_aws_error(
+                        &err,
+                        format!(
+                            "failed to describe state machine alias({}) with error: {}",
+                            alias_arn, err
+                        ),
+                    ));
+                }
+            }
+        }
+
+        next_token = output.next_token().map(|token| token.to_string());
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    aliases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(aliases)
+}
+
+pub async fn describe_state_machine_alias(
+    client: &dyn Sfn,
+    alias_arn: &str,
+) -> Result<Option<StateMachineAlias>> {
+    let res = traced(
+        "sfn:DescribeStateMachineAlias",
+        client.describe_state_machine_alias(alias_arn),
+    )
+    .await;
+
+    match res {
+        Ok(output) => Ok(Some(StateMachineAlias::from(output))),
+        Err(err) => {
+            let err = err.into_service_error();
+            if err.is_resource_not_found() {
+                Ok(None)
+            } else {
+                Err(FuburaError::from_aws_error(
+                    &err,
+                    format!(
+                        "failed to describe state machine alias({}) with error: {}",
+                        alias_arn, err
+                    ),
+                ))
+            }
+        }
+    }
+}
+
+pub async fn create_state_machine_alias(client: &dyn Sfn, alias: &StateMachineAlias) -> Result<()> {
+    let res = traced(
+        "sfn:CreateStateMachineAlias",
+        client.create_state_machine_alias(alias),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!(
+                "failed to create state machine alias({}) with error: {}",
+                alias.name, e
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn update_state_machine_alias(
+    client: &dyn Sfn,
+    alias_arn: &str,
+    alias: &StateMachineAlias,
+) -> Result<()> {
+    let res = traced(
+        "sfn:UpdateStateMachineAlias",
+        client.update_state_machine_alias(alias_arn, alias),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!(
+                "failed to update state machine alias({}) with error: {}",
+                alias_arn, e
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn delete_state_machine_alias(client: &dyn Sfn, alias_arn: &str) -> Result<()> {
+    let res = traced(
+        "sfn:DeleteStateMachineAlias",
+        client.delete_state_machine_alias(alias_arn),
+    )
+    .await;
+
+    if let Err(e) = res {
+        let e = e.into_service_error();
+        return Err(FuburaError::from_aws_error(
+            &e,
+            format!(
+                "failed to delete state machine alias({}) with error: {}",
+                alias_arn, e
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+// Executions of state machine, version or alias, from the latest.
+pub async fn list_executions(client: &dyn Sfn, state_arn: &str) -> Result<Vec<Execution>> {
+    let mut executions = vec![];
+    let mut next_token = None;
+
+    loop {
+        let res = traced(
+            "sfn:ListExecutions",
+            client.list_executions(state_arn, next_token),
+        )
+        .await;
+
+        let output = match res {
+            Ok(output) => output,
+            Err(err) => {
+                let err = err.into_service_error();
+                return Err(FuburaError::from_aws_error(
+                    &err,
+                    format!(
+                        "failed to list executions of state machine({}) with error: {}",
+                        state_arn, err
+                    ),
+                ));
+            }
+        };
+
+        for item in output.executions() {
+            executions.push(Execution {
+                execution_arn: item.execution_arn().to_string(),
+                state_machine_arn: item.state_machine_arn().to_string(),
+                state_machine_version_arn: item.state_machine_version_arn().map(|s| s.to_string()),
+                status: item.status().as_str().to_string(),
+                start_date: item.start_date().fmt(DateTimeFormat::DateTime)?,
+                error: None,
+                cause: None,
+            });
+        }
+
+        next_token = output.next_token().map(|token| token.to_string());
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(executions)
+}
+
+pub async fn describe_execution(client: &dyn Sfn, execution_arn: &str) -> Result<Execution> {
+    let res = traced(
+        "sfn:DescribeExecution",
+        client.describe_execution(execution_arn),
+    )
+    .await;
+
+    match res {
+        Ok(output) => Ok(Execution {
+            execution_arn: output.execution_arn().to_string(),
+            state_machine_arn: output.state_machine_arn().to_string(),
+            state_machine_version_arn: output.state_machine_version_arn().map(|s| s.to_string()),
+            status: output.status().as_str().to_string(),
+            start_date: output.start_date().fmt(DateTimeFormat::DateTime)?,
+            error: output.error().map(|s| s.to_string()),
+            cause: output.cause().map(|s| s.to_string()),
+        }),
+        Err(err) => {
+            let err = err.into_service_error();
+            Err(FuburaError::from_aws_error(
+                &err,
+                format!(
+                    "failed to describe execution({}) with error: {}",
+                    execution_arn, err
+                ),
+            ))
+        }
+    }
+}
+
+// State machine as of the version, which has no tags.
+pub async fn describe_state_machine_version(
+    client: &dyn Sfn,
+    version_arn: &str,
+) -> Result<StateMachine> {
+    let res = traced(
+        "sfn:DescribeStateMachine",
+        client.describe_state_machine(version_arn),
+    )
+    .await;
+
+    match res {
+        Ok(output) => match StateMachine::try_from(output) {
+            Ok(sfn) => Ok(sfn),
+            Err(e) => bail!(
+                "failed to read state machine version({}): {}",
+                version_arn,
+                e
+            ),
+        },
+        Err(err) => {
+            let err = err.into_service_error();
+            Err(FuburaError::from_aws_error(
+                &err,
+                format!(
+                    "failed to describe state machine version({}) with error: {}",
+                    version_arn, err
+                ),
+            ))
+        }
+    }
+}
+
 pub async fn describe_state_machine_with_tags(
-    client: &Sfn,
+    client: &dyn Sfn,
     state_arn: &str,
 ) -> Result<Option<StateMachine>> {
-    let res = client.describe_state_machine(state_arn).await;
+    let state = describe_state_machine_with_unmodeled_fields(client, state_arn).await?;
+
+    Ok(state.map(|(state, _)| state))
+}
+
+// State machine with tags, and names of its remote fields which fubura doesn't model,
+// see `StateMachine::unmodeled_fields`
+pub async fn describe_state_machine_with_unmodeled_fields(
+    client: &dyn Sfn,
+    state_arn: &str,
+) -> Result<Option<(StateMachine, Vec<&'static str>)>> {
+    let res = traced(
+        "sfn:DescribeStateMachine",
+        client.describe_state_machine(state_arn),
+    )
+    .await;
 
     match res {
         Ok(output) => {
             let tags = list_tags_for_resource(client, state_arn).await?;
-            let mut sfn = StateMachine::from(output);
+            let unmodeled_fields = StateMachine::unmodeled_fields(&output);
+            let mut sfn = StateMachine::try_from(output)
+                .with_context(|| format!("failed to read state machine({})", state_arn))?;
             sfn.tags = tags;
 
-            Ok(Some(sfn))
+            Ok(Some((sfn, unmodeled_fields)))
         }
         Err(err) => {
             let service_error = err.into_service_error();
             if service_error.is_state_machine_does_not_exist() {
                 Ok(None)
             } else {
-                bail!("failed to describe state machine: {}", service_error);
+                Err(FuburaError::from_aws_error(
+                    &service_error,
+                    format!("failed to describe state machine: {}", service_error),
+                ))
             }
         }
     }
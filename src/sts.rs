@@ -1,4 +1,9 @@
+// Mock of StsImpl returns SdkError as is
+#![cfg_attr(test, allow(clippy::result_large_err))]
+
+use anyhow::Result;
 use aws_sdk_sts as sts;
+use aws_sdk_sts::error::DisplayErrorContext;
 use aws_sdk_sts::operation::get_caller_identity::{
     GetCallerIdentityError, GetCallerIdentityOutput,
 };
@@ -6,26 +11,51 @@ use aws_sdk_sts::operation::get_caller_identity::{
 #[allow(unused_imports)]
 use mockall::automock;
 
-use crate::context::FuburaContext;
-use crate::types::CallerIdentity;
+use crate::context::{BoxFuture, FuburaContext};
+use crate::error::FuburaError;
+use crate::telemetry::traced;
+use crate::types::{ArnBuilder, CallerIdentity};
 
-pub struct StsImpl {
-    inner: sts::Client,
+/// STS operations used by fubura.
+///
+/// Implement this to inject your own client into `FuburaContext::new`.
+pub trait Sts: Send + Sync {
+    fn get_caller_identity(
+        &self,
+    ) -> BoxFuture<'_, Result<GetCallerIdentityOutput, sts::error::SdkError<GetCallerIdentityError>>>;
 }
 
+// Implements `Sts` by delegating to inherent methods of the given type,
+// which is shared by the aws-sdk based client and its mock.
+macro_rules! impl_sts {
+    ($impl:ty) => {
+        impl Sts for $impl {
+            fn get_caller_identity(
+                &self,
+            ) -> BoxFuture<
+                '_,
+                Result<GetCallerIdentityOutput, sts::error::SdkError<GetCallerIdentityError>>,
+            > {
+                Box::pin(<$impl>::get_caller_identity(self))
+            }
+        }
+    };
+}
+
+impl_sts!(StsImpl);
 #[cfg(test)]
-pub use MockStsImpl as Sts;
-#[cfg(not(test))]
-pub use StsImpl as Sts;
+impl_sts!(MockStsImpl);
+
+pub struct StsImpl {
+    inner: sts::Client,
+}
 
 #[cfg_attr(test, automock)]
 impl StsImpl {
-    #[allow(dead_code)]
     pub fn new(inner: sts::Client) -> Self {
         Self { inner }
     }
 
-    #[allow(dead_code)]
     pub async fn get_caller_identity(
         &self,
     ) -> Result<GetCallerIdentityOutput, sts::error::SdkError<GetCallerIdentityError>> {
@@ -33,22 +63,38 @@ impl StsImpl {
     }
 }
 
-async fn get_caller_identity(client: &Sts) -> CallerIdentity {
-    let res = client.get_caller_identity().await;
+// Any failure here is reported as credential error, since it's the first call to AWS.
+pub async fn get_caller_identity(client: &dyn Sts) -> Result<CallerIdentity> {
+    let res = traced("sts:GetCallerIdentity", client.get_caller_identity()).await;
 
     match res {
-        Ok(output) => CallerIdentity::from(output),
-        Err(err) => {
-            eprintln!("failed to get caller identity: {}", err);
-            std::process::exit(1);
-        }
+        Ok(output) => Ok(CallerIdentity::from(output)),
+        Err(err) => Err(FuburaError::Credential(format!(
+            "failed to get caller identity: {}",
+            DisplayErrorContext(&err)
+        ))
+        .into()),
     }
 }
 
-pub async fn build_state_arn_prefix(context: &FuburaContext) -> String {
-    let caller_identity = get_caller_identity(&context.sts_client).await;
-    let account_id = caller_identity.account;
+pub async fn build_arn_builder(context: &FuburaContext) -> Result<ArnBuilder> {
+    let caller_identity = context.caller_identity().await?;
     let aws_region = &context.aws_region;
+    let partition = caller_identity.partition(aws_region);
+
+    Ok(ArnBuilder::new(
+        partition,
+        aws_region,
+        &caller_identity.account,
+    ))
+}
+
+// Label to identify AWS account and region of context, like "123456789012/us-west-2"
+pub async fn build_target_label(context: &FuburaContext) -> Result<String> {
+    let caller_identity = context.caller_identity().await?;
 
-    format!("arn:aws:states:{}:{}:stateMachine:", aws_region, account_id)
+    Ok(format!(
+        "{}/{}",
+        caller_identity.account, context.aws_region
+    ))
 }
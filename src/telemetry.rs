@@ -0,0 +1,434 @@
+use std::fmt;
+use std::future::Future;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aws_sdk_sfn::primitives::{DateTime, DateTimeFormat};
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::interceptors::context::FinalizerInterceptorContextRef;
+use aws_smithy_runtime_api::client::retries::RequestAttempts;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+use serde_json::{Map, Value, json};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Instrument, Span, Subscriber, field, info_span};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::types::{SsConfig, StandaloneScheduleConfig};
+
+tokio::task_local! {
+    // span of AWS call in progress, which `TraceInterceptor` records response into
+    static AWS_CALL_SPAN: Span;
+}
+
+/// Span wrapping diff or apply of a SsConfig.
+pub fn ss_config_span(ss_config: &SsConfig) -> Span {
+    let span = info_span!(
+        "ss_config",
+        state_name = %ss_config.state.name,
+        schedule = field::Empty,
+        rule = field::Empty,
+    );
+    let schedule_names: Vec<String> = ss_config
+        .schedule_configs()
+        .iter()
+        .map(|schedule_config| schedule_config.schedule.schedule_name_with_group())
+        .collect();
+    if !schedule_names.is_empty() {
+        span.record("schedule", schedule_names.join(","));
+    }
+    let rule_names: Vec<String> = ss_config
+        .rules
+        .iter()
+        .map(|rule_config| rule_config.rule.rule_name_with_bus())
+        .collect();
+    if !rule_names.is_empty() {
+        span.record("rule", rule_names.join(","));
+    }
+
+    span
+}
+
+pub fn standalone_schedule_span(schedule_config: &StandaloneScheduleConfig) -> Span {
+    info_span!(
+        "ss_config",
+        schedule = %schedule_config.schedule_name_with_group(),
+    )
+}
+
+/// Run AWS call in its own span, named by operation like "sfn:DescribeStateMachine".
+/// Request id and retry count are recorded by `TraceInterceptor`.
+pub async fn traced<F: Future>(operation: &'static str, call: F) -> F::Output {
+    let span = info_span!(
+        "aws_call",
+        operation,
+        request_id = field::Empty,
+        retry_count = field::Empty,
+    );
+
+    AWS_CALL_SPAN
+        .scope(span.clone(), call.instrument(span))
+        .await
+}
+
+/// Records request id and retry count of AWS call into the span made by `traced`.
+///
+/// SDK runs interceptors in its own spans, so the span is passed through task local.
+#[derive(Debug)]
+pub struct TraceInterceptor;
+
+impl Intercept for TraceInterceptor {
+    fn name(&self) -> &'static str {
+        "FuburaTraceInterceptor"
+    }
+
+    fn read_after_execution(
+        &self,
+        context: &FinalizerInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let _ = AWS_CALL_SPAN.try_with(|span| {
+            if let Some(attempts) = cfg.load::<RequestAttempts>() {
+                span.record("retry_count", attempts.attempts().saturating_sub(1));
+            }
+
+            let request_id = context.response().and_then(|response| {
+                let headers = response.headers();
+                headers
+                    .get("x-amzn-requestid")
+                    .or_else(|| headers.get("x-amz-request-id"))
+            });
+            if let Some(request_id) = request_id {
+                span.record("request_id", request_id);
+            }
+        });
+
+        Ok(())
+    }
+}
+
+// Collects fields of span or event into JSON object.
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+}
+
+struct SpanData {
+    fields: Map<String, Value>,
+    start: SystemTime,
+    trace_id: u128,
+    span_id: u64,
+}
+
+type Output = Mutex<Box<dyn Write + Send>>;
+
+/// Layer writing logs as JSON lines, and closed spans as OTLP JSON lines.
+///
+/// Each OTLP line is an `ExportTraceServiceRequest` with a span, which OpenTelemetry
+/// Collector can read with its otlpjsonfile receiver.
+pub struct TelemetryLayer {
+    log_output: Option<Output>,
+    trace_output: Option<Output>,
+}
+
+impl TelemetryLayer {
+    pub fn new(
+        log_output: Option<Box<dyn Write + Send>>,
+        trace_output: Option<Box<dyn Write + Send>>,
+    ) -> Self {
+        Self {
+            log_output: log_output.map(Mutex::new),
+            trace_output: trace_output.map(Mutex::new),
+        }
+    }
+}
+
+fn write_line(output: &Output, line: &Value) {
+    let mut output = output.lock().unwrap();
+    // Logging should not break command, so failures are ignored.
+    let _ = writeln!(output, "{}", line);
+    let _ = output.flush();
+}
+
+fn timestamp(time: SystemTime) -> String {
+    DateTime::from(time).fmt(DateTimeFormat::DateTime).unwrap()
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn otlp_attributes(fields: &Map<String, Value>) -> Vec<Value> {
+    fields
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::Bool(b) => json!({ "boolValue": b }),
+                Value::Number(n) if n.is_f64() => json!({ "doubleValue": n }),
+                // 64 bit integers are strings in OTLP JSON
+                Value::Number(n) => json!({ "intValue": n.to_string() }),
+                Value::String(s) => json!({ "stringValue": s }),
+                other => json!({ "stringValue": other.to_string() }),
+            };
+            json!({ "key": key, "value": value })
+        })
+        .collect()
+}
+
+impl<S> Layer<S> for TelemetryLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+
+        let trace_id = span
+            .parent()
+            .and_then(|parent| {
+                parent
+                    .extensions()
+                    .get::<SpanData>()
+                    .map(|data| data.trace_id)
+            })
+            .unwrap_or_else(|| fastrand::u128(1..));
+
+        span.extensions_mut().insert(SpanData {
+            fields,
+            start: SystemTime::now(),
+            trace_id,
+            span_id: fastrand::u64(1..),
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        if let Some(data) = span.extensions_mut().get_mut::<SpanData>() {
+            values.record(&mut JsonVisitor(&mut data.fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(log_output) = &self.log_output else {
+            return;
+        };
+
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+        let message = fields.remove("message").unwrap_or(Value::Null);
+
+        let spans: Vec<Value> = ctx
+            .event_scope(event)
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+            .map(|span| {
+                let mut object = Map::new();
+                object.insert("name".to_string(), json!(span.name()));
+                if let Some(data) = span.extensions().get::<SpanData>() {
+                    object.extend(data.fields.clone());
+                }
+                Value::Object(object)
+            })
+            .collect();
+
+        let metadata = event.metadata();
+        write_line(
+            log_output,
+            &json!({
+                "timestamp": timestamp(SystemTime::now()),
+                "level": metadata.level().as_str(),
+                "target": metadata.target(),
+                "message": message,
+                "fields": fields,
+                "spans": spans,
+            }),
+        );
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(data) = extensions.get::<SpanData>() else {
+            return;
+        };
+
+        let end = SystemTime::now();
+        let latency = end.duration_since(data.start).unwrap_or_default();
+
+        if let Some(log_output) = &self.log_output {
+            write_line(
+                log_output,
+                &json!({
+                    "timestamp": timestamp(end),
+                    "level": span.metadata().level().as_str(),
+                    "target": span.metadata().target(),
+                    "message": "span closed",
+                    "span": span.name(),
+                    "fields": data.fields,
+                    "latency_ms": latency.as_secs_f64() * 1000.0,
+                }),
+            );
+        }
+
+        if let Some(trace_output) = &self.trace_output {
+            let parent_span_id = span
+                .parent()
+                .and_then(|parent| {
+                    parent
+                        .extensions()
+                        .get::<SpanData>()
+                        .map(|data| format!("{:016x}", data.span_id))
+                })
+                .unwrap_or_default();
+            // SPAN_KIND_CLIENT for AWS calls, SPAN_KIND_INTERNAL for others
+            let kind = if span.name() == "aws_call" { 3 } else { 1 };
+
+            write_line(
+                trace_output,
+                &json!({
+                    "resourceSpans": [{
+                        "resource": {
+                            "attributes": [
+                                { "key": "service.name", "value": { "stringValue": "fubura" } },
+                                { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+                            ],
+                        },
+                        "scopeSpans": [{
+                            "scope": { "name": span.metadata().target() },
+                            "spans": [{
+                                "traceId": format!("{:032x}", data.trace_id),
+                                "spanId": format!("{:016x}", data.span_id),
+                                "parentSpanId": parent_span_id,
+                                "name": span.name(),
+                                "kind": kind,
+                                "startTimeUnixNano": unix_nanos(data.start),
+                                "endTimeUnixNano": unix_nanos(end),
+                                "attributes": otlp_attributes(&data.fields),
+                            }],
+                        }],
+                    }],
+                }),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use tracing::info;
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_telemetry_layer() {
+        let logs = SharedBuffer::default();
+        let traces = SharedBuffer::default();
+        let layer =
+            TelemetryLayer::new(Some(Box::new(logs.clone())), Some(Box::new(traces.clone())));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let ss_config = SsConfig::test_default();
+            let _ss_config = ss_config_span(&ss_config).entered();
+            let _aws_call = info_span!(
+                "aws_call",
+                operation = "sfn:DescribeStateMachine",
+                retry_count = 2
+            )
+            .entered();
+
+            info!(arn = "arn", "Describing state machine");
+        });
+
+        let logs = logs.lines();
+        assert_eq!(json!("Describing state machine"), logs[0]["message"]);
+        assert_eq!(json!({ "arn": "arn" }), logs[0]["fields"]);
+        assert_eq!(json!("HelloWorld"), logs[0]["spans"][0]["state_name"]);
+        assert_eq!(
+            json!("sfn:DescribeStateMachine"),
+            logs[0]["spans"][1]["operation"]
+        );
+        assert_eq!(json!("aws_call"), logs[1]["span"]);
+        assert!(logs[1]["latency_ms"].is_f64());
+
+        let traces = traces.lines();
+        let aws_call = &traces[0]["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        let ss_config = &traces[1]["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(json!("aws_call"), aws_call["name"]);
+        assert_eq!(ss_config["traceId"], aws_call["traceId"]);
+        assert_eq!(ss_config["spanId"], aws_call["parentSpanId"]);
+        assert!(
+            aws_call["attributes"]
+                .as_array()
+                .unwrap()
+                .contains(&json!({ "key": "retry_count", "value": { "intValue": "2" } }))
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+
+use super::DEFAULT_EVENT_BUS_NAME;
+
+// AWS partition, which is the first component of ARN like "arn:aws-cn:..."
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Partition {
+    Aws,
+    AwsCn,
+    AwsUsGov,
+    Other(String),
+}
+
+impl Partition {
+    pub fn from_arn(arn: &str) -> Option<Self> {
+        let mut components = arn.split(':');
+
+        if components.next() != Some("arn") {
+            return None;
+        }
+
+        components.next().map(Partition::from)
+    }
+
+    pub fn from_region(region: &str) -> Self {
+        if region.starts_with("cn-") {
+            Partition::AwsCn
+        } else if region.starts_with("us-gov-") {
+            Partition::AwsUsGov
+        } else {
+            Partition::Aws
+        }
+    }
+}
+
+impl From<&str> for Partition {
+    fn from(value: &str) -> Self {
+        match value {
+            "aws" => Partition::Aws,
+            "aws-cn" => Partition::AwsCn,
+            "aws-us-gov" => Partition::AwsUsGov,
+            other => Partition::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Partition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Partition::Aws => write!(f, "aws"),
+            Partition::AwsCn => write!(f, "aws-cn"),
+            Partition::AwsUsGov => write!(f, "aws-us-gov"),
+            Partition::Other(partition) => write!(f, "{}", partition),
+        }
+    }
+}
+
+// Builds ARNs of resources managed by fubura in a partition, region and account.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ArnBuilder {
+    pub partition: Partition,
+    pub region: String,
+    pub account: String,
+}
+
+impl ArnBuilder {
+    pub fn new(partition: Partition, region: &str, account: &str) -> Self {
+        Self {
+            partition,
+            region: region.to_string(),
+            account: account.to_string(),
+        }
+    }
+
+    fn build(&self, service: &str, resource: &str) -> String {
+        format!(
+            "arn:{}:{}:{}:{}:{}",
+            self.partition, service, self.region, self.account, resource
+        )
+    }
+
+    pub fn state_machine(&self, state_name: &str) -> String {
+        self.build("states", &format!("stateMachine:{}", state_name))
+    }
+
+    pub fn state_machine_version(&self, state_name: &str, version: u32) -> String {
+        format!("{}:{}", self.state_machine(state_name), version)
+    }
+
+    pub fn state_machine_alias(&self, state_name: &str, alias_name: &str) -> String {
+        format!("{}:{}", self.state_machine(state_name), alias_name)
+    }
+
+    pub fn schedule(&self, group_name: &str, schedule_name: &str) -> String {
+        self.build(
+            "scheduler",
+            &format!("schedule/{}/{}", group_name, schedule_name),
+        )
+    }
+
+    pub fn schedule_group(&self, group_name: &str) -> String {
+        self.build("scheduler", &format!("schedule-group/{}", group_name))
+    }
+
+    // Without ":*" suffix, which state machine logging configuration needs
+    pub fn log_group(&self, log_group_name: &str) -> String {
+        self.build("logs", &format!("log-group:{}", log_group_name))
+    }
+
+    pub fn event_bus(&self, event_bus_name: &str) -> String {
+        self.build("events", &format!("event-bus/{}", event_bus_name))
+    }
+
+    // Resources below are referred by name from state machine definitions
+
+    pub fn lambda_function(&self, function_name: &str) -> String {
+        self.build("lambda", &format!("function:{}", function_name))
+    }
+
+    // "family" or "family:revision"
+    pub fn ecs_task_definition(&self, task_definition: &str) -> String {
+        self.build("ecs", &format!("task-definition/{}", task_definition))
+    }
+
+    pub fn dynamodb_table(&self, table_name: &str) -> String {
+        self.build("dynamodb", &format!("table/{}", table_name))
+    }
+
+    pub fn batch_job_queue(&self, job_queue_name: &str) -> String {
+        self.build("batch", &format!("job-queue/{}", job_queue_name))
+    }
+
+    pub fn batch_job_definition(&self, job_definition: &str) -> String {
+        self.build("batch", &format!("job-definition/{}", job_definition))
+    }
+
+    pub fn glue_job(&self, job_name: &str) -> String {
+        self.build("glue", &format!("job/{}", job_name))
+    }
+
+    pub fn kms_key(&self, key_id: &str) -> String {
+        self.build("kms", &format!("key/{}", key_id))
+    }
+
+    // Rules on default event bus don't have bus name in their ARN.
+    pub fn rule(&self, event_bus_name: &str, rule_name: &str) -> String {
+        if event_bus_name == DEFAULT_EVENT_BUS_NAME {
+            self.build("events", &format!("rule/{}", rule_name))
+        } else {
+            self.build("events", &format!("rule/{}/{}", event_bus_name, rule_name))
+        }
+    }
+}
+
+// Version number of state machine version ARN like "arn:aws:states:...:stateMachine:Name:3".
+// Unlike state machine ARN, which has 7 components, version ARN has 8.
+pub fn state_machine_version_number(version_arn: &str) -> Option<u32> {
+    let components: Vec<&str> = version_arn.split(':').collect();
+    if components.len() != 8 || components[5] != "stateMachine" {
+        return None;
+    }
+
+    components[7].parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_partition_from_arn() {
+        assert_eq!(
+            Some(Partition::AwsCn),
+            Partition::from_arn("arn:aws-cn:sts::123456789012:assumed-role/fubura/session")
+        );
+        assert_eq!(None, Partition::from_arn("not-an-arn"));
+    }
+
+    #[test]
+    fn test_partition_from_region() {
+        assert_eq!(Partition::Aws, Partition::from_region("us-west-2"));
+        assert_eq!(Partition::AwsCn, Partition::from_region("cn-north-1"));
+        assert_eq!(Partition::AwsUsGov, Partition::from_region("us-gov-west-1"));
+    }
+
+    #[test]
+    fn test_arn_builder() {
+        let arn_builder = ArnBuilder::new(Partition::AwsUsGov, "us-gov-west-1", "123456789012");
+
+        assert_eq!(
+            "arn:aws-us-gov:states:us-gov-west-1:123456789012:stateMachine:HelloWorld",
+            arn_builder.state_machine("HelloWorld")
+        );
+        assert_eq!(
+            "arn:aws-us-gov:states:us-gov-west-1:123456789012:stateMachine:HelloWorld:3",
+            arn_builder.state_machine_version("HelloWorld", 3)
+        );
+        assert_eq!(
+            "arn:aws-us-gov:scheduler:us-gov-west-1:123456789012:schedule/default/HelloWorld",
+            arn_builder.schedule("default", "HelloWorld")
+        );
+        assert_eq!(
+            "arn:aws-us-gov:scheduler:us-gov-west-1:123456789012:schedule-group/batch",
+            arn_builder.schedule_group("batch")
+        );
+        assert_eq!(
+            "arn:aws-us-gov:logs:us-gov-west-1:123456789012:log-group:HelloWorldLogGroup",
+            arn_builder.log_group("HelloWorldLogGroup")
+        );
+        assert_eq!(
+            "arn:aws-us-gov:events:us-gov-west-1:123456789012:rule/HelloWorldOnUpload",
+            arn_builder.rule("default", "HelloWorldOnUpload")
+        );
+        assert_eq!(
+            "arn:aws-us-gov:events:us-gov-west-1:123456789012:rule/orders/HelloWorldOnUpload",
+            arn_builder.rule("orders", "HelloWorldOnUpload")
+        );
+        assert_eq!(
+            "arn:aws-us-gov:lambda:us-gov-west-1:123456789012:function:HelloWorld",
+            arn_builder.lambda_function("HelloWorld")
+        );
+        assert_eq!(
+            "arn:aws-us-gov:ecs:us-gov-west-1:123456789012:task-definition/HelloWorld:*",
+            arn_builder.ecs_task_definition("HelloWorld:*")
+        );
+    }
+
+    #[test]
+    fn test_state_machine_version_number() {
+        assert_eq!(
+            Some(3),
+            state_machine_version_number(
+                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld:3"
+            )
+        );
+        // state machine named like a number is not a version
+        assert_eq!(
+            None,
+            state_machine_version_number("arn:aws:states:us-west-2:123456789012:stateMachine:42")
+        );
+        // neither is alias
+        assert_eq!(
+            None,
+            state_machine_version_number(
+                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld:live"
+            )
+        );
+    }
+}
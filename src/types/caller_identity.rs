@@ -1,11 +1,26 @@
+use super::Partition;
+
+#[derive(Debug, Clone)]
 pub struct CallerIdentity {
     pub account: String,
+    pub arn: Option<String>,
+}
+
+impl CallerIdentity {
+    // Partition is taken from caller's arn, or guessed from region if arn is not available.
+    pub fn partition(&self, region: &str) -> Partition {
+        self.arn
+            .as_deref()
+            .and_then(Partition::from_arn)
+            .unwrap_or_else(|| Partition::from_region(region))
+    }
 }
 
 impl From<aws_sdk_sts::operation::get_caller_identity::GetCallerIdentityOutput> for CallerIdentity {
     fn from(output: aws_sdk_sts::operation::get_caller_identity::GetCallerIdentityOutput) -> Self {
         Self {
             account: output.account.unwrap(),
+            arn: output.arn,
         }
     }
 }
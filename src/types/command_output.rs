@@ -0,0 +1,100 @@
+use serde::Serialize;
+
+use super::{DiffResult, RolePolicy, StateMachineVersion};
+
+// Bump on breaking change of the document, e.g. removing or renaming fields.
+pub const OUTPUT_SCHEMA_VERSION: u32 = 1;
+
+/// Document written to stdout on `--output json`.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct CommandOutput {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub result: CommandResult,
+}
+
+impl CommandOutput {
+    pub fn new(result: CommandResult) -> Self {
+        CommandOutput {
+            schema_version: OUTPUT_SCHEMA_VERSION,
+            result,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "command", content = "result", rename_all = "snake_case")]
+pub enum CommandResult {
+    Plan(DiffResult),
+    // diff applied, states skipped on interactive approval are listed in `skipped`
+    Apply(DiffResult),
+    Import(ImportResult),
+    Versions(VersionsResult),
+    Rollback(RollbackResult),
+    IamPolicy(IamPolicyResult),
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct ImportResult {
+    pub config_path: String,
+    pub state_name: String,
+    // "group/name" of imported schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_name: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct VersionsResult {
+    pub state_name: String,
+    // from the latest
+    pub versions: Vec<StateMachineVersion>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct RollbackResult {
+    pub state_name: String,
+    // set if the alias is pointed back, instead of updating state machine
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    // version the state machine or alias was on, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_version: Option<u32>,
+    pub to_version: u32,
+    // false if there is nothing to roll back
+    pub rolled_back: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct IamPolicyResult {
+    // per state machine config, or per role if merged
+    pub policies: Vec<RolePolicy>,
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_serialize_command_output() {
+        let output = CommandOutput::new(CommandResult::Import(ImportResult {
+            config_path: "fubura.jsonnet".to_string(),
+            state_name: "HelloWorld".to_string(),
+            schedule_name: Some("default/HelloWorld".to_string()),
+        }));
+
+        similar_asserts::assert_eq!(
+            json!({
+                "schema_version": 1,
+                "command": "import",
+                "result": {
+                    "config_path": "fubura.jsonnet",
+                    "state_name": "HelloWorld",
+                    "schedule_name": "default/HelloWorld",
+                },
+            }),
+            serde_json::to_value(&output).unwrap()
+        );
+    }
+}
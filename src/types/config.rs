@@ -1,28 +1,213 @@
 use std::collections::HashSet;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::{cli::StrKeyVal, fast_exit, jsonnet_evaluator};
+use crate::error::FuburaError;
+use crate::{cli::StrKeyVal, jsonnet_evaluator};
 
-use super::SsConfig;
+use super::{DeployTarget, ScheduleGroupConfig, SsConfig, StandaloneScheduleConfig};
 
-#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Default)]
-#[serde(rename_all = "camelCase")]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Default, Clone)]
+#[serde(from = "RawConfig", into = "RawConfig")]
 pub struct Config {
     pub ss_configs: Vec<SsConfig>,
+    // entries of ss configs which have only schedule
+    pub schedules: Vec<StandaloneScheduleConfig>,
+    pub schedule_groups: Vec<ScheduleGroupConfig>,
+}
+
+// Entry of ss configs, which is a standalone schedule when it has schedule but no state
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+enum ConfigEntry {
+    Ss(Box<SsConfig>),
+    Schedule(Box<StandaloneScheduleConfig>),
+}
+
+// Dispatch by keys instead of untagged enum, to keep error messages of each entry type
+impl<'de> Deserialize<'de> for ConfigEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let entry = if value.get("state").is_none() && value.get("schedule").is_some() {
+            serde_json::from_value(value).map(ConfigEntry::Schedule)
+        } else {
+            serde_json::from_value(value).map(ConfigEntry::Ss)
+        };
+
+        entry.map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RawConfig {
+    #[serde(default)]
+    ss_configs: Vec<ConfigEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    schedule_groups: Vec<ScheduleGroupConfig>,
+}
+
+impl From<RawConfig> for Config {
+    fn from(raw: RawConfig) -> Self {
+        let mut config = Config {
+            schedule_groups: raw.schedule_groups,
+            ..Default::default()
+        };
+        for entry in raw.ss_configs {
+            match entry {
+                ConfigEntry::Ss(ss_config) => config.ss_configs.push(*ss_config),
+                ConfigEntry::Schedule(schedule) => config.schedules.push(*schedule),
+            }
+        }
+
+        config
+    }
+}
+
+// Standalone schedules are written after ss configs
+impl From<Config> for RawConfig {
+    fn from(config: Config) -> Self {
+        let ss_configs = config
+            .ss_configs
+            .into_iter()
+            .map(|ss_config| ConfigEntry::Ss(Box::new(ss_config)))
+            .chain(
+                config
+                    .schedules
+                    .into_iter()
+                    .map(|schedule| ConfigEntry::Schedule(Box::new(schedule))),
+            )
+            .collect();
+
+        RawConfig {
+            ss_configs,
+            schedule_groups: config.schedule_groups,
+        }
+    }
+}
+
+// Resources of config deployed to the same deploy target
+#[derive(Debug)]
+pub struct DeployTargetResources<'a> {
+    pub deploy_target: Option<DeployTarget>,
+    pub ss_configs: Vec<&'a SsConfig>,
+    pub schedules: Vec<&'a StandaloneScheduleConfig>,
+    pub schedule_groups: Vec<&'a ScheduleGroupConfig>,
+}
+
+impl<'a> DeployTargetResources<'a> {
+    fn new(deploy_target: Option<DeployTarget>) -> Self {
+        Self {
+            deploy_target,
+            ss_configs: vec![],
+            schedules: vec![],
+            schedule_groups: vec![],
+        }
+    }
 }
 
 impl Config {
-    pub fn load_from_path(config: &str, ext_str: &[StrKeyVal]) -> Config {
-        let config_value = jsonnet_evaluator::eval(config, ext_str).unwrap_or_else(|e| {
-            fast_exit!("failed to evaluate jsonnet: {}", e);
-        });
+    pub fn load_from_path(config: &str, ext_str: &[StrKeyVal]) -> Result<Config, FuburaError> {
+        let config_value = jsonnet_evaluator::eval(config, ext_str)
+            .map_err(|e| FuburaError::Config(format!("failed to evaluate jsonnet: {}", e)))?;
 
-        let ss_configs: Vec<SsConfig> = serde_json::from_value(config_value).unwrap_or_else(|e| {
-            fast_exit!("failed to parse config file: {}", e);
-        });
+        Self::from_value(config_value)
+    }
+
+    // Config is an array of ss configs, or an object which also has schedule groups.
+    pub fn from_value(config_value: serde_json::Value) -> Result<Config, FuburaError> {
+        let config = if config_value.is_array() {
+            serde_json::from_value(config_value).map(|ss_configs| {
+                Config::from(RawConfig {
+                    ss_configs,
+                    schedule_groups: vec![],
+                })
+            })
+        } else {
+            serde_json::from_value(config_value)
+        };
+
+        let config = config
+            .map_err(|e| FuburaError::Config(format!("failed to parse config file: {}", e)))?;
+        config.reject_unknown_values()?;
+
+        Ok(config)
+    }
+
+    // Enums keep values fubura doesn't know when read from AWS, but config should use known ones.
+    fn reject_unknown_values(&self) -> Result<(), FuburaError> {
+        let mut unknown_values = vec![];
+
+        for ss_config in self.ss_configs.iter() {
+            for value in ss_config.state.unknown_values() {
+                unknown_values.push(format!(
+                    "{} of state machine '{}'",
+                    value, ss_config.state.name
+                ));
+            }
+            for schedule_config in ss_config.schedule_configs() {
+                for value in schedule_config.schedule.unknown_values() {
+                    unknown_values.push(format!(
+                        "{} of schedule '{}'",
+                        value,
+                        schedule_config.schedule.schedule_name_with_group()
+                    ));
+                }
+            }
+            for rule_config in ss_config.rules.iter() {
+                for value in rule_config.rule.unknown_values() {
+                    unknown_values.push(format!(
+                        "{} of rule '{}'",
+                        value,
+                        rule_config.rule.rule_name_with_bus()
+                    ));
+                }
+            }
+        }
+        for schedule_config in self.schedules.iter() {
+            for value in schedule_config.schedule.unknown_values() {
+                unknown_values.push(format!(
+                    "{} of schedule '{}'",
+                    value,
+                    schedule_config.schedule.schedule_name_with_group()
+                ));
+            }
+        }
+
+        if unknown_values.is_empty() {
+            return Ok(());
+        }
 
-        Config { ss_configs }
+        Err(FuburaError::Config(format!(
+            "unknown values in config: {}",
+            unknown_values.join(", ")
+        )))
+    }
+
+    // Content written to config file, which keeps array form while no schedule group is configured.
+    pub fn to_json_string_pretty(&self) -> String {
+        let raw = RawConfig::from(self.clone());
+        if raw.schedule_groups.is_empty() {
+            serde_json::to_string_pretty(&raw.ss_configs).unwrap()
+        } else {
+            serde_json::to_string_pretty(&raw).unwrap()
+        }
+    }
+
+    // State names and "group/name" of standalone schedules are both accepted as targets,
+    // which never conflict as state name can't contain '/'.
+    pub fn target_schedules(
+        &self,
+        targets: &Option<Vec<String>>,
+    ) -> Vec<&StandaloneScheduleConfig> {
+        if let Some(targets) = &targets {
+            self.schedules
+                .iter()
+                .filter(|schedule| targets.contains(&schedule.schedule_name_with_group()))
+                .collect()
+        } else {
+            self.schedules.iter().collect()
+        }
     }
 
     pub fn target_ss_configs(&self, targets: &Option<Vec<String>>) -> Vec<&SsConfig> {
@@ -37,4 +222,299 @@ impl Config {
             self.ss_configs.iter().collect::<Vec<_>>()
         }
     }
+
+    // All schedule groups without targets, or only groups used by schedules of target ss configs.
+    pub fn target_schedule_groups(
+        &self,
+        targets: &Option<Vec<String>>,
+    ) -> Vec<&ScheduleGroupConfig> {
+        if targets.is_none() {
+            return self.schedule_groups.iter().collect();
+        }
+
+        let used_groups: HashSet<(&Option<DeployTarget>, String)> = self
+            .target_ss_configs(targets)
+            .into_iter()
+            .flat_map(|ss_config| {
+                ss_config
+                    .schedule_configs()
+                    .into_iter()
+                    .map(|schedule_config| {
+                        (
+                            &ss_config.deploy_target,
+                            schedule_config.schedule.group_name,
+                        )
+                    })
+            })
+            .chain(self.target_schedules(targets).into_iter().map(|schedule| {
+                (
+                    &schedule.deploy_target,
+                    schedule.schedule.group_name.clone(),
+                )
+            }))
+            .collect();
+
+        self.schedule_groups
+            .iter()
+            .filter(|group_config| {
+                used_groups
+                    .contains(&(&group_config.deploy_target, group_config.group.name.clone()))
+            })
+            .collect()
+    }
+
+    // Group target resources by its deploy target, keeping the order of first appearance.
+    pub fn target_resources_by_deploy_target(
+        &self,
+        targets: &Option<Vec<String>>,
+    ) -> Vec<DeployTargetResources<'_>> {
+        let mut groups: Vec<DeployTargetResources> = vec![];
+
+        fn find_or_insert<'a, 'b>(
+            groups: &'b mut Vec<DeployTargetResources<'a>>,
+            deploy_target: &Option<DeployTarget>,
+        ) -> &'b mut DeployTargetResources<'a> {
+            let index = match groups
+                .iter()
+                .position(|resources| resources.deploy_target == *deploy_target)
+            {
+                Some(index) => index,
+                None => {
+                    groups.push(DeployTargetResources::new(deploy_target.clone()));
+                    groups.len() - 1
+                }
+            };
+
+            &mut groups[index]
+        }
+
+        for ss_config in self.target_ss_configs(targets) {
+            find_or_insert(&mut groups, &ss_config.deploy_target)
+                .ss_configs
+                .push(ss_config);
+        }
+        for schedule in self.target_schedules(targets) {
+            find_or_insert(&mut groups, &schedule.deploy_target)
+                .schedules
+                .push(schedule);
+        }
+        for group_config in self.target_schedule_groups(targets) {
+            find_or_insert(&mut groups, &group_config.deploy_target)
+                .schedule_groups
+                .push(group_config);
+        }
+
+        groups
+    }
+
+    pub fn deploy_targets(&self) -> Vec<DeployTarget> {
+        self.target_resources_by_deploy_target(&None)
+            .into_iter()
+            .filter_map(|resources| resources.deploy_target)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::types::{Schedule, ScheduleGroup, StateMachine};
+
+    fn ss_config(name: &str, deploy_target: Option<DeployTarget>) -> SsConfig {
+        let mut state = StateMachine::test_default();
+        state.name = name.to_string();
+
+        SsConfig {
+            state,
+            deploy_target,
+            ..SsConfig::test_default()
+        }
+    }
+
+    fn schedule_group(name: &str, deploy_target: Option<DeployTarget>) -> ScheduleGroupConfig {
+        ScheduleGroupConfig {
+            group: ScheduleGroup {
+                name: name.to_string(),
+                tags: vec![],
+            },
+            delete: false,
+            deploy_target,
+        }
+    }
+
+    fn resource_names(
+        resources: Vec<DeployTargetResources>,
+    ) -> Vec<(Option<DeployTarget>, Vec<String>, Vec<String>)> {
+        resources
+            .into_iter()
+            .map(|resources| {
+                let state_names = resources
+                    .ss_configs
+                    .iter()
+                    .map(|ss_config| ss_config.state.name.clone())
+                    .collect();
+                let group_names = resources
+                    .schedule_groups
+                    .iter()
+                    .map(|group_config| group_config.group.name.clone())
+                    .collect();
+                (resources.deploy_target, state_names, group_names)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_target_resources_by_deploy_target() {
+        let tokyo = DeployTarget {
+            region: "ap-northeast-1".to_string(),
+            role_arn: Some("arn:aws:iam::210987654321:role/fubura".to_string()),
+            external_id: None,
+        };
+        let osaka = DeployTarget {
+            region: "ap-northeast-3".to_string(),
+            role_arn: None,
+            external_id: None,
+        };
+        let config = Config {
+            ss_configs: vec![
+                ss_config("First", None),
+                ss_config("Second", Some(tokyo.clone())),
+                ss_config("Third", None),
+            ],
+            schedules: vec![],
+            schedule_groups: vec![
+                schedule_group("batch", Some(osaka.clone())),
+                schedule_group("batch", None),
+            ],
+        };
+
+        let actual = resource_names(config.target_resources_by_deploy_target(&None));
+
+        let expected = vec![
+            (
+                None,
+                vec!["First".to_string(), "Third".to_string()],
+                vec!["batch".to_string()],
+            ),
+            (Some(tokyo.clone()), vec!["Second".to_string()], vec![]),
+            (Some(osaka.clone()), vec![], vec!["batch".to_string()]),
+        ];
+
+        similar_asserts::assert_eq!(expected, actual);
+        similar_asserts::assert_eq!(vec![tokyo, osaka], config.deploy_targets());
+    }
+
+    #[test]
+    fn test_target_schedule_groups_used_by_targets() {
+        let mut first = ss_config("First", None);
+        let mut schedule = Schedule::test_default();
+        schedule.group_name = "batch".to_string();
+        first.schedule = Some(schedule);
+
+        let config = Config {
+            ss_configs: vec![first, ss_config("Second", None)],
+            schedules: vec![],
+            schedule_groups: vec![schedule_group("batch", None), schedule_group("etl", None)],
+        };
+
+        let actual = resource_names(
+            config.target_resources_by_deploy_target(&Some(vec!["First".to_string()])),
+        );
+        let expected = vec![(None, vec!["First".to_string()], vec!["batch".to_string()])];
+        similar_asserts::assert_eq!(expected, actual);
+
+        let actual = resource_names(
+            config.target_resources_by_deploy_target(&Some(vec!["Second".to_string()])),
+        );
+        let expected = vec![(None, vec!["Second".to_string()], vec![])];
+        similar_asserts::assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_from_value_accepts_array_and_object() {
+        let ss_config_value = serde_json::to_value(ss_config("First", None)).unwrap();
+
+        let actual = Config::from_value(serde_json::json!([ss_config_value])).unwrap();
+        similar_asserts::assert_eq!(vec![ss_config("First", None)], actual.ss_configs);
+        assert!(actual.schedule_groups.is_empty());
+        similar_asserts::assert_eq!(
+            serde_json::json!([ss_config_value]),
+            serde_json::from_str::<serde_json::Value>(&actual.to_json_string_pretty()).unwrap()
+        );
+
+        let value = serde_json::json!({
+            "ssConfigs": [ss_config_value],
+            "scheduleGroups": [{ "group": { "name": "batch" } }],
+        });
+        let actual = Config::from_value(value).unwrap();
+        similar_asserts::assert_eq!(vec![schedule_group("batch", None)], actual.schedule_groups);
+        let written = serde_json::from_str(&actual.to_json_string_pretty()).unwrap();
+        similar_asserts::assert_eq!(actual, Config::from_value(written).unwrap());
+
+        let err = Config::from_value(serde_json::json!({ "scheduleGroups": 1 })).unwrap_err();
+        assert!(err.to_string().starts_with("failed to parse config file"));
+    }
+
+    #[test]
+    fn test_standalone_schedules() {
+        let mut schedule = Schedule::test_default();
+        schedule.name = "Nightly".to_string();
+        schedule.group_name = "batch".to_string();
+        let ss_config_value = serde_json::to_value(ss_config("First", None)).unwrap();
+        let value = serde_json::json!([
+            ss_config_value,
+            { "schedule": schedule, "delete": true },
+        ]);
+
+        let config = Config::from_value(value.clone()).unwrap();
+        similar_asserts::assert_eq!(vec![ss_config("First", None)], config.ss_configs);
+        similar_asserts::assert_eq!(
+            vec![StandaloneScheduleConfig {
+                schedule,
+                delete: true,
+                protect: false,
+                deploy_target: None,
+            }],
+            config.schedules
+        );
+        let written = serde_json::from_str(&config.to_json_string_pretty()).unwrap();
+        similar_asserts::assert_eq!(config, Config::from_value(written).unwrap());
+
+        // Standalone schedule is targeted by "group/name"
+        let config = Config {
+            schedule_groups: vec![schedule_group("batch", None)],
+            ..config
+        };
+        let resources =
+            config.target_resources_by_deploy_target(&Some(vec!["batch/Nightly".to_string()]));
+        assert_eq!(1, resources.len());
+        assert!(resources[0].ss_configs.is_empty());
+        assert_eq!(1, resources[0].schedules.len());
+        assert_eq!(1, resources[0].schedule_groups.len());
+
+        // Entry without state and schedule is still an ss config
+        let err = Config::from_value(serde_json::json!([{ "deleteAll": true }])).unwrap_err();
+        assert!(err.to_string().contains("missing field `state`"));
+    }
+
+    #[test]
+    fn test_from_value_rejects_unknown_values() {
+        let mut ss_config_value = serde_json::to_value(ss_config("First", None)).unwrap();
+        ss_config_value["state"]["type"] = serde_json::json!("FAST");
+        let mut schedule = serde_json::to_value(Schedule::test_default()).unwrap();
+        schedule["state"] = serde_json::json!("PAUSED");
+
+        let err = Config::from_value(serde_json::json!([
+            ss_config_value,
+            { "schedule": schedule },
+        ]))
+        .unwrap_err();
+
+        assert_eq!(
+            "unknown values in config: type 'FAST' of state machine 'First', state 'PAUSED' of schedule 'default/HelloWorld'",
+            err.to_string()
+        );
+        assert!(matches!(err, FuburaError::Config(_)));
+    }
 }
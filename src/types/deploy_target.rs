@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+// AWS account and region where a config is deployed.
+// Config without deploy target is deployed with the default AWS config.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployTarget {
+    pub region: String,
+    pub role_arn: Option<String>,
+    pub external_id: Option<String>,
+}
@@ -1,15 +1,36 @@
 use serde::{Serialize, Serializer};
 
+// Ordered as applied. Schedule groups are created before schedules in them,
+// and deleted after them. Aliases are changed after versions are published,
+// and deleted before schedules and rules targeting them would be left behind.
 #[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord)]
 pub enum DiffOp {
+    CreateScheduleGroup,
+    // only for report, schedule group has nothing to update but tags
+    UpdateScheduleGroup,
+    AddScheduleGroupTag,
+    RemoveScheduleGroupTag(Vec<String>),
+    // log group which the state machine logs to, created before it
+    CreateLogGroup,
+    UpdateLogGroup,
     CreateState,
     UpdateState,
     AddStateTag,
     RemoveStateTag(Vec<String>),
-    CreateSchedule,
-    UpdateSchedule,
-    DeleteSchedule,
+    // names of aliases
+    CreateStateAlias(Vec<String>),
+    UpdateStateAlias(Vec<String>),
+    // "group/name" of schedule
+    CreateSchedule(String),
+    UpdateSchedule(String),
+    DeleteSchedule(String),
+    // "bus/name" of rule
+    CreateRule(String),
+    UpdateRule(String),
+    DeleteRule(String),
+    DeleteStateAlias(Vec<String>),
     DeleteState,
+    DeleteScheduleGroup,
 }
 
 impl DiffOp {
@@ -18,20 +39,66 @@ impl DiffOp {
         match op {
             DiffOp::AddStateTag => &DiffOp::UpdateState,
             DiffOp::RemoveStateTag(_) => &DiffOp::UpdateState,
+            DiffOp::AddScheduleGroupTag => &DiffOp::UpdateScheduleGroup,
+            DiffOp::RemoveScheduleGroupTag(_) => &DiffOp::UpdateScheduleGroup,
             op => op,
         }
     }
 
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            DiffOp::DeleteState
+                | DiffOp::DeleteStateAlias(_)
+                | DiffOp::DeleteSchedule(_)
+                | DiffOp::DeleteRule(_)
+                | DiffOp::DeleteScheduleGroup
+        )
+    }
+
+    // "group/name" of schedule which this op is for
+    pub fn schedule_name(&self) -> Option<&str> {
+        match self {
+            DiffOp::CreateSchedule(name)
+            | DiffOp::UpdateSchedule(name)
+            | DiffOp::DeleteSchedule(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    // "bus/name" of rule which this op is for
+    pub fn rule_name(&self) -> Option<&str> {
+        match self {
+            DiffOp::CreateRule(name) | DiffOp::UpdateRule(name) | DiffOp::DeleteRule(name) => {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
     pub fn op_type(&self) -> &str {
         match self {
+            DiffOp::CreateLogGroup => "create_log_group",
+            DiffOp::UpdateLogGroup => "update_log_group",
             DiffOp::CreateState => "create_state",
             DiffOp::UpdateState => "update_state",
             DiffOp::AddStateTag => "add_state_tag",
             DiffOp::RemoveStateTag(_) => "remove_state_tag",
-            DiffOp::CreateSchedule => "create_schedule",
-            DiffOp::UpdateSchedule => "update_schedule",
-            DiffOp::DeleteSchedule => "delete_schedule",
+            DiffOp::CreateStateAlias(_) => "create_state_alias",
+            DiffOp::UpdateStateAlias(_) => "update_state_alias",
+            DiffOp::DeleteStateAlias(_) => "delete_state_alias",
+            DiffOp::CreateSchedule(_) => "create_schedule",
+            DiffOp::UpdateSchedule(_) => "update_schedule",
+            DiffOp::DeleteSchedule(_) => "delete_schedule",
+            DiffOp::CreateRule(_) => "create_rule",
+            DiffOp::UpdateRule(_) => "update_rule",
+            DiffOp::DeleteRule(_) => "delete_rule",
             DiffOp::DeleteState => "delete_state",
+            DiffOp::CreateScheduleGroup => "create_schedule_group",
+            DiffOp::UpdateScheduleGroup => "update_schedule_group",
+            DiffOp::AddScheduleGroupTag => "add_schedule_group_tag",
+            DiffOp::RemoveScheduleGroupTag(_) => "remove_schedule_group_tag",
+            DiffOp::DeleteScheduleGroup => "delete_schedule_group",
         }
     }
 }
@@ -57,9 +124,9 @@ mod test {
             DiffOp::DeleteState,
             DiffOp::AddStateTag,
             DiffOp::RemoveStateTag(vec!["tag".to_string()]),
-            DiffOp::CreateSchedule,
-            DiffOp::UpdateSchedule,
-            DiffOp::DeleteSchedule,
+            DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+            DiffOp::UpdateSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
         ];
 
         actual_diff_ops.sort();
@@ -69,9 +136,82 @@ mod test {
             DiffOp::UpdateState,
             DiffOp::AddStateTag,
             DiffOp::RemoveStateTag(vec!["tag".to_string()]),
-            DiffOp::CreateSchedule,
-            DiffOp::UpdateSchedule,
-            DiffOp::DeleteSchedule,
+            DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+            DiffOp::UpdateSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteState,
+        ];
+
+        similar_asserts::assert_eq!(expected, actual_diff_ops);
+    }
+
+    #[test]
+    fn test_diff_op_ordering_schedule_group_around_schedule() {
+        let mut actual_diff_ops = vec![
+            DiffOp::DeleteScheduleGroup,
+            DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
+            DiffOp::CreateScheduleGroup,
+            DiffOp::AddScheduleGroupTag,
+        ];
+
+        actual_diff_ops.sort();
+
+        let expected = vec![
+            DiffOp::CreateScheduleGroup,
+            DiffOp::AddScheduleGroupTag,
+            DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteScheduleGroup,
+        ];
+
+        similar_asserts::assert_eq!(expected, actual_diff_ops);
+    }
+
+    #[test]
+    fn test_diff_op_ordering_state_alias() {
+        let names = vec!["live".to_string()];
+        let mut actual_diff_ops = vec![
+            DiffOp::DeleteState,
+            DiffOp::DeleteStateAlias(names.clone()),
+            DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+            DiffOp::CreateStateAlias(names.clone()),
+            DiffOp::UpdateState,
+        ];
+
+        actual_diff_ops.sort();
+
+        let expected = vec![
+            DiffOp::UpdateState,
+            DiffOp::CreateStateAlias(names.clone()),
+            DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteStateAlias(names),
+            DiffOp::DeleteState,
+        ];
+
+        similar_asserts::assert_eq!(expected, actual_diff_ops);
+    }
+
+    #[test]
+    fn test_diff_op_ordering_rule() {
+        let names = vec!["live".to_string()];
+        let mut actual_diff_ops = vec![
+            DiffOp::DeleteState,
+            DiffOp::DeleteRule("default/HelloWorldOnUpload".to_string()),
+            DiffOp::DeleteStateAlias(names.clone()),
+            DiffOp::CreateRule("default/HelloWorldOnUpload".to_string()),
+            DiffOp::CreateStateAlias(names.clone()),
+            DiffOp::CreateState,
+        ];
+
+        actual_diff_ops.sort();
+
+        let expected = vec![
+            DiffOp::CreateState,
+            DiffOp::CreateStateAlias(names.clone()),
+            DiffOp::CreateRule("default/HelloWorldOnUpload".to_string()),
+            DiffOp::DeleteRule("default/HelloWorldOnUpload".to_string()),
+            DiffOp::DeleteStateAlias(names),
             DiffOp::DeleteState,
         ];
 
@@ -86,9 +226,9 @@ mod test {
             DiffOp::DeleteState,
             DiffOp::AddStateTag,
             DiffOp::RemoveStateTag(vec!["tag".to_string()]),
-            DiffOp::CreateSchedule,
-            DiffOp::UpdateSchedule,
-            DiffOp::DeleteSchedule,
+            DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+            DiffOp::UpdateSchedule("default/HelloWorld".to_string()),
+            DiffOp::DeleteSchedule("default/HelloWorld".to_string()),
         ];
 
         let serialized = serde_json::to_string_pretty(&actual_diff_ops).unwrap();
@@ -107,4 +247,12 @@ mod test {
 
         similar_asserts::assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_is_destructive() {
+        assert!(!DiffOp::UpdateState.is_destructive());
+        assert!(!DiffOp::RemoveStateTag(vec!["tag".to_string()]).is_destructive());
+        assert!(DiffOp::DeleteSchedule("default/HelloWorld".to_string()).is_destructive());
+        assert!(DiffOp::DeleteScheduleGroup.is_destructive());
+    }
 }
@@ -7,10 +7,110 @@ use super::DiffOp;
 
 type OpName = String;
 
+// Identifies ss config in diff result, by deploy target label and state name,
+// or "group/name" for standalone schedule
+pub type SsKey = (Option<String>, String);
+
+// Name of ss config, reported as "state_name" or "schedule_name"
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum SsName {
+    StateName(String),
+    // "group/name" of standalone schedule
+    ScheduleName(String),
+}
+
+impl SsName {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SsName::StateName(name) | SsName::ScheduleName(name) => name,
+        }
+    }
+}
+
+// Name shown to user, with label of deploy target unless it's the default one
+pub fn target_display_name(name: &str, target: Option<&str>) -> String {
+    match target {
+        Some(target) => format!("{} ({})", name, target),
+        None => name.to_string(),
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct DiffOpsForSchedule {
+    // "group/name" of schedule
+    pub schedule_name: String,
+    pub diff_ops: Vec<DiffOp>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct DiffOpsForRule {
+    // "bus/name" of rule
+    pub rule_name: String,
+    pub diff_ops: Vec<DiffOp>,
+}
+
 #[derive(Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct DiffOpsForSs {
-    pub state_name: String,
+    // label of deploy target, like "123456789012/us-west-2". None for default target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(flatten)]
+    pub name: SsName,
     pub diff_ops: Vec<DiffOp>,
+    // schedule ops by each schedule, only for report. diff_ops has them once for each op type
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<DiffOpsForSchedule>,
+    // same as schedules, for rules
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<DiffOpsForRule>,
+}
+
+impl DiffOpsForSs {
+    pub fn key(&self) -> SsKey {
+        (self.target.clone(), self.name.as_str().to_string())
+    }
+
+    pub fn display_name(&self) -> String {
+        target_display_name(self.name.as_str(), self.target.as_deref())
+    }
+
+    // Schedule and rule ops are counted for each of them, and others once for the ss config
+    fn summary_ops(&self) -> impl Iterator<Item = &DiffOp> {
+        let schedule_ops = self
+            .schedules
+            .iter()
+            .flat_map(|ops_for_schedule| ops_for_schedule.diff_ops.iter());
+        let rule_ops = self
+            .rules
+            .iter()
+            .flat_map(|ops_for_rule| ops_for_rule.diff_ops.iter());
+
+        self.diff_ops
+            .iter()
+            .filter(|op| self.schedules.is_empty() || op.schedule_name().is_none())
+            .filter(|op| self.rules.is_empty() || op.rule_name().is_none())
+            .chain(schedule_ops)
+            .chain(rule_ops)
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct DiffOpsForScheduleGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub group_name: String,
+    pub diff_ops: Vec<DiffOp>,
+}
+
+impl DiffOpsForScheduleGroup {
+    pub fn key(&self) -> SsKey {
+        (self.target.clone(), self.group_name.clone())
+    }
+
+    pub fn display_name(&self) -> String {
+        target_display_name(&self.group_name, self.target.as_deref())
+    }
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq, Clone)]
@@ -21,8 +121,27 @@ pub struct DiffResult {
     // diff_ops list used for apply. which know about tags
     #[serde(skip_serializing)]
     pub detail_diff_ops: Vec<DiffOpsForSs>,
+    // same as diff_ops and detail_diff_ops, for schedule groups
+    pub schedule_group_diff_ops: Vec<DiffOpsForScheduleGroup>,
+    #[serde(skip_serializing)]
+    pub detail_schedule_group_diff_ops: Vec<DiffOpsForScheduleGroup>,
+    // text diff of each state, used for interactive approval
+    #[serde(skip_serializing)]
+    pub text_diff_by_state: HashMap<SsKey, String>,
+    // same as text_diff_by_state, keyed by group name
+    #[serde(skip_serializing)]
+    pub text_diff_by_group: HashMap<SsKey, String>,
+    // deploy target label which following diff ops belong to
+    #[serde(skip_serializing)]
+    pub current_target: Option<String>,
     pub no_change: bool,
     pub summary: HashMap<OpName, usize>,
+    // state names skipped on interactive approval
+    pub skipped: Vec<String>,
+    // arns of state machine versions published on apply
+    pub published_versions: Vec<String>,
+    // warnings found on diff, e.g. remote fields reset on update
+    pub warnings: Vec<String>,
 }
 
 impl Default for DiffResult {
@@ -31,70 +150,200 @@ impl Default for DiffResult {
             text_diff: vec![],
             diff_ops: vec![],
             detail_diff_ops: vec![],
+            schedule_group_diff_ops: vec![],
+            detail_schedule_group_diff_ops: vec![],
+            text_diff_by_state: HashMap::new(),
+            text_diff_by_group: HashMap::new(),
+            current_target: None,
             no_change: true,
             summary: HashMap::from([
                 ("create_state".to_string(), 0),
                 ("update_state".to_string(), 0),
                 ("delete_state".to_string(), 0),
+                ("create_state_alias".to_string(), 0),
+                ("update_state_alias".to_string(), 0),
+                ("delete_state_alias".to_string(), 0),
                 ("create_schedule".to_string(), 0),
                 ("update_schedule".to_string(), 0),
                 ("delete_schedule".to_string(), 0),
+                ("create_rule".to_string(), 0),
+                ("update_rule".to_string(), 0),
+                ("delete_rule".to_string(), 0),
+                ("create_schedule_group".to_string(), 0),
+                ("update_schedule_group".to_string(), 0),
+                ("delete_schedule_group".to_string(), 0),
+                ("create_log_group".to_string(), 0),
+                ("update_log_group".to_string(), 0),
             ]),
+            skipped: vec![],
+            published_versions: vec![],
+            warnings: vec![],
         }
     }
 }
 
 impl DiffResult {
-    pub fn append_text_diff(&mut self, diff: String) {
+    pub fn set_target(&mut self, target: Option<String>) {
+        self.current_target = target;
+    }
+
+    // Name is state name, or "group/name" for standalone schedule
+    pub fn append_text_diff(&mut self, name: &str, diff: String) {
+        let stripped_diff = strip_ansi_codes(&diff);
+        self.text_diff.push(stripped_diff.to_string());
+        self.text_diff_by_state
+            .insert((self.current_target.clone(), name.to_string()), diff);
+    }
+
+    pub fn append_schedule_group_text_diff(&mut self, group_name: &str, diff: String) {
         let stripped_diff = strip_ansi_codes(&diff);
         self.text_diff.push(stripped_diff.to_string());
+        self.text_diff_by_group
+            .insert((self.current_target.clone(), group_name.to_string()), diff);
+    }
+
+    // Drop all diff ops of given state from this result, and remember it as skipped.
+    pub fn skip(&mut self, ss_key: &SsKey) {
+        self.detail_diff_ops
+            .retain(|ops_for_ss| ops_for_ss.key() != *ss_key);
+
+        if let Some(index) = self
+            .diff_ops
+            .iter()
+            .position(|ops_for_ss| ops_for_ss.key() == *ss_key)
+        {
+            let ops_for_ss = self.diff_ops.remove(index);
+            for diff_op in ops_for_ss.summary_ops() {
+                self.summary
+                    .entry(diff_op.op_type().to_string())
+                    .and_modify(|e| *e -= 1);
+            }
+            self.skipped.push(ops_for_ss.display_name());
+        }
+
+        self.no_change =
+            self.detail_diff_ops.is_empty() && self.detail_schedule_group_diff_ops.is_empty();
+    }
+
+    // Same as skip, for schedule group.
+    pub fn skip_schedule_group(&mut self, group_key: &SsKey) {
+        self.detail_schedule_group_diff_ops
+            .retain(|ops_for_group| ops_for_group.key() != *group_key);
+
+        if let Some(index) = self
+            .schedule_group_diff_ops
+            .iter()
+            .position(|ops_for_group| ops_for_group.key() == *group_key)
+        {
+            let ops_for_group = self.schedule_group_diff_ops.remove(index);
+            for diff_op in &ops_for_group.diff_ops {
+                self.summary
+                    .entry(diff_op.op_type().to_string())
+                    .and_modify(|e| *e -= 1);
+            }
+            self.skipped.push(ops_for_group.display_name());
+        }
+
+        self.no_change =
+            self.detail_diff_ops.is_empty() && self.detail_schedule_group_diff_ops.is_empty();
+    }
+
+    pub fn append_schedule_group_diff_op(&mut self, group_name: &str, diff_op: &DiffOp) {
+        let target = self.current_target.clone();
+        let report_op = DiffOp::op_for_report(diff_op);
+
+        push_schedule_group_diff_op(
+            &mut self.detail_schedule_group_diff_ops,
+            &target,
+            group_name,
+            diff_op,
+        );
+        if push_schedule_group_diff_op(
+            &mut self.schedule_group_diff_ops,
+            &target,
+            group_name,
+            report_op,
+        ) {
+            self.add_summary(report_op);
+        }
+
+        self.no_change = false;
     }
 
     pub fn append_diff_op(&mut self, state_name: &str, diff_op: &DiffOp) {
-        self.add_detail_diff_op(state_name, diff_op);
-        self.add_diff_op(state_name, diff_op);
+        let name = SsName::StateName(state_name.to_string());
+        self.add_detail_diff_op(&name, diff_op);
+        self.add_diff_op(&name, diff_op);
 
         self.no_change = false;
     }
 
-    fn add_detail_diff_op(&mut self, state_name: &str, diff_op: &DiffOp) {
-        let state_name = state_name.to_string();
+    pub fn append_standalone_schedule_diff_op(&mut self, schedule_name: &str, diff_op: &DiffOp) {
+        let name = SsName::ScheduleName(schedule_name.to_string());
+        self.add_detail_diff_op(&name, diff_op);
+        self.add_diff_op(&name, diff_op);
+
+        self.no_change = false;
+    }
 
+    fn add_detail_diff_op(&mut self, name: &SsName, diff_op: &DiffOp) {
         let diff_op_for_ss = self
             .detail_diff_ops
             .iter_mut()
-            .find(|ddo| ddo.state_name == state_name);
+            .find(|ddo| ddo.target == self.current_target && ddo.name == *name);
         if let Some(diff_op_for_ss) = diff_op_for_ss {
             diff_op_for_ss.diff_ops.push(diff_op.clone());
         } else {
             let diff_op_for_ss = DiffOpsForSs {
-                state_name,
+                target: self.current_target.clone(),
+                name: name.clone(),
                 diff_ops: vec![diff_op.clone()],
+                schedules: vec![],
+                rules: vec![],
             };
             self.detail_diff_ops.push(diff_op_for_ss);
         }
     }
 
-    fn add_diff_op(&mut self, state_name: &str, diff_op: &DiffOp) {
-        let state_name = state_name.to_string();
+    // Schedule and rule ops are counted for each of them, and others for each state.
+    fn add_diff_op(&mut self, name: &SsName, diff_op: &DiffOp) {
         let diff_op = DiffOp::op_for_report(diff_op);
 
-        let diff_op_for_ss = self
+        let index = match self
             .diff_ops
-            .iter_mut()
-            .find(|ddo| ddo.state_name == state_name);
-        if let Some(diff_op_for_ss) = diff_op_for_ss {
-            if !diff_op_for_ss.diff_ops.contains(diff_op) {
-                diff_op_for_ss.diff_ops.push(diff_op.clone());
-
-                self.add_summary(diff_op);
+            .iter()
+            .position(|ddo| ddo.target == self.current_target && ddo.name == *name)
+        {
+            Some(index) => index,
+            None => {
+                self.diff_ops.push(DiffOpsForSs {
+                    target: self.current_target.clone(),
+                    name: name.clone(),
+                    diff_ops: vec![],
+                    schedules: vec![],
+                    rules: vec![],
+                });
+                self.diff_ops.len() - 1
             }
-        } else {
-            let diff_op_for_ss = DiffOpsForSs {
-                state_name,
-                diff_ops: vec![diff_op.clone()],
-            };
-            self.diff_ops.push(diff_op_for_ss);
+        };
+        let diff_op_for_ss = &mut self.diff_ops[index];
+
+        let mut is_new = !diff_op_for_ss
+            .diff_ops
+            .iter()
+            .any(|op| op.op_type() == diff_op.op_type());
+        if is_new {
+            diff_op_for_ss.diff_ops.push(diff_op.clone());
+        }
+        // Standalone schedule is the schedule itself, so no need to list it again
+        if let (Some(schedule_name), SsName::StateName(_)) = (diff_op.schedule_name(), name) {
+            is_new = push_schedule_diff_op(&mut diff_op_for_ss.schedules, schedule_name, diff_op);
+        }
+        if let Some(rule_name) = diff_op.rule_name() {
+            is_new = push_rule_diff_op(&mut diff_op_for_ss.rules, rule_name, diff_op);
+        }
+
+        if is_new {
             self.add_summary(diff_op);
         }
     }
@@ -106,6 +355,84 @@ impl DiffResult {
     }
 }
 
+// Returns false if the schedule already has the op.
+fn push_schedule_diff_op(
+    diff_ops: &mut Vec<DiffOpsForSchedule>,
+    schedule_name: &str,
+    diff_op: &DiffOp,
+) -> bool {
+    match diff_ops
+        .iter_mut()
+        .find(|ops_for_schedule| ops_for_schedule.schedule_name == schedule_name)
+    {
+        Some(ops_for_schedule) if ops_for_schedule.diff_ops.contains(diff_op) => false,
+        Some(ops_for_schedule) => {
+            ops_for_schedule.diff_ops.push(diff_op.clone());
+            true
+        }
+        None => {
+            diff_ops.push(DiffOpsForSchedule {
+                schedule_name: schedule_name.to_string(),
+                diff_ops: vec![diff_op.clone()],
+            });
+            true
+        }
+    }
+}
+
+// Returns false if the rule already has the op.
+fn push_rule_diff_op(
+    diff_ops: &mut Vec<DiffOpsForRule>,
+    rule_name: &str,
+    diff_op: &DiffOp,
+) -> bool {
+    match diff_ops
+        .iter_mut()
+        .find(|ops_for_rule| ops_for_rule.rule_name == rule_name)
+    {
+        Some(ops_for_rule) if ops_for_rule.diff_ops.contains(diff_op) => false,
+        Some(ops_for_rule) => {
+            ops_for_rule.diff_ops.push(diff_op.clone());
+            true
+        }
+        None => {
+            diff_ops.push(DiffOpsForRule {
+                rule_name: rule_name.to_string(),
+                diff_ops: vec![diff_op.clone()],
+            });
+            true
+        }
+    }
+}
+
+// Returns false if the group already has the op.
+fn push_schedule_group_diff_op(
+    diff_ops: &mut Vec<DiffOpsForScheduleGroup>,
+    target: &Option<String>,
+    group_name: &str,
+    diff_op: &DiffOp,
+) -> bool {
+    let ops_for_group = diff_ops.iter_mut().find(|ops_for_group| {
+        ops_for_group.target == *target && ops_for_group.group_name == group_name
+    });
+
+    match ops_for_group {
+        Some(ops_for_group) if ops_for_group.diff_ops.contains(diff_op) => false,
+        Some(ops_for_group) => {
+            ops_for_group.diff_ops.push(diff_op.clone());
+            true
+        }
+        None => {
+            diff_ops.push(DiffOpsForScheduleGroup {
+                target: target.clone(),
+                group_name: group_name.to_string(),
+                diff_ops: vec![diff_op.clone()],
+            });
+            true
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -121,18 +448,87 @@ mod test {
             "HelloWorld",
             &DiffOp::RemoveStateTag(vec!["tag".to_string()]),
         );
-        actual.append_diff_op("HelloWorld", &DiffOp::CreateSchedule);
+        actual.append_diff_op(
+            "HelloWorld",
+            &DiffOp::CreateSchedule("default/HelloWorld".to_string()),
+        );
+        actual.append_diff_op(
+            "HelloWorld",
+            &DiffOp::CreateSchedule("default/HelloWorldOnWeekend".to_string()),
+        );
+        actual.append_diff_op(
+            "HelloWorld",
+            &DiffOp::CreateRule("default/HelloWorldOnUpload".to_string()),
+        );
+        actual.append_diff_op(
+            "HelloWorld",
+            &DiffOp::CreateRule("orders/HelloWorldOnOrder".to_string()),
+        );
 
         let expected = HashMap::from([
             ("create_state".to_string(), 1),
             ("update_state".to_string(), 1),
             ("delete_state".to_string(), 0),
-            ("create_schedule".to_string(), 1),
+            ("create_state_alias".to_string(), 0),
+            ("update_state_alias".to_string(), 0),
+            ("delete_state_alias".to_string(), 0),
+            ("create_schedule".to_string(), 2),
             ("update_schedule".to_string(), 0),
             ("delete_schedule".to_string(), 0),
+            ("create_rule".to_string(), 2),
+            ("update_rule".to_string(), 0),
+            ("delete_rule".to_string(), 0),
+            ("create_schedule_group".to_string(), 0),
+            ("update_schedule_group".to_string(), 0),
+            ("delete_schedule_group".to_string(), 0),
+            ("create_log_group".to_string(), 0),
+            ("update_log_group".to_string(), 0),
         ]);
 
         similar_asserts::assert_eq!(expected, actual.summary);
+
+        // Reported once for the state, and for each schedule
+        let schedule_names: Vec<&str> = actual.diff_ops[1]
+            .schedules
+            .iter()
+            .map(|ops_for_schedule| ops_for_schedule.schedule_name.as_str())
+            .collect();
+        similar_asserts::assert_eq!(
+            vec!["default/HelloWorld", "default/HelloWorldOnWeekend"],
+            schedule_names
+        );
+        similar_asserts::assert_eq!(3, actual.diff_ops[1].diff_ops.len());
+        similar_asserts::assert_eq!(2, actual.diff_ops[1].rules.len());
+
+        actual.skip(&(None, "HelloWorld".to_string()));
+        similar_asserts::assert_eq!(0, actual.summary["create_schedule"]);
+        similar_asserts::assert_eq!(0, actual.summary["create_rule"]);
+        similar_asserts::assert_eq!(0, actual.summary["update_state"]);
+    }
+
+    #[test]
+    fn test_standalone_schedule_diff_ops() {
+        let mut actual = DiffResult::default();
+
+        actual.append_diff_op("HelloWorld", &DiffOp::UpdateState);
+        actual.append_standalone_schedule_diff_op(
+            "batch/Nightly",
+            &DiffOp::CreateSchedule("batch/Nightly".to_string()),
+        );
+
+        similar_asserts::assert_eq!(
+            serde_json::json!([
+                { "state_name": "HelloWorld", "diff_ops": ["update_state"] },
+                { "schedule_name": "batch/Nightly", "diff_ops": ["create_schedule"] },
+            ]),
+            serde_json::to_value(&actual.diff_ops).unwrap()
+        );
+        similar_asserts::assert_eq!(1, actual.summary["create_schedule"]);
+
+        actual.skip(&(None, "batch/Nightly".to_string()));
+        similar_asserts::assert_eq!(0, actual.summary["create_schedule"]);
+        similar_asserts::assert_eq!(0, actual.summary["create_rule"]);
+        similar_asserts::assert_eq!(vec!["batch/Nightly".to_string()], actual.skipped);
     }
 
     #[tokio::test]
@@ -144,4 +540,88 @@ mod test {
         actual.append_diff_op("NewBatch", &DiffOp::CreateState);
         similar_asserts::assert_eq!(false, actual.no_change);
     }
+
+    #[test]
+    fn test_skip() {
+        let mut actual = DiffResult::default();
+
+        actual.append_diff_op("NewBatch", &DiffOp::CreateState);
+        actual.append_diff_op("HelloWorld", &DiffOp::UpdateState);
+        actual.append_diff_op("HelloWorld", &DiffOp::AddStateTag);
+
+        actual.skip(&(None, "HelloWorld".to_string()));
+
+        let mut expected = DiffResult::default();
+        expected.append_diff_op("NewBatch", &DiffOp::CreateState);
+        expected.skipped.push("HelloWorld".to_string());
+
+        similar_asserts::assert_eq!(expected, actual);
+
+        actual.skip(&(None, "NewBatch".to_string()));
+        similar_asserts::assert_eq!(true, actual.no_change);
+    }
+
+    #[test]
+    fn test_same_state_name_on_different_targets() {
+        let mut actual = DiffResult::default();
+
+        actual.append_diff_op("HelloWorld", &DiffOp::UpdateState);
+        actual.set_target(Some("210987654321/ap-northeast-1".to_string()));
+        actual.append_diff_op("HelloWorld", &DiffOp::CreateState);
+
+        let expected = vec![
+            DiffOpsForSs {
+                target: None,
+                name: SsName::StateName("HelloWorld".to_string()),
+                diff_ops: vec![DiffOp::UpdateState],
+                schedules: vec![],
+                rules: vec![],
+            },
+            DiffOpsForSs {
+                target: Some("210987654321/ap-northeast-1".to_string()),
+                name: SsName::StateName("HelloWorld".to_string()),
+                diff_ops: vec![DiffOp::CreateState],
+                schedules: vec![],
+                rules: vec![],
+            },
+        ];
+
+        similar_asserts::assert_eq!(expected, actual.diff_ops);
+        similar_asserts::assert_eq!(
+            "HelloWorld (210987654321/ap-northeast-1)",
+            actual.diff_ops[1].display_name()
+        );
+    }
+
+    #[test]
+    fn test_schedule_group_diff_ops() {
+        let mut actual = DiffResult::default();
+
+        actual.append_schedule_group_diff_op("batch", &DiffOp::AddScheduleGroupTag);
+        actual.append_schedule_group_diff_op(
+            "batch",
+            &DiffOp::RemoveScheduleGroupTag(vec!["tag".to_string()]),
+        );
+        actual.append_schedule_group_diff_op("etl", &DiffOp::DeleteScheduleGroup);
+
+        similar_asserts::assert_eq!(
+            vec![
+                DiffOpsForScheduleGroup {
+                    target: None,
+                    group_name: "batch".to_string(),
+                    diff_ops: vec![DiffOp::UpdateScheduleGroup],
+                },
+                DiffOpsForScheduleGroup {
+                    target: None,
+                    group_name: "etl".to_string(),
+                    diff_ops: vec![DiffOp::DeleteScheduleGroup],
+                },
+            ],
+            actual.schedule_group_diff_ops
+        );
+        similar_asserts::assert_eq!(2, actual.detail_schedule_group_diff_ops[0].diff_ops.len());
+        similar_asserts::assert_eq!(1, actual.summary["update_schedule_group"]);
+        similar_asserts::assert_eq!(1, actual.summary["delete_schedule_group"]);
+        assert!(!actual.no_change);
+    }
 }
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+// Execution of state machine, watched on canary deployment.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Execution {
+    pub execution_arn: String,
+    pub state_machine_arn: String,
+    // set if the execution is started with a version, or an alias routing to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_machine_version_arn: Option<String>,
+    // RUNNING, SUCCEEDED, FAILED, TIMED_OUT, ABORTED or PENDING_REDRIVE
+    pub status: String,
+    // RFC 3339, like "2024-01-01T00:00:00Z"
+    pub start_date: String,
+    // only returned by DescribeExecution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cause: Option<String>,
+}
+
+impl Execution {
+    pub fn is_running(&self) -> bool {
+        self.status == "RUNNING"
+    }
+
+    // Failed execution waiting for redrive is failed too
+    pub fn is_failed(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "FAILED" | "TIMED_OUT" | "ABORTED" | "PENDING_REDRIVE"
+        )
+    }
+}
@@ -0,0 +1,134 @@
+use serde::Serialize;
+use serde_json::Value;
+
+const POLICY_VERSION: &str = "2012-10-17";
+
+// IAM policy document, serialized as IAM JSON
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyDocument {
+    pub version: String,
+    pub statement: Vec<PolicyStatement>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyStatement {
+    pub effect: String,
+    pub action: Vec<String>,
+    pub resource: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Value>,
+}
+
+impl Default for PolicyDocument {
+    fn default() -> Self {
+        PolicyDocument {
+            version: POLICY_VERSION.to_string(),
+            statement: vec![],
+        }
+    }
+}
+
+impl PolicyDocument {
+    pub fn allow(&mut self, actions: &[&str], resources: &[String]) {
+        self.add(actions, resources, None);
+    }
+
+    // Statements with the same actions and condition are merged into one, keeping the order of first appearance.
+    pub fn add(&mut self, actions: &[&str], resources: &[String], condition: Option<Value>) {
+        if resources.is_empty() {
+            return;
+        }
+
+        let actions: Vec<String> = actions.iter().map(|action| action.to_string()).collect();
+        let index =
+            match self.statement.iter().position(|statement| {
+                statement.action == actions && statement.condition == condition
+            }) {
+                Some(index) => index,
+                None => {
+                    self.statement.push(PolicyStatement {
+                        effect: "Allow".to_string(),
+                        action: actions,
+                        resource: vec![],
+                        condition,
+                    });
+                    self.statement.len() - 1
+                }
+            };
+
+        let statement = &mut self.statement[index];
+        for resource in resources {
+            if !statement.resource.contains(resource) {
+                statement.resource.push(resource.clone());
+            }
+        }
+        // Other resources are redundant with wildcard
+        if statement.resource.iter().any(|resource| resource == "*") {
+            statement.resource = vec!["*".to_string()];
+        }
+    }
+
+    pub fn merge(&mut self, other: &PolicyDocument) {
+        for statement in other.statement.iter() {
+            let actions: Vec<&str> = statement.action.iter().map(|s| s.as_str()).collect();
+            self.add(&actions, &statement.resource, statement.condition.clone());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statement.is_empty()
+    }
+}
+
+// Policy which the role needs for the state machines
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct RolePolicy {
+    pub role_arn: String,
+    pub state_names: Vec<String>,
+    pub policy: PolicyDocument,
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_policy_document_merges_statements() {
+        let mut policy = PolicyDocument::default();
+        policy.allow(&["lambda:InvokeFunction"], &["arn:1".to_string()]);
+        policy.allow(&["sns:Publish"], &["arn:2".to_string()]);
+        policy.allow(
+            &["lambda:InvokeFunction"],
+            &["arn:1".to_string(), "arn:3".to_string()],
+        );
+        policy.allow(&["sqs:SendMessage"], &[]);
+
+        similar_asserts::assert_eq!(
+            json!({
+                "Version": "2012-10-17",
+                "Statement": [
+                    {
+                        "Effect": "Allow",
+                        "Action": ["lambda:InvokeFunction"],
+                        "Resource": ["arn:1", "arn:3"],
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": ["sns:Publish"],
+                        "Resource": ["arn:2"],
+                    },
+                ],
+            }),
+            serde_json::to_value(&policy).unwrap()
+        );
+
+        let mut other = PolicyDocument::default();
+        other.allow(&["sns:Publish"], &["*".to_string()]);
+        policy.merge(&other);
+        assert_eq!(vec!["*".to_string()], policy.statement[1].resource);
+    }
+}
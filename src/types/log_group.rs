@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::ResourceTag;
+
+// CloudWatch Logs log group which the state machine logs to, created and updated by fubura.
+// It's never deleted with the state machine, to keep its logs.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogGroup {
+    pub name: String,
+    // Never expire when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention_in_days: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kms_key_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<ResourceTag>,
+}
+
+impl LogGroup {
+    // Tags are sorted by key, as they are returned as a map
+    pub fn from_remote(
+        value: &aws_sdk_cloudwatchlogs::types::LogGroup,
+        tags: Option<&HashMap<String, String>>,
+    ) -> Self {
+        let mut tags: Vec<ResourceTag> = tags
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| ResourceTag {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        tags.sort();
+
+        LogGroup {
+            name: value.log_group_name().unwrap_or_default().to_string(),
+            retention_in_days: value.retention_in_days(),
+            kms_key_id: value.kms_key_id().map(|s| s.to_string()),
+            tags,
+        }
+    }
+
+    pub fn to_remote(&self, log_group_arn: &str) -> aws_sdk_cloudwatchlogs::types::LogGroup {
+        aws_sdk_cloudwatchlogs::types::LogGroup::builder()
+            .log_group_name(&self.name)
+            .set_retention_in_days(self.retention_in_days)
+            .set_kms_key_id(self.kms_key_id.clone())
+            .arn(format!("{}:*", log_group_arn))
+            .log_group_arn(log_group_arn)
+            .build()
+    }
+
+    pub fn tags_map(&self) -> HashMap<String, String> {
+        self.tags
+            .iter()
+            .map(|tag| (tag.key.clone(), tag.value.clone()))
+            .collect()
+    }
+
+    #[cfg(test)]
+    pub fn test_default() -> Self {
+        LogGroup {
+            name: "HelloWorldLogGroup".to_string(),
+            retention_in_days: Some(30),
+            kms_key_id: None,
+            tags: vec![ResourceTag {
+                key: "Env".to_string(),
+                value: "Test".to_string(),
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_log_group_from_remote() {
+        let log_group = LogGroup::test_default();
+        let remote =
+            log_group.to_remote("arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup");
+        let tags = log_group.tags_map();
+
+        similar_asserts::assert_eq!(log_group, LogGroup::from_remote(&remote, Some(&tags)));
+        assert_eq!(
+            Some("arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup:*"),
+            remote.arn()
+        );
+    }
+}
@@ -1,17 +1,37 @@
+mod arn;
 mod caller_identity;
+mod command_output;
 mod config;
+mod deploy_target;
 mod diff_op;
 mod diff_result;
+mod execution;
+mod iam_policy;
+mod log_group;
 mod resource_tag;
+mod rule;
 mod schedule;
+mod schedule_group;
 mod ssconfig;
 mod state_machine;
+mod state_machine_alias;
+mod state_machine_version;
 
+pub use arn::*;
 pub use caller_identity::*;
+pub use command_output::*;
 pub use config::*;
+pub use deploy_target::*;
 pub use diff_op::*;
 pub use diff_result::*;
+pub use execution::*;
+pub use iam_policy::*;
+pub use log_group::*;
 pub use resource_tag::*;
+pub use rule::*;
 pub use schedule::*;
+pub use schedule_group::*;
 pub use ssconfig::*;
 pub use state_machine::*;
+pub use state_machine_alias::*;
+pub use state_machine_version::*;
@@ -35,6 +35,15 @@ impl From<ResourceTag> for aws_sdk_sfn::types::Tag {
     }
 }
 
+impl From<aws_sdk_scheduler::types::Tag> for ResourceTag {
+    fn from(value: aws_sdk_scheduler::types::Tag) -> Self {
+        ResourceTag {
+            key: value.key().to_string(),
+            value: value.value().to_string(),
+        }
+    }
+}
+
 impl From<ResourceTag> for aws_sdk_scheduler::types::Tag {
     fn from(value: ResourceTag) -> Self {
         aws_sdk_scheduler::types::builders::TagBuilder::default()
@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::FuburaError;
+
+pub const DEFAULT_EVENT_BUS_NAME: &str = "default";
+// Id of rule target managed by fubura, if it is not given.
+pub const DEFAULT_RULE_TARGET_ID: &str = "fubura";
+
+fn default_event_bus_name() -> String {
+    DEFAULT_EVENT_BUS_NAME.to_string()
+}
+
+fn default_rule_target_id() -> String {
+    DEFAULT_RULE_TARGET_ID.to_string()
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub enum RuleState {
+    #[default]
+    #[serde(rename = "ENABLED")]
+    Enabled,
+    #[serde(rename = "DISABLED")]
+    Disabled,
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl From<aws_sdk_eventbridge::types::RuleState> for RuleState {
+    fn from(value: aws_sdk_eventbridge::types::RuleState) -> Self {
+        match value {
+            aws_sdk_eventbridge::types::RuleState::Enabled => RuleState::Enabled,
+            aws_sdk_eventbridge::types::RuleState::Disabled => RuleState::Disabled,
+            other => RuleState::Unknown(other.as_str().to_string()),
+        }
+    }
+}
+
+impl From<RuleState> for aws_sdk_eventbridge::types::RuleState {
+    fn from(value: RuleState) -> Self {
+        match value {
+            RuleState::Enabled => aws_sdk_eventbridge::types::RuleState::Enabled,
+            RuleState::Disabled => aws_sdk_eventbridge::types::RuleState::Disabled,
+            RuleState::Unknown(other) => {
+                aws_sdk_eventbridge::types::RuleState::from(other.as_str())
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InputTransformer {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub input_paths_map: BTreeMap<String, String>,
+    pub input_template: String,
+}
+
+impl From<aws_sdk_eventbridge::types::InputTransformer> for InputTransformer {
+    fn from(value: aws_sdk_eventbridge::types::InputTransformer) -> Self {
+        InputTransformer {
+            input_paths_map: value
+                .input_paths_map()
+                .map(|map| map.clone().into_iter().collect())
+                .unwrap_or_default(),
+            input_template: value.input_template().to_string(),
+        }
+    }
+}
+
+impl From<InputTransformer> for aws_sdk_eventbridge::types::InputTransformer {
+    fn from(value: InputTransformer) -> Self {
+        let input_paths_map = (!value.input_paths_map.is_empty())
+            .then(|| value.input_paths_map.into_iter().collect());
+
+        aws_sdk_eventbridge::types::InputTransformer::builder()
+            .set_input_paths_map(input_paths_map)
+            .input_template(value.input_template)
+            .build()
+            .unwrap()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTarget {
+    #[serde(default = "default_rule_target_id")]
+    pub id: String,
+    // state machine of the config when omitted, see `SsConfig::rule_configs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arn: Option<String>,
+    pub role_arn: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_transformer: Option<InputTransformer>,
+}
+
+impl From<aws_sdk_eventbridge::types::Target> for RuleTarget {
+    fn from(value: aws_sdk_eventbridge::types::Target) -> Self {
+        RuleTarget {
+            id: value.id().to_string(),
+            arn: Some(value.arn().to_string()),
+            role_arn: value.role_arn().unwrap_or_default().to_string(),
+            input: value.input().map(|s| s.to_string()),
+            input_path: value.input_path().map(|s| s.to_string()),
+            input_transformer: value
+                .input_transformer()
+                .map(|it| InputTransformer::from(it.clone())),
+        }
+    }
+}
+
+impl From<RuleTarget> for aws_sdk_eventbridge::types::Target {
+    fn from(value: RuleTarget) -> Self {
+        aws_sdk_eventbridge::types::Target::builder()
+            .id(value.id)
+            .arn(value.arn.unwrap_or_default())
+            .role_arn(value.role_arn)
+            .set_input(value.input)
+            .set_input_path(value.input_path)
+            .set_input_transformer(value.input_transformer.map(|it| it.into()))
+            .build()
+            .unwrap()
+    }
+}
+
+// EventBridge rule which starts a state machine on events matching its pattern.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    #[serde(default = "default_event_bus_name")]
+    pub event_bus_name: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub event_pattern: Value,
+    #[serde(default)]
+    pub state: RuleState,
+    // Only this target of the rule is managed, other targets are left as is.
+    // None for remote rule which doesn't have the target.
+    pub target: Option<RuleTarget>,
+}
+
+impl Rule {
+    // "bus/name" which identifies the rule in a region
+    pub fn rule_name_with_bus(&self) -> String {
+        format!("{}/{}", self.event_bus_name, self.name)
+    }
+
+    // Values only AWS can give, which config can't use
+    pub fn unknown_values(&self) -> Vec<String> {
+        match &self.state {
+            RuleState::Unknown(value) => vec![format!("state '{}'", value)],
+            _ => vec![],
+        }
+    }
+
+    pub fn target_id(&self) -> &str {
+        self.target
+            .as_ref()
+            .map_or(DEFAULT_RULE_TARGET_ID, |target| target.id.as_str())
+    }
+
+    pub fn from_remote(
+        value: &aws_sdk_eventbridge::operation::describe_rule::DescribeRuleOutput,
+        target: Option<&aws_sdk_eventbridge::types::Target>,
+    ) -> Result<Self, FuburaError> {
+        let missing = |field| FuburaError::MissingField {
+            resource: "DescribeRule response",
+            field,
+        };
+        let event_pattern = value
+            .event_pattern()
+            .ok_or_else(|| missing("event_pattern"))?;
+
+        Ok(Rule {
+            event_bus_name: value
+                .event_bus_name()
+                .map_or(default_event_bus_name(), |v| v.to_string()),
+            name: value.name().ok_or_else(|| missing("name"))?.to_string(),
+            description: value.description().map(|s| s.to_string()),
+            // AWS validates pattern, keep it as is if it somehow isn't JSON
+            event_pattern: serde_json::from_str(event_pattern)
+                .unwrap_or_else(|_| Value::String(event_pattern.to_string())),
+            state: value
+                .state()
+                .map(|state| RuleState::from(state.clone()))
+                .unwrap_or_default(),
+            target: target.map(|target| RuleTarget::from(target.clone())),
+        })
+    }
+
+    pub fn to_describe_rule_output(
+        &self,
+        rule_arn: &str,
+    ) -> aws_sdk_eventbridge::operation::describe_rule::DescribeRuleOutput {
+        aws_sdk_eventbridge::operation::describe_rule::DescribeRuleOutput::builder()
+            .arn(rule_arn)
+            .name(&self.name)
+            .event_bus_name(&self.event_bus_name)
+            .set_description(self.description.clone())
+            .event_pattern(self.event_pattern.to_string())
+            .state(self.state.clone().into())
+            .build()
+    }
+
+    #[cfg(test)]
+    pub fn test_default() -> Self {
+        Rule {
+            event_bus_name: DEFAULT_EVENT_BUS_NAME.to_string(),
+            name: "HelloWorldOnUpload".to_string(),
+            description: Some("HelloWorld on upload".to_string()),
+            event_pattern: serde_json::json!({
+                "source": ["aws.s3"],
+                "detail-type": ["Object Created"],
+            }),
+            state: RuleState::Enabled,
+            target: Some(RuleTarget {
+                id: DEFAULT_RULE_TARGET_ID.to_string(),
+                arn: Some(
+                    "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld".to_string(),
+                ),
+                role_arn: "arn:aws:iam::123456789012:role/service-role/HelloWorldRole".to_string(),
+                input: None,
+                input_path: None,
+                input_transformer: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rule_from_remote() {
+        let mut rule = Rule::test_default();
+        let target = rule.target.as_mut().unwrap();
+        target.input_transformer = Some(InputTransformer {
+            input_paths_map: BTreeMap::from([("key".to_string(), "$.detail.key".to_string())]),
+            input_template: r#"{"key": <key>}"#.to_string(),
+        });
+
+        let output = rule.to_describe_rule_output(
+            "arn:aws:events:us-west-2:123456789012:rule/HelloWorldOnUpload",
+        );
+        let remote_target: aws_sdk_eventbridge::types::Target = rule.target.clone().unwrap().into();
+
+        let actual = Rule::from_remote(&output, Some(&remote_target)).unwrap();
+        similar_asserts::assert_eq!(rule, actual);
+
+        let actual = Rule::from_remote(&output, None).unwrap();
+        assert_eq!(None, actual.target);
+    }
+
+    #[test]
+    fn test_rule_deserialize_with_defaults() {
+        let rule: Rule = serde_json::from_value(serde_json::json!({
+            "name": "HelloWorldOnUpload",
+            "eventPattern": { "source": ["aws.s3"] },
+            "target": { "roleArn": "arn:aws:iam::123456789012:role/HelloWorldRole" },
+        }))
+        .unwrap();
+
+        assert_eq!("default/HelloWorldOnUpload", rule.rule_name_with_bus());
+        assert_eq!(RuleState::Enabled, rule.state);
+        assert_eq!(DEFAULT_RULE_TARGET_ID, rule.target_id());
+        assert_eq!(None, rule.target.unwrap().arn);
+    }
+}
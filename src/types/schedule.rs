@@ -1,7 +1,7 @@
-use aws_sdk_scheduler::primitives::DateTime;
+use aws_sdk_scheduler::primitives::{DateTime, DateTimeFormat};
 use serde::{Deserialize, Serialize};
 
-use crate::fast_exit;
+use crate::error::FuburaError;
 
 use super::ResourceTag;
 
@@ -11,6 +11,8 @@ pub enum FlexibleTimeWindowMode {
     Off,
     #[serde(rename = "FLEXIBLE")]
     Flexible,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_scheduler::types::FlexibleTimeWindowMode> for FlexibleTimeWindowMode {
@@ -20,8 +22,7 @@ impl From<aws_sdk_scheduler::types::FlexibleTimeWindowMode> for FlexibleTimeWind
             aws_sdk_scheduler::types::FlexibleTimeWindowMode::Flexible => {
                 FlexibleTimeWindowMode::Flexible
             }
-            // XXX: Don't fast_exit here
-            _ => fast_exit!("Unexpected flexible time window mode"),
+            other => FlexibleTimeWindowMode::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -33,6 +34,9 @@ impl From<FlexibleTimeWindowMode> for aws_sdk_scheduler::types::FlexibleTimeWind
             FlexibleTimeWindowMode::Flexible => {
                 aws_sdk_scheduler::types::FlexibleTimeWindowMode::Flexible
             }
+            FlexibleTimeWindowMode::Unknown(value) => {
+                aws_sdk_scheduler::types::FlexibleTimeWindowMode::from(value.as_str())
+            }
         }
     }
 }
@@ -117,6 +121,8 @@ pub enum AssignPublicIp {
     Disabled,
     #[serde(rename = "ENABLED")]
     Enabled,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_scheduler::types::AssignPublicIp> for AssignPublicIp {
@@ -124,8 +130,7 @@ impl From<aws_sdk_scheduler::types::AssignPublicIp> for AssignPublicIp {
         match value {
             aws_sdk_scheduler::types::AssignPublicIp::Disabled => AssignPublicIp::Disabled,
             aws_sdk_scheduler::types::AssignPublicIp::Enabled => AssignPublicIp::Enabled,
-            // XXX: Don't fast_exit here
-            _ => fast_exit!("Unexpected assign public ip"),
+            other => AssignPublicIp::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -135,6 +140,9 @@ impl From<AssignPublicIp> for aws_sdk_scheduler::types::AssignPublicIp {
         match value {
             AssignPublicIp::Disabled => aws_sdk_scheduler::types::AssignPublicIp::Disabled,
             AssignPublicIp::Enabled => aws_sdk_scheduler::types::AssignPublicIp::Enabled,
+            AssignPublicIp::Unknown(value) => {
+                aws_sdk_scheduler::types::AssignPublicIp::from(value.as_str())
+            }
         }
     }
 }
@@ -205,6 +213,8 @@ impl From<NetworkConfiguration> for aws_sdk_scheduler::types::NetworkConfigurati
 pub enum PlacementConstraintType {
     MemberOf,
     DistinctInstance,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_scheduler::types::PlacementConstraintType> for PlacementConstraintType {
@@ -216,8 +226,7 @@ impl From<aws_sdk_scheduler::types::PlacementConstraintType> for PlacementConstr
             aws_sdk_scheduler::types::PlacementConstraintType::DistinctInstance => {
                 PlacementConstraintType::DistinctInstance
             }
-            // XXX: Don't fast_exit here
-            _ => fast_exit!("Unexpected placement constraint type"),
+            other => PlacementConstraintType::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -231,6 +240,9 @@ impl From<PlacementConstraintType> for aws_sdk_scheduler::types::PlacementConstr
             PlacementConstraintType::DistinctInstance => {
                 aws_sdk_scheduler::types::PlacementConstraintType::DistinctInstance
             }
+            PlacementConstraintType::Unknown(value) => {
+                aws_sdk_scheduler::types::PlacementConstraintType::from(value.as_str())
+            }
         }
     }
 }
@@ -269,6 +281,8 @@ pub enum PlacementStrategyType {
     Spread,
     #[serde(rename = "binpack")]
     Binpack,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_scheduler::types::PlacementStrategyType> for PlacementStrategyType {
@@ -283,8 +297,7 @@ impl From<aws_sdk_scheduler::types::PlacementStrategyType> for PlacementStrategy
             aws_sdk_scheduler::types::PlacementStrategyType::Binpack => {
                 PlacementStrategyType::Binpack
             }
-            // XXX: Don't fast_exit here
-            _ => fast_exit!("Unexpected placement strategy type"),
+            other => PlacementStrategyType::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -301,6 +314,9 @@ impl From<PlacementStrategyType> for aws_sdk_scheduler::types::PlacementStrategy
             PlacementStrategyType::Binpack => {
                 aws_sdk_scheduler::types::PlacementStrategyType::Binpack
             }
+            PlacementStrategyType::Unknown(value) => {
+                aws_sdk_scheduler::types::PlacementStrategyType::from(value.as_str())
+            }
         }
     }
 }
@@ -342,6 +358,8 @@ pub enum LaunchType {
     Fargate,
     #[serde(rename = "EXTERNAL")]
     External,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_scheduler::types::LaunchType> for LaunchType {
@@ -350,8 +368,7 @@ impl From<aws_sdk_scheduler::types::LaunchType> for LaunchType {
             aws_sdk_scheduler::types::LaunchType::Ec2 => LaunchType::Ec2,
             aws_sdk_scheduler::types::LaunchType::Fargate => LaunchType::Fargate,
             aws_sdk_scheduler::types::LaunchType::External => LaunchType::External,
-            // XXX: Don't fast_exit here
-            _ => fast_exit!("Unexpected launch type"),
+            other => LaunchType::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -362,6 +379,9 @@ impl From<LaunchType> for aws_sdk_scheduler::types::LaunchType {
             LaunchType::Ec2 => aws_sdk_scheduler::types::LaunchType::Ec2,
             LaunchType::Fargate => aws_sdk_scheduler::types::LaunchType::Fargate,
             LaunchType::External => aws_sdk_scheduler::types::LaunchType::External,
+            LaunchType::Unknown(value) => {
+                aws_sdk_scheduler::types::LaunchType::from(value.as_str())
+            }
         }
     }
 }
@@ -370,6 +390,8 @@ impl From<LaunchType> for aws_sdk_scheduler::types::LaunchType {
 pub enum PropagateTags {
     #[serde(rename = "TASK_DEFINITION")]
     TaskDefinition,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_scheduler::types::PropagateTags> for PropagateTags {
@@ -378,8 +400,7 @@ impl From<aws_sdk_scheduler::types::PropagateTags> for PropagateTags {
             aws_sdk_scheduler::types::PropagateTags::TaskDefinition => {
                 PropagateTags::TaskDefinition
             }
-            // XXX: Don't fast_exit here
-            _ => fast_exit!("Unexpected propagate tags"),
+            other => PropagateTags::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -390,6 +411,9 @@ impl From<PropagateTags> for aws_sdk_scheduler::types::PropagateTags {
             PropagateTags::TaskDefinition => {
                 aws_sdk_scheduler::types::PropagateTags::TaskDefinition
             }
+            PropagateTags::Unknown(value) => {
+                aws_sdk_scheduler::types::PropagateTags::from(value.as_str())
+            }
         }
     }
 }
@@ -714,6 +738,8 @@ pub enum ScheduleState {
     Enabled,
     #[serde(rename = "DISABLED")]
     Disabled,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_scheduler::types::ScheduleState> for ScheduleState {
@@ -721,8 +747,7 @@ impl From<aws_sdk_scheduler::types::ScheduleState> for ScheduleState {
         match value {
             aws_sdk_scheduler::types::ScheduleState::Enabled => ScheduleState::Enabled,
             aws_sdk_scheduler::types::ScheduleState::Disabled => ScheduleState::Disabled,
-            // XXX: Don't fast_exit here
-            _ => fast_exit!("Unexpected schedule state"),
+            other => ScheduleState::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -732,10 +757,54 @@ impl From<ScheduleState> for aws_sdk_scheduler::types::ScheduleState {
         match value {
             ScheduleState::Enabled => aws_sdk_scheduler::types::ScheduleState::Enabled,
             ScheduleState::Disabled => aws_sdk_scheduler::types::ScheduleState::Disabled,
+            ScheduleState::Unknown(value) => {
+                aws_sdk_scheduler::types::ScheduleState::from(value.as_str())
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub enum ActionAfterCompletion {
+    #[serde(rename = "DELETE")]
+    Delete,
+    // AWS reports NONE for schedules created without it
+    #[default]
+    #[serde(rename = "NONE")]
+    None,
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl From<aws_sdk_scheduler::types::ActionAfterCompletion> for ActionAfterCompletion {
+    fn from(value: aws_sdk_scheduler::types::ActionAfterCompletion) -> Self {
+        match value {
+            aws_sdk_scheduler::types::ActionAfterCompletion::Delete => {
+                ActionAfterCompletion::Delete
+            }
+            aws_sdk_scheduler::types::ActionAfterCompletion::None => ActionAfterCompletion::None,
+            other => ActionAfterCompletion::Unknown(other.as_str().to_string()),
+        }
+    }
+}
+
+impl From<ActionAfterCompletion> for aws_sdk_scheduler::types::ActionAfterCompletion {
+    fn from(value: ActionAfterCompletion) -> Self {
+        match value {
+            ActionAfterCompletion::Delete => {
+                aws_sdk_scheduler::types::ActionAfterCompletion::Delete
+            }
+            ActionAfterCompletion::None => aws_sdk_scheduler::types::ActionAfterCompletion::None,
+            ActionAfterCompletion::Unknown(value) => {
+                aws_sdk_scheduler::types::ActionAfterCompletion::from(value.as_str())
+            }
         }
     }
 }
 
+// Largest offset behind UTC among time zones, UTC-12:00
+const MAX_OFFSET_BEHIND_UTC_SECS: i64 = 12 * 60 * 60;
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Schedule {
@@ -756,6 +825,8 @@ pub struct Schedule {
     pub end_date: Option<DateTime>,
     pub flexible_time_window: Option<FlexibleTimeWindow>,
     pub kms_key_arn: Option<String>,
+    #[serde(default)]
+    pub action_after_completion: ActionAfterCompletion,
     pub target: ScheduleTarget,
 }
 
@@ -764,6 +835,111 @@ impl Schedule {
         format!("{}/{}", self.group_name, self.name)
     }
 
+    // One-time schedule deletes itself after it's invoked with DELETE action,
+    // so it's gone from remote once its time has passed.
+    pub fn is_completed_at(&self, now: DateTime) -> bool {
+        if self.action_after_completion != ActionAfterCompletion::Delete {
+            return false;
+        }
+
+        let Some(at) = self
+            .schedule_expression
+            .strip_prefix("at(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        else {
+            return false;
+        };
+        let Ok(at) = DateTime::from_str(&format!("{}Z", at), DateTimeFormat::DateTime) else {
+            return false;
+        };
+
+        // Time zone is not resolved, so assume the latest one unless it's UTC.
+        let offset = match self.schedule_expression_timezone.as_deref() {
+            None | Some("UTC") | Some("Etc/UTC") => 0,
+            Some(_) => MAX_OFFSET_BEHIND_UTC_SECS,
+        };
+
+        at.secs() + offset < now.secs()
+    }
+
+    // Values only AWS can give, which config can't use
+    pub fn unknown_values(&self) -> Vec<String> {
+        let mut values = vec![];
+
+        if let ScheduleState::Unknown(value) = &self.state {
+            values.push(format!("state '{}'", value));
+        }
+        if let ActionAfterCompletion::Unknown(value) = &self.action_after_completion {
+            values.push(format!("actionAfterCompletion '{}'", value));
+        }
+        if let Some(FlexibleTimeWindowMode::Unknown(value)) =
+            self.flexible_time_window.as_ref().map(|ftw| &ftw.mode)
+        {
+            values.push(format!("flexibleTimeWindow.mode '{}'", value));
+        }
+
+        let Some(ecs) = &self.target.ecs_parameters else {
+            return values;
+        };
+        if let Some(LaunchType::Unknown(value)) = &ecs.launch_type {
+            values.push(format!("target.ecsParameters.launchType '{}'", value));
+        }
+        if let Some(PropagateTags::Unknown(value)) = &ecs.propagate_tags {
+            values.push(format!("target.ecsParameters.propagateTags '{}'", value));
+        }
+        if let Some(AssignPublicIp::Unknown(value)) = ecs
+            .network_configuration
+            .as_ref()
+            .and_then(|nc| nc.awsvpc_configuration.assign_public_ip.as_ref())
+        {
+            values.push(format!(
+                "target.ecsParameters.networkConfiguration.awsvpcConfiguration.assignPublicIp '{}'",
+                value
+            ));
+        }
+        for constraint in ecs.placement_constraints.iter() {
+            if let Some(PlacementConstraintType::Unknown(value)) = &constraint.r#type {
+                values.push(format!(
+                    "target.ecsParameters.placementConstraints.type '{}'",
+                    value
+                ));
+            }
+        }
+        for strategy in ecs.placement_strategy.iter() {
+            if let Some(PlacementStrategyType::Unknown(value)) = &strategy.r#type {
+                values.push(format!(
+                    "target.ecsParameters.placementStrategy.type '{}'",
+                    value
+                ));
+            }
+        }
+
+        values
+    }
+
+    // GetSchedule response describing this schedule, used by fake clients and to find
+    // unmodeled fields.
+    pub fn to_get_schedule_output(
+        &self,
+        arn: &str,
+    ) -> aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput {
+        aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput::builder()
+            .arn(arn)
+            .group_name(&self.group_name)
+            .name(&self.name)
+            .set_description(self.description.clone())
+            .state(self.state.clone().into())
+            .schedule_expression(&self.schedule_expression)
+            .set_schedule_expression_timezone(self.schedule_expression_timezone.clone())
+            .set_start_date(self.start_date)
+            .set_end_date(self.end_date)
+            .set_flexible_time_window(self.flexible_time_window.clone().map(|v| v.into()))
+            .set_kms_key_arn(self.kms_key_arn.clone())
+            .action_after_completion(self.action_after_completion.clone().into())
+            .target(self.target.clone().into())
+            .build()
+    }
+
     #[cfg(test)]
     pub fn test_default() -> Self {
         Schedule {
@@ -776,6 +952,7 @@ impl Schedule {
             schedule_expression_timezone: Some("UTC".to_string()),
             flexible_time_window: None,
             kms_key_arn: None,
+            action_after_completion: ActionAfterCompletion::None,
             state: ScheduleState::Enabled,
             target: ScheduleTarget {
                 arn: "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld".to_string(),
@@ -803,19 +980,18 @@ fn default_group_name() -> String {
 
 mod datetime_format_as_aws_dt {
     use aws_sdk_sts::primitives::{DateTime, DateTimeFormat};
-    use serde::Deserialize;
+    use serde::{Deserialize, de, ser};
 
-    use crate::fast_exit;
+    use crate::error::FuburaError;
 
     pub fn serialize<S>(date: &Option<DateTime>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
         if let Some(date) = date {
-            let date_str = date.fmt(DateTimeFormat::DateTime).unwrap_or_else(|e| {
-                // XXX: Don't fast_exit here
-                fast_exit!("Fail to parse datetime string {:?}", e);
-            });
+            let date_str = date
+                .fmt(DateTimeFormat::DateTime)
+                .map_err(|e| ser::Error::custom(FuburaError::InvalidDateTime(e.to_string())))?;
 
             serializer.serialize_str(&date_str)
         } else {
@@ -830,10 +1006,8 @@ mod datetime_format_as_aws_dt {
         let s = Option::<String>::deserialize(deserializer)?;
 
         if let Some(s) = s {
-            let s = DateTime::from_str(s.as_str(), DateTimeFormat::DateTime).unwrap_or_else(|e| {
-                // XXX: Don't fast_exit here
-                fast_exit!("Fail to parse datetime string {:?}", e);
-            });
+            let s = DateTime::from_str(s.as_str(), DateTimeFormat::DateTime)
+                .map_err(|e| de::Error::custom(FuburaError::InvalidDateTime(e.to_string())))?;
 
             Ok(Some(s))
         } else {
@@ -842,13 +1016,128 @@ mod datetime_format_as_aws_dt {
     }
 }
 
-impl From<aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput> for Schedule {
-    fn from(value: aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput) -> Self {
+// Empty lists are returned either as empty list or omitted
+fn normalize_target(
+    mut value: aws_sdk_scheduler::types::Target,
+) -> aws_sdk_scheduler::types::Target {
+    fn none_if_empty<T>(list: &mut Option<Vec<T>>) {
+        if list.as_ref().is_some_and(|l| l.is_empty()) {
+            *list = None;
+        }
+    }
+
+    if let Some(ecs) = value.ecs_parameters.as_mut() {
+        none_if_empty(&mut ecs.capacity_provider_strategy);
+        none_if_empty(&mut ecs.placement_constraints);
+        none_if_empty(&mut ecs.placement_strategy);
+        none_if_empty(&mut ecs.tags);
+        if let Some(vpc) = ecs
+            .network_configuration
+            .as_mut()
+            .and_then(|nc| nc.awsvpc_configuration.as_mut())
+        {
+            none_if_empty(&mut vpc.security_groups);
+        }
+    }
+    if let Some(smp) = value.sage_maker_pipeline_parameters.as_mut() {
+        none_if_empty(&mut smp.pipeline_parameter_list);
+    }
+    value
+}
+
+impl Schedule {
+    // Remote fields which are lost on converting to `Schedule`, found by comparing the response
+    // with the one rebuilt from it. They are reset on update, since update sends the whole
+    // configuration.
+    pub fn unmodeled_fields(
+        value: &aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput,
+    ) -> Vec<&'static str> {
+        // Response fubura can't read fails on conversion instead
+        let Ok(schedule) = Schedule::try_from(value.clone()) else {
+            return vec![];
+        };
+        let rebuilt = normalize_get_schedule_output(
+            schedule.to_get_schedule_output(value.arn().unwrap_or("")),
+        );
+        let value = normalize_get_schedule_output(value.clone());
+        if value == rebuilt {
+            return vec![];
+        }
+
+        [
+            ("groupName", value.group_name == rebuilt.group_name),
+            ("name", value.name == rebuilt.name),
+            (
+                "scheduleExpression",
+                value.schedule_expression == rebuilt.schedule_expression,
+            ),
+            ("startDate", value.start_date == rebuilt.start_date),
+            ("endDate", value.end_date == rebuilt.end_date),
+            ("description", value.description == rebuilt.description),
+            (
+                "scheduleExpressionTimezone",
+                value.schedule_expression_timezone == rebuilt.schedule_expression_timezone,
+            ),
+            ("state", value.state == rebuilt.state),
+            ("kmsKeyArn", value.kms_key_arn == rebuilt.kms_key_arn),
+            ("target", value.target == rebuilt.target),
+            (
+                "flexibleTimeWindow",
+                value.flexible_time_window == rebuilt.flexible_time_window,
+            ),
+            (
+                "actionAfterCompletion",
+                value.action_after_completion == rebuilt.action_after_completion,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, same)| !same)
+        .map(|(field, _)| field)
+        .collect()
+    }
+}
+
+// Response is built again to drop its request id, and dates given by AWS are cleared.
+// Omitted group and action after completion are the same as their defaults.
+fn normalize_get_schedule_output(
+    value: aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput,
+) -> aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput {
+    aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput::builder()
+        .set_arn(value.arn)
+        .group_name(value.group_name.unwrap_or_else(default_group_name))
+        .set_name(value.name)
+        .set_schedule_expression(value.schedule_expression)
+        .set_start_date(value.start_date)
+        .set_end_date(value.end_date)
+        .set_description(value.description)
+        .set_schedule_expression_timezone(value.schedule_expression_timezone)
+        .set_state(value.state)
+        .set_kms_key_arn(value.kms_key_arn)
+        .set_target(value.target.map(normalize_target))
+        .set_flexible_time_window(value.flexible_time_window)
+        .action_after_completion(
+            value
+                .action_after_completion
+                .unwrap_or(aws_sdk_scheduler::types::ActionAfterCompletion::None),
+        )
+        .build()
+}
+
+impl TryFrom<aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput> for Schedule {
+    type Error = FuburaError;
+
+    fn try_from(
+        value: aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput,
+    ) -> Result<Self, Self::Error> {
+        let missing = |field| FuburaError::MissingField {
+            resource: "GetSchedule response",
+            field,
+        };
         let flexible_time_window = value.flexible_time_window();
-        let target = value.target().unwrap();
+        let target = value.target().ok_or_else(|| missing("target"))?;
 
-        Schedule {
-            name: value.name().unwrap().to_string(),
+        Ok(Schedule {
+            name: value.name().ok_or_else(|| missing("name"))?.to_string(),
             description: value.description().map(|s| s.to_string()),
             start_date: value.start_date().copied(),
             end_date: value.end_date().copied(),
@@ -858,12 +1147,151 @@ impl From<aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput> for Sch
                 .group_name()
                 .map_or(default_group_name(), |v| v.to_string()),
             kms_key_arn: value.kms_key_arn().map(|s| s.to_string()),
-            schedule_expression: value.schedule_expression().unwrap().to_string(),
+            action_after_completion: value
+                .action_after_completion()
+                .map(|a| ActionAfterCompletion::from(a.clone()))
+                .unwrap_or_default(),
+            schedule_expression: value
+                .schedule_expression()
+                .ok_or_else(|| missing("schedule_expression"))?
+                .to_string(),
             schedule_expression_timezone: value
                 .schedule_expression_timezone()
                 .map(|s| s.to_string()),
             target: ScheduleTarget::from(target.clone()),
-            state: ScheduleState::from(value.state().unwrap().clone()),
-        }
+            state: ScheduleState::from(value.state().ok_or_else(|| missing("state"))?.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use aws_sdk_scheduler::operation::get_schedule::GetScheduleOutput;
+
+    #[test]
+    fn test_unmodeled_fields() {
+        let mut schedule = Schedule::test_default();
+        schedule.target.ecs_parameters = Some(EcsParameters {
+            task_definition_arn: "arn:aws:ecs:us-west-2:123456789012:task-definition/batch:1"
+                .to_string(),
+            capacity_provider_strategy: vec![],
+            enable_ecs_managed_tags: None,
+            enable_execute_command: None,
+            group: None,
+            launch_type: None,
+            network_configuration: None,
+            placement_constraints: vec![],
+            placement_strategy: vec![],
+            platform_version: None,
+            propagate_tags: None,
+            reference_id: None,
+            tags: vec![],
+            task_count: Some(1),
+        });
+        let arn = "arn:aws:scheduler:us-west-2:123456789012:schedule/default/HelloWorld";
+
+        let output = schedule.to_get_schedule_output(arn);
+        assert!(Schedule::unmodeled_fields(&output).is_empty());
+
+        // Empty lists returned by AWS are the same as omitted ones
+        let mut target = output.target().unwrap().clone();
+        let ecs_parameters = target.ecs_parameters.as_mut().unwrap();
+        ecs_parameters.tags = Some(vec![]);
+        ecs_parameters.placement_constraints = Some(vec![]);
+        let output = GetScheduleOutput::builder()
+            .name(&schedule.name)
+            .schedule_expression(&schedule.schedule_expression)
+            .state(schedule.state.clone().into())
+            .target(target.clone())
+            .build();
+        assert!(Schedule::unmodeled_fields(&output).is_empty());
+
+        // Entries other than key and value are dropped in ResourceTag
+        let mut tags = std::collections::HashMap::new();
+        tags.insert("key".to_string(), "Team".to_string());
+        tags.insert("value".to_string(), "Batch".to_string());
+        tags.insert("propagate".to_string(), "true".to_string());
+        target.ecs_parameters.as_mut().unwrap().tags = Some(vec![tags]);
+        let output = GetScheduleOutput::builder()
+            .name(&schedule.name)
+            .schedule_expression(&schedule.schedule_expression)
+            .state(schedule.state.clone().into())
+            .target(target)
+            .build();
+        assert_eq!(vec!["target"], Schedule::unmodeled_fields(&output));
+    }
+
+    #[test]
+    fn test_unknown_variant_round_trip() {
+        let actual: ScheduleState = serde_json::from_value(serde_json::json!("PAUSED")).unwrap();
+        assert_eq!(ScheduleState::Unknown("PAUSED".to_string()), actual);
+        assert_eq!(
+            serde_json::json!("PAUSED"),
+            serde_json::to_value(&actual).unwrap()
+        );
+
+        let aws: aws_sdk_scheduler::types::ScheduleState = actual.clone().into();
+        assert_eq!(actual, ScheduleState::from(aws));
+    }
+
+    #[test]
+    fn test_action_after_completion_round_trip() {
+        let mut schedule = Schedule::test_default();
+        schedule.action_after_completion = ActionAfterCompletion::Delete;
+
+        let output = schedule.to_get_schedule_output("arn");
+        similar_asserts::assert_eq!(Ok(schedule), Schedule::try_from(output));
+
+        // omitted in config
+        let mut value = serde_json::to_value(Schedule::test_default()).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .remove("actionAfterCompletion");
+        let actual: Schedule = serde_json::from_value(value).unwrap();
+        assert_eq!(ActionAfterCompletion::None, actual.action_after_completion);
+    }
+
+    #[test]
+    fn test_is_completed_at() {
+        let now = DateTime::from_str("2024-06-01T00:00:00Z", DateTimeFormat::DateTime).unwrap();
+        let mut schedule = Schedule::test_default();
+        schedule.schedule_expression = "at(2024-05-31T00:00:00)".to_string();
+
+        assert!(!schedule.is_completed_at(now));
+
+        schedule.action_after_completion = ActionAfterCompletion::Delete;
+        assert!(schedule.is_completed_at(now));
+
+        schedule.schedule_expression = "at(2024-06-01T09:00:00)".to_string();
+        assert!(!schedule.is_completed_at(now));
+
+        // assumed to be in the latest time zone
+        schedule.schedule_expression = "at(2024-05-31T13:00:00)".to_string();
+        schedule.schedule_expression_timezone = Some("Asia/Tokyo".to_string());
+        assert!(!schedule.is_completed_at(now));
+
+        schedule.schedule_expression = "rate(1 minute)".to_string();
+        assert!(!schedule.is_completed_at(now));
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let schedule = Schedule::test_default();
+        let output = GetScheduleOutput::builder()
+            .name(&schedule.name)
+            .schedule_expression(&schedule.schedule_expression)
+            .state(schedule.state.clone().into())
+            .build();
+
+        assert_eq!(
+            Err(FuburaError::MissingField {
+                resource: "GetSchedule response",
+                field: "target",
+            }),
+            Schedule::try_from(output)
+        );
     }
 }
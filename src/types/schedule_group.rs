@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use super::{DeployTarget, ResourceTag};
+
+// Group which always exists and is managed by AWS
+pub const DEFAULT_SCHEDULE_GROUP_NAME: &str = "default";
+
+fn default_delete_flag() -> bool {
+    false
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleGroup {
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<ResourceTag>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleGroupConfig {
+    pub group: ScheduleGroup,
+    // If true, delete the group. Schedules in it must be deleted before.
+    #[serde(default = "default_delete_flag")]
+    pub delete: bool,
+    pub deploy_target: Option<DeployTarget>,
+}
+
+impl ScheduleGroup {
+    #[cfg(test)]
+    pub fn test_default() -> Self {
+        ScheduleGroup {
+            name: "batch".to_string(),
+            tags: vec![ResourceTag {
+                key: "Team".to_string(),
+                value: "Batch".to_string(),
+            }],
+        }
+    }
+}
@@ -1,18 +1,237 @@
 use serde::{Deserialize, Serialize};
 
-use super::{Schedule, StateMachine};
+use super::{ArnBuilder, DeployTarget, Rule, Schedule, StateMachine};
 
 fn default_delete_flag() -> bool {
     false
 }
 
+fn default_protect_flag() -> bool {
+    false
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleConfig {
+    #[serde(flatten)]
+    pub schedule: Schedule,
+    // If true, delete the schedule
+    #[serde(default = "default_delete_flag")]
+    pub delete: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleConfig {
+    #[serde(flatten)]
+    pub rule: Rule,
+    // If true, delete the rule
+    #[serde(default = "default_delete_flag")]
+    pub delete: bool,
+}
+
+// Schedule which doesn't target a state machine managed by fubura, e.g. Lambda or SQS.
+// Keyed by "group/name" instead of state name.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StandaloneScheduleConfig {
+    pub schedule: Schedule,
+    #[serde(default = "default_delete_flag")]
+    pub delete: bool,
+    // If true, fubura refuses to delete the schedule.
+    #[serde(default = "default_protect_flag")]
+    pub protect: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deploy_target: Option<DeployTarget>,
+}
+
+impl StandaloneScheduleConfig {
+    pub fn schedule_name_with_group(&self) -> String {
+        self.schedule.schedule_name_with_group()
+    }
+
+    pub fn schedule_config(&self) -> ScheduleConfig {
+        ScheduleConfig {
+            schedule: self.schedule.clone(),
+            delete: self.delete,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SsConfig {
     pub state: StateMachine,
+    // Shorthand of a single entry of schedules
     pub schedule: Option<Schedule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<ScheduleConfig>,
+    // EventBridge rules which start the state machine
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<RuleConfig>,
     #[serde(default = "default_delete_flag")]
     pub delete_all: bool,
+    // Same as delete flag of schedule given by the shorthand
     #[serde(default = "default_delete_flag")]
     pub delete_schedule: bool,
+    // If true, fubura refuses to delete any resource of this config.
+    #[serde(default = "default_protect_flag")]
+    pub protect: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deploy_target: Option<DeployTarget>,
+}
+
+impl SsConfig {
+    // All schedules of this config, the shorthand first.
+    pub fn schedule_configs(&self) -> Vec<ScheduleConfig> {
+        let shorthand = self.schedule.as_ref().map(|schedule| ScheduleConfig {
+            schedule: schedule.clone(),
+            delete: self.delete_schedule,
+        });
+
+        shorthand
+            .into_iter()
+            .chain(self.schedules.iter().cloned())
+            .collect()
+    }
+
+    // Schedule by "group/name"
+    pub fn find_schedule(&self, schedule_name_with_group: &str) -> Option<Schedule> {
+        self.schedule_configs()
+            .into_iter()
+            .map(|schedule_config| schedule_config.schedule)
+            .find(|schedule| schedule.schedule_name_with_group() == schedule_name_with_group)
+    }
+
+    // Rules of this config, whose target points to the given state machine unless specified.
+    pub fn rule_configs(&self, state_arn: &str) -> Vec<RuleConfig> {
+        self.rules
+            .iter()
+            .cloned()
+            .map(|mut rule_config| {
+                if let Some(target) = rule_config.rule.target.as_mut()
+                    && target.arn.is_none()
+                {
+                    target.arn = Some(state_arn.to_string());
+                }
+                rule_config
+            })
+            .collect()
+    }
+
+    // Same config whose state logs to the managed log group, if any
+    pub fn with_log_group_arn(&self, arn_builder: &ArnBuilder) -> SsConfig {
+        SsConfig {
+            state: self.state.with_log_group_arn(arn_builder),
+            ..self.clone()
+        }
+    }
+
+    // Rule by "bus/name"
+    pub fn find_rule(&self, rule_name_with_bus: &str, state_arn: &str) -> Option<Rule> {
+        self.rule_configs(state_arn)
+            .into_iter()
+            .map(|rule_config| rule_config.rule)
+            .find(|rule| rule.rule_name_with_bus() == rule_name_with_bus)
+    }
+
+    #[cfg(test)]
+    pub fn test_default() -> Self {
+        SsConfig {
+            state: StateMachine::test_default(),
+            schedule: None,
+            schedules: vec![],
+            rules: vec![],
+            delete_all: false,
+            delete_schedule: false,
+            protect: false,
+            deploy_target: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_schedule_configs() {
+        let mut weekend = serde_json::to_value(Schedule::test_default()).unwrap();
+        weekend["name"] = serde_json::json!("HelloWorldOnWeekend");
+        weekend["delete"] = serde_json::json!(true);
+
+        let ss_config: SsConfig = serde_json::from_value(serde_json::json!({
+            "state": StateMachine::test_default(),
+            "schedule": Schedule::test_default(),
+            "schedules": [weekend],
+        }))
+        .unwrap();
+
+        let actual: Vec<(String, bool)> = ss_config
+            .schedule_configs()
+            .iter()
+            .map(|sc| (sc.schedule.schedule_name_with_group(), sc.delete))
+            .collect();
+        assert_eq!(
+            vec![
+                ("default/HelloWorld".to_string(), false),
+                ("default/HelloWorldOnWeekend".to_string(), true),
+            ],
+            actual
+        );
+        assert_eq!(
+            "HelloWorldOnWeekend",
+            ss_config
+                .find_schedule("default/HelloWorldOnWeekend")
+                .unwrap()
+                .name
+        );
+        // deployTarget is omitted, like in imported config
+        assert!(
+            serde_json::to_value(&ss_config)
+                .unwrap()
+                .get("deployTarget")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_rule_configs() {
+        let state_arn = "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld";
+        let mut on_upload = serde_json::to_value(Rule::test_default()).unwrap();
+        on_upload["target"].as_object_mut().unwrap().remove("arn");
+        let mut on_alias = serde_json::to_value(Rule::test_default()).unwrap();
+        on_alias["name"] = serde_json::json!("HelloWorldLiveOnUpload");
+        on_alias["target"]["arn"] = serde_json::json!(format!("{}:live", state_arn));
+
+        let ss_config: SsConfig = serde_json::from_value(serde_json::json!({
+            "state": StateMachine::test_default(),
+            "rules": [on_upload, on_alias],
+            "deployTarget": null,
+        }))
+        .unwrap();
+
+        let actual: Vec<(String, Option<String>)> = ss_config
+            .rule_configs(state_arn)
+            .into_iter()
+            .map(|rc| (rc.rule.rule_name_with_bus(), rc.rule.target.unwrap().arn))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "default/HelloWorldOnUpload".to_string(),
+                    Some(state_arn.to_string())
+                ),
+                (
+                    "default/HelloWorldLiveOnUpload".to_string(),
+                    Some(format!("{}:live", state_arn))
+                ),
+            ],
+            actual
+        );
+        assert_eq!(
+            Some(Rule::test_default()),
+            ss_config.find_rule("default/HelloWorldOnUpload", state_arn)
+        );
+    }
 }
@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::fast_exit;
+use crate::error::FuburaError;
 
-use super::ResourceTag;
+use super::{ArnBuilder, LogGroup, ResourceTag, StateMachineAlias};
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -19,16 +19,17 @@ impl From<aws_sdk_sfn::types::CloudWatchLogsLogGroup> for CloudWatchLogsLogGroup
     }
 }
 
-impl From<CloudWatchLogsLogGroup> for aws_sdk_sfn::types::CloudWatchLogsLogGroup {
-    fn from(value: CloudWatchLogsLogGroup) -> Self {
-        // XXX: Don't fast_exit here
-        let log_group_arn = value
-            .log_group_arn
-            .unwrap_or_else(|| fast_exit!("log_group_arn is required for CloudWatchLogsLogGroup"));
+impl TryFrom<CloudWatchLogsLogGroup> for aws_sdk_sfn::types::CloudWatchLogsLogGroup {
+    type Error = FuburaError;
 
-        aws_sdk_sfn::types::builders::CloudWatchLogsLogGroupBuilder::default()
-            .log_group_arn(log_group_arn)
-            .build()
+    fn try_from(value: CloudWatchLogsLogGroup) -> Result<Self, Self::Error> {
+        let log_group_arn = value.log_group_arn.ok_or(FuburaError::MissingLogGroupArn)?;
+
+        Ok(
+            aws_sdk_sfn::types::builders::CloudWatchLogsLogGroupBuilder::default()
+                .log_group_arn(log_group_arn)
+                .build(),
+        )
     }
 }
 
@@ -48,15 +49,17 @@ impl From<aws_sdk_sfn::types::LogDestination> for LogDestination {
     }
 }
 
-impl From<LogDestination> for aws_sdk_sfn::types::LogDestination {
-    fn from(value: LogDestination) -> Self {
+impl TryFrom<LogDestination> for aws_sdk_sfn::types::LogDestination {
+    type Error = FuburaError;
+
+    fn try_from(value: LogDestination) -> Result<Self, Self::Error> {
         let mut builder = aws_sdk_sfn::types::builders::LogDestinationBuilder::default();
 
         if let Some(cloud_watch_logs_log_group) = value.cloud_watch_logs_log_group {
-            builder = builder.cloud_watch_logs_log_group(cloud_watch_logs_log_group.into())
+            builder = builder.cloud_watch_logs_log_group(cloud_watch_logs_log_group.try_into()?)
         }
 
-        builder.build()
+        Ok(builder.build())
     }
 }
 
@@ -70,17 +73,18 @@ pub enum LogLevel {
     Fatal,
     #[serde(rename = "OFF")]
     Off,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_sfn::types::LogLevel> for LogLevel {
     fn from(value: aws_sdk_sfn::types::LogLevel) -> Self {
-        // XXX: Don't fast_exit here
         match value {
             aws_sdk_sfn::types::LogLevel::All => LogLevel::All,
             aws_sdk_sfn::types::LogLevel::Error => LogLevel::Error,
             aws_sdk_sfn::types::LogLevel::Fatal => LogLevel::Fatal,
             aws_sdk_sfn::types::LogLevel::Off => LogLevel::Off,
-            _ => fast_exit!("unknown log level: {:?}", value),
+            other => LogLevel::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -92,6 +96,7 @@ impl From<LogLevel> for aws_sdk_sfn::types::LogLevel {
             LogLevel::Error => aws_sdk_sfn::types::LogLevel::Error,
             LogLevel::Fatal => aws_sdk_sfn::types::LogLevel::Fatal,
             LogLevel::Off => aws_sdk_sfn::types::LogLevel::Off,
+            LogLevel::Unknown(value) => aws_sdk_sfn::types::LogLevel::from(value.as_str()),
         }
     }
 }
@@ -119,17 +124,20 @@ impl From<aws_sdk_sfn::types::LoggingConfiguration> for LoggingConfiguration {
     }
 }
 
-impl From<LoggingConfiguration> for aws_sdk_sfn::types::LoggingConfiguration {
-    fn from(value: LoggingConfiguration) -> Self {
+impl TryFrom<LoggingConfiguration> for aws_sdk_sfn::types::LoggingConfiguration {
+    type Error = FuburaError;
+
+    fn try_from(value: LoggingConfiguration) -> Result<Self, Self::Error> {
         let mut builder = aws_sdk_sfn::types::builders::LoggingConfigurationBuilder::default();
 
         if value.destinations.len() > 1 {
-            // XXX: Don't fast_exit here
-            fast_exit!("destinations size is limited to 1.");
+            return Err(FuburaError::TooManyLogDestinations(
+                value.destinations.len(),
+            ));
         }
 
         if let Some(destination) = value.destinations.first() {
-            builder = builder.destinations(destination.clone().into());
+            builder = builder.destinations(destination.clone().try_into()?);
         }
 
         if let Some(level) = value.level {
@@ -140,7 +148,7 @@ impl From<LoggingConfiguration> for aws_sdk_sfn::types::LoggingConfiguration {
             builder = builder.include_execution_data(include_execution_data);
         }
 
-        builder.build()
+        Ok(builder.build())
     }
 }
 
@@ -165,12 +173,91 @@ impl From<TracingConfiguration> for aws_sdk_sfn::types::TracingConfiguration {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+pub enum EncryptionType {
+    #[serde(rename = "AWS_OWNED_KEY")]
+    AwsOwnedKey,
+    #[serde(rename = "CUSTOMER_MANAGED_KMS_KEY")]
+    CustomerManagedKmsKey,
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl From<aws_sdk_sfn::types::EncryptionType> for EncryptionType {
+    fn from(value: aws_sdk_sfn::types::EncryptionType) -> Self {
+        match value {
+            aws_sdk_sfn::types::EncryptionType::AwsOwnedKey => EncryptionType::AwsOwnedKey,
+            aws_sdk_sfn::types::EncryptionType::CustomerManagedKmsKey => {
+                EncryptionType::CustomerManagedKmsKey
+            }
+            other => EncryptionType::Unknown(other.as_str().to_string()),
+        }
+    }
+}
+
+impl From<EncryptionType> for aws_sdk_sfn::types::EncryptionType {
+    fn from(value: EncryptionType) -> Self {
+        match value {
+            EncryptionType::AwsOwnedKey => aws_sdk_sfn::types::EncryptionType::AwsOwnedKey,
+            EncryptionType::CustomerManagedKmsKey => {
+                aws_sdk_sfn::types::EncryptionType::CustomerManagedKmsKey
+            }
+            EncryptionType::Unknown(value) => {
+                aws_sdk_sfn::types::EncryptionType::from(value.as_str())
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionConfiguration {
+    pub kms_key_id: Option<String>,
+    pub kms_data_key_reuse_period_seconds: Option<i32>,
+    pub r#type: EncryptionType,
+}
+
+impl EncryptionConfiguration {
+    // Encryption by AWS owned key, which is used when encryption is not configured
+    pub fn aws_owned_key() -> Self {
+        EncryptionConfiguration {
+            kms_key_id: None,
+            kms_data_key_reuse_period_seconds: None,
+            r#type: EncryptionType::AwsOwnedKey,
+        }
+    }
+}
+
+impl From<aws_sdk_sfn::types::EncryptionConfiguration> for EncryptionConfiguration {
+    fn from(value: aws_sdk_sfn::types::EncryptionConfiguration) -> Self {
+        EncryptionConfiguration {
+            kms_key_id: value.kms_key_id().map(|k| k.to_string()),
+            kms_data_key_reuse_period_seconds: value.kms_data_key_reuse_period_seconds(),
+            r#type: EncryptionType::from(value.r#type().clone()),
+        }
+    }
+}
+
+impl From<EncryptionConfiguration> for aws_sdk_sfn::types::EncryptionConfiguration {
+    fn from(value: EncryptionConfiguration) -> Self {
+        aws_sdk_sfn::types::builders::EncryptionConfigurationBuilder::default()
+            .set_kms_key_id(value.kms_key_id)
+            .set_kms_data_key_reuse_period_seconds(value.kms_data_key_reuse_period_seconds)
+            .r#type(value.r#type.into())
+            .build()
+            // type, the only required field, is always set
+            .unwrap()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 pub enum StateMachineType {
     #[serde(rename = "STANDARD")]
     Standard,
     #[serde(rename = "EXPRESS")]
     Express,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl From<aws_sdk_sfn::types::StateMachineType> for StateMachineType {
@@ -178,8 +265,7 @@ impl From<aws_sdk_sfn::types::StateMachineType> for StateMachineType {
         match value {
             aws_sdk_sfn::types::StateMachineType::Standard => StateMachineType::Standard,
             aws_sdk_sfn::types::StateMachineType::Express => StateMachineType::Express,
-            // XXX: Don't fast_exit here
-            _ => fast_exit!("unknown state machine type: {:?}", value),
+            other => StateMachineType::Unknown(other.as_str().to_string()),
         }
     }
 }
@@ -189,6 +275,9 @@ impl From<StateMachineType> for aws_sdk_sfn::types::StateMachineType {
         match value {
             StateMachineType::Standard => aws_sdk_sfn::types::StateMachineType::Standard,
             StateMachineType::Express => aws_sdk_sfn::types::StateMachineType::Express,
+            StateMachineType::Unknown(value) => {
+                aws_sdk_sfn::types::StateMachineType::from(value.as_str())
+            }
         }
     }
 }
@@ -202,10 +291,119 @@ pub struct StateMachine {
     pub r#type: StateMachineType,
     pub logging_configuration: Option<LoggingConfiguration>,
     pub tracing_configuration: Option<TracingConfiguration>,
+    // Omitted for AWS owned key, which is the default of Step Functions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_configuration: Option<EncryptionConfiguration>,
+    // Log group is managed only if given, and created or updated by its own api.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_group: Option<LogGroup>,
     pub tags: Vec<ResourceTag>,
+    // Aliases are managed only if given, and remote aliases missing here are deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aliases: Option<Vec<StateMachineAlias>>,
+    // Publish a version on create or update. Not a part of remote state, so never diffed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub publish: bool,
+    // Description of published version, e.g. built with git sha by jsonnet ext-str
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_description: Option<String>,
 }
 
+// State machine tagged with this key and "true" value can't be deleted by fubura.
+pub const PROTECTION_TAG_KEY: &str = "fubura:protect";
+
 impl StateMachine {
+    // Values only AWS can give, which config can't use
+    pub fn unknown_values(&self) -> Vec<String> {
+        let mut values = vec![];
+
+        if let StateMachineType::Unknown(value) = &self.r#type {
+            values.push(format!("type '{}'", value));
+        }
+        if let Some(LogLevel::Unknown(value)) = self
+            .logging_configuration
+            .as_ref()
+            .and_then(|lc| lc.level.as_ref())
+        {
+            values.push(format!("loggingConfiguration.level '{}'", value));
+        }
+        if let Some(EncryptionType::Unknown(value)) =
+            self.encryption_configuration.as_ref().map(|ec| &ec.r#type)
+        {
+            values.push(format!("encryptionConfiguration.type '{}'", value));
+        }
+
+        values
+    }
+
+    pub fn is_protected_by_tag(&self) -> bool {
+        self.tags
+            .iter()
+            .any(|tag| tag.key == PROTECTION_TAG_KEY && tag.value == "true")
+    }
+
+    // Log destinations without logGroupArn point to the managed log group.
+    // A destination is added if logging configuration has none.
+    pub fn with_log_group_arn(&self, arn_builder: &ArnBuilder) -> StateMachine {
+        let mut state = self.clone();
+        let (Some(log_group), Some(logging_configuration)) =
+            (&state.log_group, state.logging_configuration.as_mut())
+        else {
+            return state;
+        };
+
+        // Step Functions requires ":*" suffix
+        let log_group_arn = format!("{}:*", arn_builder.log_group(&log_group.name));
+        if logging_configuration.destinations.is_empty() {
+            logging_configuration.destinations.push(LogDestination {
+                cloud_watch_logs_log_group: None,
+            });
+        }
+        for destination in logging_configuration.destinations.iter_mut() {
+            let cloud_watch_logs_log_group =
+                destination
+                    .cloud_watch_logs_log_group
+                    .get_or_insert(CloudWatchLogsLogGroup {
+                        log_group_arn: None,
+                    });
+            cloud_watch_logs_log_group
+                .log_group_arn
+                .get_or_insert(log_group_arn.clone());
+        }
+
+        state
+    }
+
+    // DescribeStateMachine response describing this state machine, used by fake clients and
+    // to find unmodeled fields.
+    pub fn to_describe_state_machine_output(
+        &self,
+        state_arn: &str,
+        creation_date: aws_sdk_sfn::primitives::DateTime,
+    ) -> aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput {
+        aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput::builder()
+            .state_machine_arn(state_arn)
+            .name(&self.name)
+            .definition(serde_json::to_string(&self.definition).unwrap())
+            .role_arn(&self.role_arn)
+            .r#type(self.r#type.clone().into())
+            .set_logging_configuration(
+                self.logging_configuration
+                    .clone()
+                    .and_then(|lc| lc.try_into().ok()),
+            )
+            .set_tracing_configuration(self.tracing_configuration.clone().map(|tc| tc.into()))
+            .encryption_configuration(
+                self.encryption_configuration
+                    .clone()
+                    .unwrap_or_else(EncryptionConfiguration::aws_owned_key)
+                    .into(),
+            )
+            .creation_date(creation_date)
+            .build()
+            .unwrap()
+    }
+
     #[cfg(test)]
     pub fn test_default() -> Self {
         StateMachine {
@@ -228,6 +426,8 @@ impl StateMachine {
                 }],
             }),
             tracing_configuration: None,
+            encryption_configuration: None,
+            log_group: None,
             tags: vec![
                 ResourceTag {
                     key: "Env".to_string(),
@@ -238,19 +438,123 @@ impl StateMachine {
                     value: "HelloWorld".to_string(),
                 },
             ],
+            aliases: None,
+            publish: false,
+            version_description: None,
         }
     }
 }
 
-impl From<aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput>
+// Empty destinations are returned either as empty list or omitted
+fn normalize_logging_configuration(
+    mut value: aws_sdk_sfn::types::LoggingConfiguration,
+) -> aws_sdk_sfn::types::LoggingConfiguration {
+    if value.destinations.as_ref().is_some_and(|d| d.is_empty()) {
+        value.destinations = None;
+    }
+    value
+}
+
+impl StateMachine {
+    // Remote fields which are lost on converting to `StateMachine`, found by comparing the
+    // response with the one rebuilt from it. They are reset on update, since update sends the
+    // whole configuration.
+    pub fn unmodeled_fields(
+        value: &aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput,
+    ) -> Vec<&'static str> {
+        // Response fubura can't read fails on conversion instead
+        let Ok(state) = StateMachine::try_from(value.clone()) else {
+            return vec![];
+        };
+        let rebuilt =
+            normalize_describe_state_machine_output(state.to_describe_state_machine_output(
+                value.state_machine_arn(),
+                *value.creation_date(),
+            ));
+        let value = normalize_describe_state_machine_output(value.clone());
+        if value == rebuilt {
+            return vec![];
+        }
+
+        [
+            ("name", value.name == rebuilt.name),
+            ("definition", value.definition == rebuilt.definition),
+            ("roleArn", value.role_arn == rebuilt.role_arn),
+            ("type", value.r#type == rebuilt.r#type),
+            (
+                "loggingConfiguration",
+                value.logging_configuration == rebuilt.logging_configuration,
+            ),
+            (
+                "tracingConfiguration",
+                value.tracing_configuration == rebuilt.tracing_configuration,
+            ),
+            ("label", value.label == rebuilt.label),
+            ("description", value.description == rebuilt.description),
+            (
+                "encryptionConfiguration",
+                value.encryption_configuration == rebuilt.encryption_configuration,
+            ),
+            (
+                "variableReferences",
+                value.variable_references == rebuilt.variable_references,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, same)| !same)
+        .map(|(field, _)| field)
+        .collect()
+    }
+}
+
+// Response is built again to drop its request id, and status and revision given by AWS are
+// cleared. Formatting of definition is not kept, and omitted encryption configuration is the
+// same as AWS owned key.
+fn normalize_describe_state_machine_output(
+    value: aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput,
+) -> aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput {
+    let definition = serde_json::from_str::<Value>(&value.definition)
+        .map(|definition| definition.to_string())
+        .unwrap_or(value.definition);
+    let encryption_configuration = value
+        .encryption_configuration
+        .unwrap_or_else(|| EncryptionConfiguration::aws_owned_key().into());
+
+    aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput::builder()
+        .state_machine_arn(value.state_machine_arn)
+        .name(value.name)
+        .definition(definition)
+        .role_arn(value.role_arn)
+        .r#type(value.r#type)
+        .creation_date(value.creation_date)
+        .set_logging_configuration(
+            value
+                .logging_configuration
+                .map(normalize_logging_configuration),
+        )
+        .set_tracing_configuration(value.tracing_configuration)
+        .set_label(value.label)
+        .set_description(value.description)
+        .encryption_configuration(encryption_configuration)
+        .set_variable_references(value.variable_references)
+        .build()
+        .unwrap()
+}
+
+impl TryFrom<aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput>
     for StateMachine
 {
-    fn from(
+    type Error = FuburaError;
+
+    fn try_from(
         value: aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput,
-    ) -> Self {
-        StateMachine {
+    ) -> Result<Self, Self::Error> {
+        let definition = serde_json::from_str(value.definition())
+            .map_err(|e| FuburaError::InvalidDefinition(e.to_string()))?;
+
+        Ok(StateMachine {
             name: value.name().to_string(),
-            definition: serde_json::from_str(value.definition()).unwrap(),
+            definition,
             role_arn: value.role_arn().to_string(),
             r#type: StateMachineType::from(value.r#type().clone()),
             logging_configuration: value
@@ -259,7 +563,206 @@ impl From<aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOu
             tracing_configuration: value
                 .tracing_configuration()
                 .map(|tc| TracingConfiguration::from(tc.clone())),
+            encryption_configuration: value
+                .encryption_configuration()
+                .map(|ec| EncryptionConfiguration::from(ec.clone()))
+                .filter(|ec| *ec != EncryptionConfiguration::aws_owned_key()),
+            log_group: None,
             tags: vec![],
-        }
+            aliases: None,
+            publish: false,
+            version_description: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::types::Partition;
+
+    #[test]
+    fn test_unknown_variant_round_trip() {
+        let actual: StateMachineType = serde_json::from_value(serde_json::json!("FAST")).unwrap();
+        assert_eq!(StateMachineType::Unknown("FAST".to_string()), actual);
+        assert_eq!(
+            serde_json::json!("FAST"),
+            serde_json::to_value(&actual).unwrap()
+        );
+
+        let aws: aws_sdk_sfn::types::StateMachineType = actual.clone().into();
+        assert_eq!(actual, StateMachineType::from(aws));
+    }
+
+    #[test]
+    fn test_too_many_log_destinations() {
+        let mut logging_configuration = StateMachine::test_default().logging_configuration.unwrap();
+        logging_configuration
+            .destinations
+            .push(logging_configuration.destinations[0].clone());
+
+        let actual = aws_sdk_sfn::types::LoggingConfiguration::try_from(logging_configuration);
+        assert_eq!(
+            Err(FuburaError::TooManyLogDestinations(2)),
+            actual.map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_invalid_definition() {
+        let output =
+            aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput::builder()
+                .state_machine_arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
+                .name("HelloWorld")
+                .definition("{")
+                .role_arn("arn:aws:iam::123456789012:role/service-role/HelloWorldRole")
+                .r#type(aws_sdk_sfn::types::StateMachineType::Standard)
+                .creation_date(aws_sdk_sfn::primitives::DateTime::from_secs(0))
+                .build()
+                .unwrap();
+
+        let actual = StateMachine::try_from(output);
+        assert!(matches!(actual, Err(FuburaError::InvalidDefinition(_))));
+    }
+
+    #[test]
+    fn test_unmodeled_fields() {
+        let state = StateMachine::test_default();
+        let logging_configuration: aws_sdk_sfn::types::LoggingConfiguration = state
+            .logging_configuration
+            .clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let builder =
+            aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput::builder()
+                .state_machine_arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
+                .name("HelloWorld")
+                .definition("{}")
+                .role_arn("arn:aws:iam::123456789012:role/service-role/HelloWorldRole")
+                .r#type(aws_sdk_sfn::types::StateMachineType::Standard)
+                .creation_date(aws_sdk_sfn::primitives::DateTime::from_secs(0))
+                .tracing_configuration(TracingConfiguration { enabled: true }.into());
+
+        let output = builder
+            .clone()
+            .logging_configuration(logging_configuration.clone())
+            .build()
+            .unwrap();
+        assert!(StateMachine::unmodeled_fields(&output).is_empty());
+
+        // Formatting of definition is not kept
+        let output = builder
+            .clone()
+            .definition("{\n  \"StartAt\": \"FirstState\"\n}")
+            .logging_configuration(logging_configuration.clone())
+            .build()
+            .unwrap();
+        assert!(StateMachine::unmodeled_fields(&output).is_empty());
+
+        // Status and revision are given by AWS
+        let output = builder
+            .clone()
+            .logging_configuration(logging_configuration.clone())
+            .status(aws_sdk_sfn::types::StateMachineStatus::Active)
+            .revision_id("1")
+            .build()
+            .unwrap();
+        assert!(StateMachine::unmodeled_fields(&output).is_empty());
+
+        let output = builder
+            .clone()
+            .logging_configuration(logging_configuration.clone())
+            .label("live")
+            .description("v1")
+            .variable_references("FirstState", vec!["$name".to_string()])
+            .build()
+            .unwrap();
+        assert_eq!(
+            vec!["label", "description", "variableReferences"],
+            StateMachine::unmodeled_fields(&output)
+        );
+
+        // Only a single destination is modeled
+        let destination = logging_configuration.destinations()[0].clone();
+        let output = builder
+            .logging_configuration(
+                aws_sdk_sfn::types::LoggingConfiguration::builder()
+                    .destinations(destination.clone())
+                    .destinations(destination)
+                    .build(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(
+            vec!["loggingConfiguration"],
+            StateMachine::unmodeled_fields(&output)
+        );
+    }
+
+    #[test]
+    fn test_encryption_configuration() {
+        let builder =
+            aws_sdk_sfn::operation::describe_state_machine::DescribeStateMachineOutput::builder()
+                .state_machine_arn("arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld")
+                .name("HelloWorld")
+                .definition("{}")
+                .role_arn("arn:aws:iam::123456789012:role/service-role/HelloWorldRole")
+                .r#type(aws_sdk_sfn::types::StateMachineType::Standard)
+                .creation_date(aws_sdk_sfn::primitives::DateTime::from_secs(0));
+        let encryption_configuration = EncryptionConfiguration {
+            kms_key_id: Some("alias/fubura".to_string()),
+            kms_data_key_reuse_period_seconds: Some(300),
+            r#type: EncryptionType::CustomerManagedKmsKey,
+        };
+
+        let output = builder
+            .clone()
+            .encryption_configuration(encryption_configuration.clone().into())
+            .build()
+            .unwrap();
+        let actual = StateMachine::try_from(output).unwrap();
+        assert_eq!(
+            Some(encryption_configuration),
+            actual.encryption_configuration
+        );
+
+        // AWS owned key is the same as no encryption configuration
+        let output = builder
+            .encryption_configuration(EncryptionConfiguration::aws_owned_key().into())
+            .build()
+            .unwrap();
+        let actual = StateMachine::try_from(output).unwrap();
+        assert_eq!(None, actual.encryption_configuration);
+    }
+
+    #[test]
+    fn test_with_log_group_arn() {
+        let arn_builder = ArnBuilder::new(Partition::Aws, "us-west-2", "123456789012");
+        let log_group_arn = "arn:aws:logs:us-west-2:123456789012:log-group:HelloWorldLogGroup:*";
+
+        // Unchanged unless log group is managed
+        let mut state = StateMachine::test_default();
+        state.logging_configuration.as_mut().unwrap().destinations = vec![];
+        similar_asserts::assert_eq!(state.clone(), state.with_log_group_arn(&arn_builder));
+
+        state.log_group = Some(LogGroup::test_default());
+        let actual = state.with_log_group_arn(&arn_builder);
+        similar_asserts::assert_eq!(
+            vec![LogDestination {
+                cloud_watch_logs_log_group: Some(CloudWatchLogsLogGroup {
+                    log_group_arn: Some(log_group_arn.to_string()),
+                }),
+            }],
+            actual.logging_configuration.unwrap().destinations
+        );
+
+        // Given arn is kept as is
+        let state = StateMachine {
+            log_group: Some(LogGroup::test_default()),
+            ..StateMachine::test_default()
+        };
+        similar_asserts::assert_eq!(state.clone(), state.with_log_group_arn(&arn_builder));
     }
 }
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingConfigurationListItem {
+    pub state_machine_version_arn: String,
+    // percentage of executions routed to the version
+    pub weight: i32,
+}
+
+impl From<aws_sdk_sfn::types::RoutingConfigurationListItem> for RoutingConfigurationListItem {
+    fn from(value: aws_sdk_sfn::types::RoutingConfigurationListItem) -> Self {
+        RoutingConfigurationListItem {
+            state_machine_version_arn: value.state_machine_version_arn().to_string(),
+            weight: value.weight(),
+        }
+    }
+}
+
+impl From<RoutingConfigurationListItem> for aws_sdk_sfn::types::RoutingConfigurationListItem {
+    fn from(value: RoutingConfigurationListItem) -> Self {
+        aws_sdk_sfn::types::RoutingConfigurationListItem::builder()
+            .state_machine_version_arn(value.state_machine_version_arn)
+            .weight(value.weight)
+            .build()
+            .unwrap()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StateMachineAlias {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub routing_configuration: Vec<RoutingConfigurationListItem>,
+}
+
+impl StateMachineAlias {
+    // Alias is not updated by the order of versions
+    pub fn sorted_routing_configuration(&self) -> Vec<RoutingConfigurationListItem> {
+        let mut routing_configuration = self.routing_configuration.clone();
+        routing_configuration.sort_by(|a, b| {
+            a.state_machine_version_arn
+                .cmp(&b.state_machine_version_arn)
+        });
+
+        routing_configuration
+    }
+
+    pub fn is_same_as(&self, other: &StateMachineAlias) -> bool {
+        self.name == other.name
+            && self.description == other.description
+            && self.sorted_routing_configuration() == other.sorted_routing_configuration()
+    }
+}
+
+impl From<aws_sdk_sfn::operation::describe_state_machine_alias::DescribeStateMachineAliasOutput>
+    for StateMachineAlias
+{
+    fn from(
+        value: aws_sdk_sfn::operation::describe_state_machine_alias::DescribeStateMachineAliasOutput,
+    ) -> Self {
+        StateMachineAlias {
+            name: value.name().unwrap_or_default().to_string(),
+            // empty description is same as none, which is how fubura removes it
+            description: value
+                .description()
+                .filter(|d| !d.is_empty())
+                .map(|d| d.to_string()),
+            routing_configuration: value
+                .routing_configuration()
+                .iter()
+                .map(|item| RoutingConfigurationListItem::from(item.clone()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_same_as_ignores_routing_order() {
+        let v1 = RoutingConfigurationListItem {
+            state_machine_version_arn:
+                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld:1".to_string(),
+            weight: 90,
+        };
+        let v2 = RoutingConfigurationListItem {
+            state_machine_version_arn:
+                "arn:aws:states:us-west-2:123456789012:stateMachine:HelloWorld:2".to_string(),
+            weight: 10,
+        };
+        let alias = StateMachineAlias {
+            name: "live".to_string(),
+            description: None,
+            routing_configuration: vec![v1.clone(), v2.clone()],
+        };
+
+        let mut other = alias.clone();
+        other.routing_configuration = vec![v2, v1];
+        assert!(alias.is_same_as(&other));
+
+        other.routing_configuration[0].weight = 20;
+        assert!(!alias.is_same_as(&other));
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+// Published version of state machine, which is immutable once published.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct StateMachineVersion {
+    pub version: u32,
+    pub version_arn: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    // RFC 3339, like "2024-01-01T00:00:00Z"
+    pub creation_date: String,
+}